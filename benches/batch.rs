@@ -0,0 +1,121 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use nalgebra::Vector3;
+use simple_collision::collision3d::*;
+
+/// A small linear congruential generator so the inputs are the same every run.
+fn random(seed: &mut u64) -> f32 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    (*seed >> 40) as f32 / (1u64 << 24) as f32
+}
+
+fn random_vector(seed: &mut u64) -> Vector3<f32> {
+    Vector3::new(random(seed), random(seed), random(seed)) * 100.0
+}
+
+fn spheres(count: usize) -> Vec<Sphere<f32>> {
+    let mut seed = 1;
+    (0..count)
+        .map(|_| Sphere {
+            center: random_vector(&mut seed),
+            radius: random(&mut seed) + 0.5,
+        })
+        .collect()
+}
+
+fn boxes(count: usize) -> Vec<AxisAlignedBoundingBox<f32>> {
+    let mut seed = 2;
+    (0..count)
+        .map(|_| {
+            let start = random_vector(&mut seed);
+            AxisAlignedBoundingBox {
+                start,
+                end: start + Vector3::new(1.0, 2.0, 1.0),
+            }
+        })
+        .collect()
+}
+
+fn sphere_vs_spheres(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sphere_vs_spheres");
+    let agent = Sphere {
+        center: Vector3::new(50.0, 50.0, 50.0),
+        radius: 5.0,
+    };
+    for &count in &[256, 4096] {
+        let others = spheres(count);
+        let batch = SphereBatch::from(&others[..]);
+        let mut out = Vec::with_capacity(count);
+
+        group.bench_with_input(BenchmarkId::new("loop", count), &others, |b, others| {
+            b.iter(|| {
+                out.clear();
+                for (i, other) in others.iter().enumerate() {
+                    if let Some(res) = agent.get_sphere_collision(other) {
+                        out.push((i, res));
+                    }
+                }
+                black_box(out.len())
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("slice", count), &others, |b, others| {
+            b.iter(|| {
+                out.clear();
+                agent.get_sphere_collisions(others, &mut out);
+                black_box(out.len())
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("batch", count), &batch, |b, batch| {
+            b.iter(|| {
+                out.clear();
+                batch.get_sphere_collisions(&agent, &mut out);
+                black_box(out.len())
+            })
+        });
+    }
+    group.finish();
+}
+
+fn aabb_vs_aabbs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("aabb_vs_aabbs");
+    let agent = AxisAlignedBoundingBox {
+        start: Vector3::new(45.0, 45.0, 45.0),
+        end: Vector3::new(55.0, 55.0, 55.0),
+    };
+    for &count in &[256, 4096] {
+        let others = boxes(count);
+        let batch = AABBBatch::from(&others[..]);
+        let mut out = Vec::with_capacity(count);
+
+        group.bench_with_input(BenchmarkId::new("loop", count), &others, |b, others| {
+            b.iter(|| {
+                out.clear();
+                for (i, other) in others.iter().enumerate() {
+                    if agent.get_aabb_collision(other).is_some() {
+                        out.push(i);
+                    }
+                }
+                black_box(out.len())
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("slice", count), &others, |b, others| {
+            b.iter(|| {
+                out.clear();
+                agent.get_aabb_overlaps(others, &mut out);
+                black_box(out.len())
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("batch", count), &batch, |b, batch| {
+            b.iter(|| {
+                out.clear();
+                batch.get_aabb_overlaps(&agent, &mut out);
+                black_box(out.len())
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, sphere_vs_spheres, aabb_vs_aabbs);
+criterion_main!(benches);
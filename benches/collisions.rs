@@ -0,0 +1,408 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use nalgebra::{Vector2, Vector3};
+use simple_collision::collision2d::{self, Circle, Shape2D, Tile, TileMap2D};
+use simple_collision::collision3d::*;
+use simple_collision::{Axis, FloatingPhysicsScalar};
+
+fn n<N: FloatingPhysicsScalar>(value: f64) -> N {
+    N::from_f64(value).unwrap()
+}
+
+fn v3<N: FloatingPhysicsScalar>(x: f64, y: f64, z: f64) -> Vector3<N> {
+    Vector3::new(n(x), n(y), n(z))
+}
+
+fn v2<N: FloatingPhysicsScalar>(x: f64, y: f64) -> Vector2<N> {
+    Vector2::new(n(x), n(y))
+}
+
+/// The shape on the left of every pair, centred on the origin.
+struct First<N: FloatingPhysicsScalar> {
+    sphere: Sphere<N>,
+    aabb: AxisAlignedBoundingBox<N>,
+    capsule: Capsule<N>,
+    cylinder: Cylinder<N>,
+    cone: Cone<N>,
+    triangle: Triangle<N>,
+    ray: Ray<N>,
+    circle: Circle<N>,
+    aabb2d: collision2d::AxisAlignedBoundingBox<N>,
+}
+
+impl<N: FloatingPhysicsScalar> First<N> {
+    fn new() -> Self {
+        First {
+            sphere: Sphere {
+                center: Vector3::zeros(),
+                radius: N::one(),
+            },
+            aabb: AxisAlignedBoundingBox {
+                start: v3(-1.0, -1.0, -1.0),
+                end: v3(1.0, 1.0, 1.0),
+            },
+            capsule: Capsule {
+                start: v3(-0.5, -1.0, 0.0),
+                end: v3(0.5, 1.0, 0.0),
+                radius: n(0.5),
+            },
+            cylinder: Cylinder {
+                center: Vector3::zeros(),
+                half_height: N::one(),
+                radius: N::one(),
+                axis: Axis::Y,
+            },
+            cone: Cone::new(&v3(0.0, 1.0, 0.0), &-Vector3::y(), n(2.0), N::one()),
+            // Stands upright through the floor triangle of `Second`.
+            triangle: Triangle {
+                point1: v3(-1.0, -1.5, 0.0),
+                point2: v3(1.0, -1.5, 0.0),
+                point3: v3(0.0, 1.0, 0.0),
+            },
+            ray: Ray::new(&v3(0.0, 5.0, 0.0), &-Vector3::y()),
+            circle: Circle {
+                center: Vector2::zeros(),
+                radius: N::one(),
+            },
+            aabb2d: collision2d::AxisAlignedBoundingBox {
+                start: v2(-1.0, -1.0),
+                end: v2(1.0, 1.0),
+            },
+        }
+    }
+}
+
+/// The shape on the right of every pair. At no offset each one overlaps every
+/// shape of `First`.
+struct Second<N: FloatingPhysicsScalar> {
+    sphere: Sphere<N>,
+    aabb: AxisAlignedBoundingBox<N>,
+    capsule: Capsule<N>,
+    triangle: Triangle<N>,
+    plane: Plane<N>,
+    ray: Ray<N>,
+    sdf: SmoothUnion<Sphere<N>, AxisAlignedBoundingBox<N>, N>,
+    mesh: TriangleMesh<N>,
+    voxels: VoxelGrid<N>,
+    circle: Circle<N>,
+    aabb2d: collision2d::AxisAlignedBoundingBox<N>,
+    tiles: TileMap2D<N>,
+}
+
+impl<N: FloatingPhysicsScalar> Second<N> {
+    fn at(offset: f64) -> Self {
+        let offset3 = v3(offset, offset, offset);
+        let offset2 = v2(offset, offset);
+        // A floor of 32 triangles facing up, just below the origin.
+        let mesh: TriangleMesh<N> = (0..16)
+            .flat_map(|i| {
+                let (x, z) = ((i % 4) as f64 * 2.0 - 4.0, (i / 4) as f64 * 2.0 - 4.0);
+                let corner = |dx: f64, dz: f64| v3(x + dx, -0.5, z + dz);
+                vec![
+                    Triangle {
+                        point1: corner(0.0, 0.0),
+                        point2: corner(0.0, 2.0),
+                        point3: corner(2.0, 0.0),
+                    },
+                    Triangle {
+                        point1: corner(2.0, 0.0),
+                        point2: corner(0.0, 2.0),
+                        point3: corner(2.0, 2.0),
+                    },
+                ]
+            })
+            .collect();
+        let sphere = Sphere {
+            center: v3(1.5, 0.2, 0.0),
+            radius: N::one(),
+        }
+        .translate(&offset3);
+        let aabb = AxisAlignedBoundingBox {
+            start: v3(0.5, -0.5, -0.5),
+            end: v3(2.0, 1.5, 0.5),
+        }
+        .translate(&offset3);
+        Second {
+            sphere,
+            aabb,
+            capsule: Capsule {
+                start: v3(1.0, 0.0, -1.0),
+                end: v3(1.0, 0.0, 1.0),
+                radius: n(0.5),
+            }
+            .translate(&offset3),
+            triangle: Triangle {
+                point1: v3(-2.0, -0.5, -2.0),
+                point2: v3(0.0, -0.5, 2.0),
+                point3: v3(2.0, -0.5, -2.0),
+            }
+            .translate(&offset3),
+            plane: Plane::from_point(&Vector3::y(), &v3(0.0, -0.5, 0.0)).translate(&offset3),
+            ray: Ray::new(&(v3(-5.0, 0.0, 0.0) + offset3), &Vector3::x()),
+            sdf: SmoothUnion::new(sphere, aabb, n(0.25)),
+            mesh: mesh.translate(&offset3),
+            // A floor of 8x8 voxels with its top just below the origin.
+            voxels: VoxelGrid::from_fn(&v3(-4.0, -1.5, -4.0), N::one(), [8, 1, 8], |_| true)
+                .translate(&offset3),
+            circle: Circle {
+                center: v2(1.5, 0.2),
+                radius: N::one(),
+            }
+            .translate(&offset2),
+            aabb2d: collision2d::AxisAlignedBoundingBox {
+                start: v2(0.5, -0.5),
+                end: v2(2.0, 1.5),
+            }
+            .translate(&offset2),
+            // A floor of 8 tiles with its top just below the origin.
+            tiles: TileMap2D::from_fn(&v2(-4.0, -1.5), N::one(), [8, 1], |_| Tile::Solid)
+                .translate(&offset2),
+        }
+    }
+}
+
+/// Benchmarks one routine against the overlapping and the separated fixture.
+fn bench<N, R, F>(c: &mut Criterion, ty: &str, pair: &str, cases: &[Second<N>; 2], collide: F)
+where
+    N: FloatingPhysicsScalar,
+    F: Fn(&Second<N>) -> Option<R>,
+{
+    let mut group = c.benchmark_group(pair);
+    for (case, second) in ["colliding", "apart"].iter().zip(cases.iter()) {
+        assert_eq!(
+            collide(second).is_some(),
+            *case == "colliding",
+            "{} should be {}",
+            pair,
+            case
+        );
+        group.bench_with_input(BenchmarkId::new(ty, case), second, |b, second| {
+            b.iter(|| black_box(collide(black_box(second))))
+        });
+    }
+    group.finish();
+}
+
+fn collisions<N: FloatingPhysicsScalar>(c: &mut Criterion, ty: &str) {
+    let a = First::<N>::new();
+    let cases = [Second::<N>::at(0.0), Second::<N>::at(10.0)];
+
+    bench(c, ty, "sphere_sphere", &cases, |b| {
+        a.sphere.get_sphere_collision(&b.sphere)
+    });
+    bench(c, ty, "sphere_plane", &cases, |b| {
+        a.sphere
+            .get_plane_collision(&b.plane, FaceCulling::OneSided)
+    });
+    bench(c, ty, "sphere_triangle", &cases, |b| {
+        a.sphere
+            .get_triangle_collision(&b.triangle, FaceCulling::OneSided)
+    });
+    bench(c, ty, "sphere_capsule", &cases, |b| {
+        a.sphere.get_capsule_collision(&b.capsule)
+    });
+    bench(c, ty, "aabb_aabb", &cases, |b| {
+        a.aabb.get_aabb_collision(&b.aabb)
+    });
+    bench(c, ty, "aabb_sphere", &cases, |b| {
+        a.aabb.get_sphere_collision(&b.sphere)
+    });
+    bench(c, ty, "aabb_plane", &cases, |b| {
+        a.aabb.get_plane_collision(&b.plane, FaceCulling::OneSided)
+    });
+    bench(c, ty, "aabb_triangle", &cases, |b| {
+        a.aabb
+            .get_triangle_collision(&b.triangle, FaceCulling::OneSided)
+    });
+    bench(c, ty, "aabb_triangle_overlap", &cases, |b| {
+        a.aabb.intersects_triangle(&b.triangle).then_some(())
+    });
+    bench(c, ty, "aabb_capsule", &cases, |b| {
+        a.aabb.get_capsule_collision(&b.capsule)
+    });
+    bench(c, ty, "capsule_capsule", &cases, |b| {
+        a.capsule.get_capsule_collision(&b.capsule)
+    });
+    bench(c, ty, "capsule_triangle", &cases, |b| {
+        a.capsule
+            .get_triangle_collision(&b.triangle, FaceCulling::OneSided)
+    });
+    bench(c, ty, "capsule_plane", &cases, |b| {
+        a.capsule
+            .get_plane_collision(&b.plane, FaceCulling::OneSided)
+    });
+    bench(c, ty, "capsule_aabb", &cases, |b| {
+        a.capsule.get_aabb_collision(&b.aabb)
+    });
+    bench(c, ty, "capsule_cylinder", &cases, |b| {
+        b.capsule.get_cylinder_collision(&a.cylinder)
+    });
+    bench(c, ty, "sphere_sdf", &cases, |b| {
+        a.sphere.get_sdf_collision(&b.sdf)
+    });
+    bench(c, ty, "capsule_sdf", &cases, |b| {
+        a.capsule.get_sdf_collision(&b.sdf)
+    });
+    bench(c, ty, "cylinder_sphere", &cases, |b| {
+        a.cylinder.get_sphere_collision(&b.sphere)
+    });
+    bench(c, ty, "cylinder_aabb", &cases, |b| {
+        a.cylinder.get_aabb_collision(&b.aabb)
+    });
+    bench(c, ty, "cylinder_capsule", &cases, |b| {
+        a.cylinder.get_capsule_collision(&b.capsule)
+    });
+    bench(c, ty, "cylinder_triangle", &cases, |b| {
+        a.cylinder
+            .get_triangle_collision(&b.triangle, FaceCulling::OneSided)
+    });
+    bench(c, ty, "triangle_triangle", &cases, |b| {
+        a.triangle.get_triangle_collision(&b.triangle)
+    });
+    bench(c, ty, "triangle_segment", &cases, |b| {
+        a.triangle.intersection_segment(&b.triangle)
+    });
+    bench(c, ty, "cone_sphere", &cases, |b| {
+        a.cone.get_sphere_collision(&b.sphere)
+    });
+    bench(c, ty, "cone_aabb", &cases, |b| {
+        a.cone.get_aabb_collision(&b.aabb)
+    });
+    bench(c, ty, "cone_capsule", &cases, |b| {
+        a.cone.get_capsule_collision(&b.capsule)
+    });
+    bench(c, ty, "ray_cone", &cases, |b| {
+        b.ray.intersects_cone(&a.cone)
+    });
+    bench(c, ty, "mesh_sphere", &cases, |b| {
+        b.mesh
+            .get_sphere_collision(&a.sphere, FaceCulling::OneSided)
+    });
+    bench(c, ty, "mesh_capsule", &cases, |b| {
+        b.mesh
+            .get_capsule_collision(&a.capsule, FaceCulling::OneSided)
+    });
+    bench(c, ty, "mesh_aabb", &cases, |b| {
+        b.mesh.get_aabb_collision(&a.aabb, FaceCulling::OneSided)
+    });
+    bench(c, ty, "mesh_cylinder", &cases, |b| {
+        b.mesh
+            .get_cylinder_collision(&a.cylinder, FaceCulling::OneSided)
+    });
+    bench(c, ty, "mesh_triangle", &cases, |b| {
+        b.mesh.get_triangle_collision(&a.triangle)
+    });
+    bench(c, ty, "voxels_sphere", &cases, |b| {
+        b.voxels.get_sphere_collision(&a.sphere)
+    });
+    bench(c, ty, "voxels_capsule", &cases, |b| {
+        b.voxels.get_capsule_collision(&a.capsule)
+    });
+    bench(c, ty, "voxels_aabb", &cases, |b| {
+        b.voxels.get_aabb_collision(&a.aabb)
+    });
+    bench(c, ty, "voxels_ray", &cases, |b| {
+        b.voxels.cast_ray(&a.ray, n(10.0))
+    });
+    bench(c, ty, "circle_circle", &cases, |b| {
+        a.circle.get_circle_collision(&b.circle)
+    });
+    bench(c, ty, "aabb2d_aabb2d", &cases, |b| {
+        a.aabb2d.get_aabb_collision(&b.aabb2d)
+    });
+    bench(c, ty, "aabb2d_circle", &cases, |b| {
+        a.aabb2d.get_circle_collision(&b.circle)
+    });
+    bench(c, ty, "tiles_aabb2d", &cases, |b| {
+        b.tiles.get_aabb_collision(&a.aabb2d)
+    });
+    bench(c, ty, "tiles_circle", &cases, |b| {
+        b.tiles.get_circle_collision(&a.circle)
+    });
+    bench(c, ty, "tiles_ray", &cases, |b| {
+        b.tiles.cast_ray(&v2(0.0, 5.0), &-Vector2::y(), n(10.0))
+    });
+}
+
+fn bounding_volumes<N: FloatingPhysicsScalar>(c: &mut Criterion, ty: &str) {
+    let a = First::<N>::new();
+    let b = Second::<N>::at(0.0);
+
+    let mut group = c.benchmark_group("bounding_aabb");
+    group.bench_function(BenchmarkId::new(ty, "sphere"), |bench| {
+        bench.iter(|| black_box(&a.sphere).bounding_aabb())
+    });
+    group.bench_function(BenchmarkId::new(ty, "aabb"), |bench| {
+        bench.iter(|| black_box(&a.aabb).bounding_aabb())
+    });
+    group.bench_function(BenchmarkId::new(ty, "capsule"), |bench| {
+        bench.iter(|| black_box(&a.capsule).bounding_aabb())
+    });
+    group.bench_function(BenchmarkId::new(ty, "cylinder"), |bench| {
+        bench.iter(|| black_box(&a.cylinder).bounding_aabb())
+    });
+    group.bench_function(BenchmarkId::new(ty, "cone"), |bench| {
+        bench.iter(|| black_box(&a.cone).bounding_aabb())
+    });
+    group.bench_function(BenchmarkId::new(ty, "triangle"), |bench| {
+        bench.iter(|| black_box(&b.triangle).bounding_aabb())
+    });
+    group.bench_function(BenchmarkId::new(ty, "mesh"), |bench| {
+        bench.iter(|| black_box(&b.mesh).bounding_aabb())
+    });
+    group.bench_function(BenchmarkId::new(ty, "circle"), |bench| {
+        bench.iter(|| black_box(&a.circle).bounding_aabb())
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("bounding_sphere");
+    group.bench_function(BenchmarkId::new(ty, "sphere"), |bench| {
+        bench.iter(|| black_box(&a.sphere).bounding_sphere())
+    });
+    group.bench_function(BenchmarkId::new(ty, "aabb"), |bench| {
+        bench.iter(|| black_box(&a.aabb).bounding_sphere())
+    });
+    group.bench_function(BenchmarkId::new(ty, "capsule"), |bench| {
+        bench.iter(|| black_box(&a.capsule).bounding_sphere())
+    });
+    group.bench_function(BenchmarkId::new(ty, "cylinder"), |bench| {
+        bench.iter(|| black_box(&a.cylinder).bounding_sphere())
+    });
+    group.bench_function(BenchmarkId::new(ty, "cone"), |bench| {
+        bench.iter(|| black_box(&a.cone).bounding_sphere())
+    });
+    group.bench_function(BenchmarkId::new(ty, "triangle"), |bench| {
+        bench.iter(|| black_box(&b.triangle).bounding_sphere())
+    });
+    group.bench_function(BenchmarkId::new(ty, "mesh"), |bench| {
+        bench.iter(|| black_box(&b.mesh).bounding_sphere())
+    });
+    group.bench_function(BenchmarkId::new(ty, "aabb2d"), |bench| {
+        bench.iter(|| black_box(&a.aabb2d).bounding_sphere())
+    });
+    group.finish();
+}
+
+fn collisions_f32(c: &mut Criterion) {
+    collisions::<f32>(c, "f32");
+}
+
+fn collisions_f64(c: &mut Criterion) {
+    collisions::<f64>(c, "f64");
+}
+
+fn bounding_volumes_f32(c: &mut Criterion) {
+    bounding_volumes::<f32>(c, "f32");
+}
+
+fn bounding_volumes_f64(c: &mut Criterion) {
+    bounding_volumes::<f64>(c, "f64");
+}
+
+criterion_group!(
+    benches,
+    collisions_f32,
+    collisions_f64,
+    bounding_volumes_f32,
+    bounding_volumes_f64
+);
+criterion_main!(benches);
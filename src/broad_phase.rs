@@ -0,0 +1,114 @@
+//! Pair generation shared by the 2D and 3D collections. Both the sequential and
+//! the parallel versions return pairs sorted by index so the output does not
+//! depend on the number of threads.
+
+use super::*;
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use core::ops::Mul;
+
+/// A collision between two shapes of a collection, with `a < b`. The resolution
+/// pushes shape `a` away from shape `b`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct PairCollision<T: Mul<N> + Copy, N: PhysicsScalar> {
+    pub a: usize,
+    pub b: usize,
+    pub resolution: CollisionResolution<T, N>,
+}
+
+/// Orders shapes by the start of their extent along the sweep axis, breaking
+/// ties by index.
+fn sweep_order<N: PhysicsScalar>(extents: &[(N, N)]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..extents.len()).collect();
+    order.sort_unstable_by(|a, b| n_ordering(extents[*a].0, extents[*b].0).then(a.cmp(b)));
+    order
+}
+
+/// Returns the pairs that start after `order[position]` but before it ends.
+fn sweep_from<N, F>(
+    extents: &[(N, N)],
+    order: &[usize],
+    position: usize,
+    overlaps: &F,
+) -> Vec<(usize, usize)>
+where
+    N: PhysicsScalar,
+    F: Fn(usize, usize) -> bool,
+{
+    let a = order[position];
+    order[position + 1..]
+        .iter()
+        .take_while(|b| extents[**b].0 <= extents[a].1)
+        .filter(|b| overlaps(a, **b))
+        .map(|b| if a < *b { (a, *b) } else { (*b, a) })
+        .collect()
+}
+
+/// Sweep and prune over `extents`, the `(min, max)` of each shape on one axis.
+/// `overlaps` is the full bounds test for pairs that overlap on that axis.
+pub(crate) fn sweep_pairs<N, F>(extents: &[(N, N)], overlaps: F) -> Vec<(usize, usize)>
+where
+    N: PhysicsScalar,
+    F: Fn(usize, usize) -> bool,
+{
+    let order = sweep_order(extents);
+    let mut pairs: Vec<(usize, usize)> = (0..order.len())
+        .flat_map(|position| sweep_from(extents, &order, position, &overlaps))
+        .collect();
+    pairs.sort_unstable();
+    pairs
+}
+
+#[cfg(feature = "rayon")]
+pub(crate) fn par_sweep_pairs<N, F>(extents: &[(N, N)], overlaps: F) -> Vec<(usize, usize)>
+where
+    N: PhysicsScalar + Send + Sync,
+    F: Fn(usize, usize) -> bool + Sync,
+{
+    let order = sweep_order(extents);
+    let mut pairs: Vec<(usize, usize)> = (0..order.len())
+        .into_par_iter()
+        .flat_map_iter(|position| sweep_from(extents, &order, position, &overlaps))
+        .collect();
+    pairs.par_sort_unstable();
+    pairs
+}
+
+pub(crate) fn narrow_phase<T, N, F>(
+    pairs: &[(usize, usize)],
+    collide: F,
+) -> Vec<PairCollision<T, N>>
+where
+    T: Mul<N> + Copy,
+    N: PhysicsScalar,
+    F: Fn(usize, usize) -> Option<CollisionResolution<T, N>>,
+{
+    pairs
+        .iter()
+        .filter_map(|&(a, b)| collide(a, b).map(|resolution| PairCollision { a, b, resolution }))
+        .collect()
+}
+
+/// Like `narrow_phase` but spread over the rayon thread pool. Results keep the
+/// order of `pairs`.
+#[cfg(feature = "rayon")]
+pub(crate) fn par_narrow_phase<T, N, F>(
+    pairs: &[(usize, usize)],
+    collide: F,
+) -> Vec<PairCollision<T, N>>
+where
+    T: Mul<N> + Copy + Send,
+    N: PhysicsScalar + Send,
+    F: Fn(usize, usize) -> Option<CollisionResolution<T, N>> + Sync,
+{
+    pairs
+        .par_iter()
+        .filter_map(|&(a, b)| collide(a, b).map(|resolution| PairCollision { a, b, resolution }))
+        .collect()
+}
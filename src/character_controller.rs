@@ -0,0 +1,205 @@
+//! The move, slide and step logic shared by the 2D and 3D character
+//! controllers, which are `CharacterController` over `Vector2` and `Vector3`.
+
+use super::*;
+
+use collision2d::Shape2D;
+use collision3d::Shape3D;
+
+use core::ops::{Add, Mul, Sub};
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+/// The vector operations a character controller needs.
+pub trait ControllerVector<N>:
+    Copy + Add<Output = Self> + Sub<Output = Self> + Mul<N, Output = Self>
+{
+    fn dot(&self, other: &Self) -> N;
+
+    fn has_nan(&self) -> bool;
+}
+
+impl<N: FloatingPhysicsScalar> ControllerVector<N> for Vector2<N> {
+    fn dot(&self, other: &Self) -> N {
+        Matrix::dot(self, other)
+    }
+    fn has_nan(&self) -> bool {
+        self.iter().any(|v| v.is_nan())
+    }
+}
+
+impl<N: FloatingPhysicsScalar> ControllerVector<N> for Vector3<N> {
+    fn dot(&self, other: &Self) -> N {
+        Matrix::dot(self, other)
+    }
+    fn has_nan(&self) -> bool {
+        self.iter().any(|v| v.is_nan())
+    }
+}
+
+/// A shape a character controller can move.
+pub trait ControllerShape<V>: Copy {
+    fn center(&self) -> V;
+
+    fn translate(&self, point: &V) -> Self;
+}
+
+impl<N: PhysicsScalar, S: Shape2D<N> + Copy> ControllerShape<Vector2<N>> for S {
+    fn center(&self) -> Vector2<N> {
+        Shape2D::center(self)
+    }
+    fn translate(&self, point: &Vector2<N>) -> Self {
+        Shape2D::translate(self, point)
+    }
+}
+
+impl<N: PhysicsScalar, S: Shape3D<N> + Copy> ControllerShape<Vector3<N>> for S {
+    fn center(&self) -> Vector3<N> {
+        Shape3D::center(self)
+    }
+    fn translate(&self, point: &Vector3<N>) -> Self {
+        Shape3D::translate(self, point)
+    }
+}
+
+/// Moves a shape through static geometry, resolving penetration with
+/// `CollisionResolution::total_force` and sliding along anything it hits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct CharacterController<N: PhysicsScalar, V> {
+    /// Unit vector pointing away from the ground.
+    pub up: V,
+    /// Steepest slope, in radians, that counts as ground.
+    pub max_slope: N,
+    /// Tallest ledge the character can step onto.
+    pub step_height: N,
+    /// Distance below the character that is searched for ground.
+    pub ground_probe: N,
+    /// Number of resolution passes per move.
+    pub max_iterations: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct CharacterMove<S, V> {
+    pub shape: S,
+    pub grounded: bool,
+    pub ground_normal: Option<V>,
+    pub hit_wall: bool,
+}
+
+impl<N: FloatingPhysicsScalar, V: ControllerVector<N>> CharacterController<N, V> {
+    pub fn is_walkable(&self, normal: &V) -> bool {
+        normal.dot(&self.up) >= Float::cos(self.max_slope)
+    }
+    pub(crate) fn move_shape<S, C, F>(
+        &self,
+        shape: &S,
+        displacement: &V,
+        colliders: &[C],
+        collide: F,
+    ) -> CharacterMove<S, V>
+    where
+        S: ControllerShape<V>,
+        F: Fn(&S, &C) -> Option<CollisionResolution<V, N>>,
+    {
+        let result = self.slide(shape, displacement, colliders, &collide);
+        if !result.hit_wall || self.step_height <= N::zero() {
+            return result;
+        }
+
+        let vertical = self.up * displacement.dot(&self.up);
+        let horizontal = *displacement - vertical;
+        if is_zero(horizontal.dot(&horizontal)) {
+            return result;
+        }
+
+        let raised = self.slide(shape, &(self.up * self.step_height), colliders, &collide);
+        let moved = self.slide(&raised.shape, &horizontal, colliders, &collide);
+        let lowered = self.slide(
+            &moved.shape,
+            &(vertical - self.up * self.step_height),
+            colliders,
+            &collide,
+        );
+
+        let progress = |s: &S| (s.center() - shape.center()).dot(&horizontal);
+        if lowered.grounded && progress(&lowered.shape) > progress(&result.shape) {
+            CharacterMove {
+                hit_wall: moved.hit_wall,
+                ..lowered
+            }
+        } else {
+            result
+        }
+    }
+    fn slide<S, C, F>(
+        &self,
+        shape: &S,
+        displacement: &V,
+        colliders: &[C],
+        collide: &F,
+    ) -> CharacterMove<S, V>
+    where
+        S: ControllerShape<V>,
+        F: Fn(&S, &C) -> Option<CollisionResolution<V, N>>,
+    {
+        let mut result = CharacterMove {
+            shape: shape.translate(displacement),
+            grounded: false,
+            ground_normal: None,
+            hit_wall: false,
+        };
+
+        for _ in 0..self.max_iterations {
+            let mut resolved = true;
+            for collider in colliders {
+                let res = match collide(&result.shape, collider) {
+                    Some(res) if res.penetration > N::zero() && !res.normal.has_nan() => res,
+                    _ => continue,
+                };
+                resolved = false;
+                let push = self.push(&res, &mut result);
+                result.shape = result.shape.translate(&push);
+            }
+            if resolved {
+                break;
+            }
+        }
+
+        if !result.grounded {
+            let probe = result.shape.translate(&(self.up * -self.ground_probe));
+            result.ground_normal = colliders
+                .iter()
+                .filter_map(|collider| collide(&probe, collider))
+                .map(|res| res.normal)
+                .find(|normal| self.is_walkable(normal));
+            result.grounded = result.ground_normal.is_some();
+        }
+        result
+    }
+    fn push<S>(&self, res: &CollisionResolution<V, N>, result: &mut CharacterMove<S, V>) -> V {
+        let up = res.normal.dot(&self.up);
+        if self.is_walkable(&res.normal) {
+            result.grounded = true;
+            result.ground_normal = Some(res.normal);
+            // Push straight up so standing on a slope does not slide down it,
+            // unless the surface is too close to vertical to divide by.
+            if up > Float::sqrt(N::epsilon()) {
+                return self.up * (res.penetration / up);
+            }
+            return res.total_force();
+        }
+
+        let horizontal = res.normal - self.up * up;
+        let length = Float::sqrt(horizontal.dot(&horizontal));
+        if up < N::zero() || is_zero(length) {
+            res.total_force()
+        } else {
+            // Steep surfaces only push sideways so they cannot be climbed.
+            result.hit_wall = true;
+            horizontal * (res.penetration / (length * length))
+        }
+    }
+}
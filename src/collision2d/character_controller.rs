@@ -0,0 +1,91 @@
+use super::*;
+
+/// Moves a shape through static 2D geometry; see
+/// `character_controller::CharacterController`.
+pub type CharacterController<N> = crate::character_controller::CharacterController<N, Vector2<N>>;
+
+pub type CharacterMove<S, N> = crate::character_controller::CharacterMove<S, Vector2<N>>;
+
+impl<N: FloatingPhysicsScalar> CharacterController<N> {
+    pub fn move_aabb(
+        &self,
+        aabb: &AxisAlignedBoundingBox<N>,
+        displacement: &Vector2<N>,
+        colliders: &[Collider2D<N>],
+    ) -> CharacterMove<AxisAlignedBoundingBox<N>, N> {
+        self.move_shape(aabb, displacement, colliders, |aabb, collider| {
+            collider.get_aabb_collision(aabb).map(|r| r.flip())
+        })
+    }
+    pub fn move_circle(
+        &self,
+        circle: &Circle<N>,
+        displacement: &Vector2<N>,
+        colliders: &[Collider2D<N>],
+    ) -> CharacterMove<Circle<N>, N> {
+        self.move_shape(circle, displacement, colliders, |circle, collider| {
+            collider.get_circle_collision(circle).map(|r| r.flip())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn controller() -> CharacterController<f32> {
+        CharacterController {
+            up: Vector2::y(),
+            max_slope: core::f32::consts::FRAC_PI_4,
+            step_height: 0.5,
+            ground_probe: 0.05,
+            max_iterations: 4,
+        }
+    }
+
+    fn body() -> AABB2D<f32> {
+        AABB2D {
+            start: Vector2::new(-0.5, 0.0),
+            end: Vector2::new(0.5, 2.0),
+        }
+    }
+
+    fn floor() -> Collider2D<f32> {
+        Collider2D::AABB(AABB2D {
+            start: Vector2::new(-10.0, -1.0),
+            end: Vector2::new(10.0, 0.0),
+        })
+    }
+
+    #[test]
+    fn falls_onto_ground_test() {
+        let result = controller().move_aabb(&body(), &Vector2::new(0.0, -0.25), &[floor()]);
+        assert!(result.grounded);
+        assert!(!result.hit_wall);
+        assert!(result.shape.start.y.abs() < 1e-4);
+    }
+
+    #[test]
+    fn blocked_by_wall_test() {
+        let wall = Collider2D::AABB(AABB2D {
+            start: Vector2::new(0.75, 0.0),
+            end: Vector2::new(5.0, 10.0),
+        });
+        let result = controller().move_aabb(&body(), &Vector2::new(1.0, 0.0), &[floor(), wall]);
+        assert!(result.hit_wall);
+        assert!(result.grounded);
+        assert!((result.shape.end.x - 0.75).abs() < 1e-4);
+    }
+
+    #[test]
+    fn steps_up_ledge_test() {
+        let ledge = Collider2D::AABB(AABB2D {
+            start: Vector2::new(0.75, 0.0),
+            end: Vector2::new(5.0, 0.3),
+        });
+        let result = controller().move_aabb(&body(), &Vector2::new(1.0, 0.0), &[floor(), ledge]);
+        assert!(result.grounded);
+        assert!((result.shape.start.x - 0.5).abs() < 1e-4);
+        assert!((result.shape.start.y - 0.3).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,110 @@
+use super::*;
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum Collider2D<N: PhysicsScalar> {
+    AABB(AxisAlignedBoundingBox<N>),
+    Circle(Circle<N>),
+}
+
+impl<N: FloatingPhysicsScalar> Collider2D<N> {
+    /// Returns a resolution that pushes `self` away from `other`.
+    pub fn get_collision(
+        &self,
+        other: &Collider2D<N>,
+    ) -> Option<CollisionResolution<Vector2<N>, N>> {
+        match other {
+            Collider2D::AABB(b) => self.get_aabb_collision(b),
+            Collider2D::Circle(b) => self.get_circle_collision(b),
+        }
+    }
+    pub fn get_aabb_collision(
+        &self,
+        aabb: &AxisAlignedBoundingBox<N>,
+    ) -> Option<CollisionResolution<Vector2<N>, N>> {
+        match self {
+            Collider2D::AABB(a) => a.get_aabb_collision(aabb),
+            Collider2D::Circle(a) => aabb.get_circle_collision(a).map(|r| r.flip()),
+        }
+    }
+    pub fn get_circle_collision(
+        &self,
+        circle: &Circle<N>,
+    ) -> Option<CollisionResolution<Vector2<N>, N>> {
+        match self {
+            Collider2D::AABB(a) => a.get_circle_collision(circle),
+            Collider2D::Circle(a) => a.get_circle_collision(circle),
+        }
+    }
+    pub fn contains_point(&self, point: &Vector2<N>) -> bool {
+        match self {
+            Collider2D::AABB(a) => a.contains_point(point),
+            Collider2D::Circle(a) => a.contains_point(point),
+        }
+    }
+    pub fn project_point(&self, point: &Vector2<N>) -> PointProjection<Vector2<N>> {
+        match self {
+            Collider2D::AABB(a) => a.project_point(point),
+            Collider2D::Circle(a) => a.project_point(point),
+        }
+    }
+}
+
+impl<N: FloatingPhysicsScalar> MassProperties<N> for Collider2D<N> {
+    type Vector = Vector2<N>;
+    type Inertia = N;
+
+    fn mass_properties(&self, density: N) -> MassData<Vector2<N>, N, N> {
+        match self {
+            Collider2D::AABB(a) => a.mass_properties(density),
+            Collider2D::Circle(a) => a.mass_properties(density),
+        }
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Shape2D<N> for Collider2D<N> {
+    fn bounding_aabb(&self) -> AxisAlignedBoundingBox<N> {
+        match self {
+            Collider2D::AABB(a) => a.bounding_aabb(),
+            Collider2D::Circle(a) => a.bounding_aabb(),
+        }
+    }
+    fn bounding_sphere(&self) -> Circle<N> {
+        match self {
+            Collider2D::AABB(a) => a.bounding_sphere(),
+            Collider2D::Circle(a) => a.bounding_sphere(),
+        }
+    }
+    fn center(&self) -> Vector2<N> {
+        match self {
+            Collider2D::AABB(a) => a.center(),
+            Collider2D::Circle(a) => a.center(),
+        }
+    }
+    fn translate(&self, point: &Vector2<N>) -> Self {
+        let mut c = *self;
+        c.translate_mut(point);
+        c
+    }
+    fn set_center(&self, point: &Vector2<N>) -> Self {
+        let mut c = *self;
+        c.set_center_mut(point);
+        c
+    }
+    fn translate_mut(&mut self, point: &Vector2<N>) {
+        match self {
+            Collider2D::AABB(a) => a.translate_mut(point),
+            Collider2D::Circle(a) => a.translate_mut(point),
+        }
+    }
+    fn set_center_mut(&mut self, point: &Vector2<N>) {
+        match self {
+            Collider2D::AABB(a) => a.set_center_mut(point),
+            Collider2D::Circle(a) => a.set_center_mut(point),
+        }
+    }
+}
@@ -3,6 +3,22 @@ use super::*;
 pub mod shapes;
 pub use shapes::*;
 
+pub mod collider;
+pub use collider::*;
+
+pub mod character_controller;
+pub use character_controller::*;
+
+#[cfg(feature = "alloc")]
+pub mod pairs;
+#[cfg(feature = "alloc")]
+pub use pairs::*;
+
+#[cfg(feature = "alloc")]
+pub mod quadtree;
+#[cfg(feature = "alloc")]
+pub use quadtree::*;
+
 pub trait Shape2D<N : PhysicsScalar> {
     fn bounding_aabb(&self) -> AxisAlignedBoundingBox<N>;
 
@@ -0,0 +1,91 @@
+use super::*;
+use crate::broad_phase::*;
+
+/// Pairs whose bounding boxes touch, as `(a, b)` with `a < b`, in ascending order.
+pub fn find_pairs<N: FloatingPhysicsScalar>(shapes: &[Collider2D<N>]) -> Vec<(usize, usize)> {
+    let bounds: Vec<_> = shapes.iter().map(|s| s.bounding_aabb().min_max()).collect();
+    sweep_pairs(&extents(&bounds), |a, b| touches(&bounds[a], &bounds[b]))
+}
+
+/// Runs `get_collision` on every pair from `find_pairs`. The resolution of each
+/// result pushes shape `a` away from shape `b`.
+pub fn find_collisions<N: FloatingPhysicsScalar>(
+    shapes: &[Collider2D<N>],
+) -> Vec<PairCollision<Vector2<N>, N>> {
+    narrow_phase(&find_pairs(shapes), |a, b| {
+        shapes[a].get_collision(&shapes[b])
+    })
+}
+
+/// The parallel version of `find_pairs`. The result is identical whatever the
+/// number of threads.
+#[cfg(feature = "rayon")]
+pub fn par_find_pairs<N>(shapes: &[Collider2D<N>]) -> Vec<(usize, usize)>
+where
+    N: FloatingPhysicsScalar + Send + Sync,
+{
+    use rayon::prelude::*;
+
+    let bounds: Vec<_> = shapes
+        .par_iter()
+        .map(|s| s.bounding_aabb().min_max())
+        .collect();
+    par_sweep_pairs(&extents(&bounds), |a, b| touches(&bounds[a], &bounds[b]))
+}
+
+/// The parallel version of `find_collisions`. The result is identical whatever
+/// the number of threads.
+#[cfg(feature = "rayon")]
+pub fn par_find_collisions<N>(shapes: &[Collider2D<N>]) -> Vec<PairCollision<Vector2<N>, N>>
+where
+    N: FloatingPhysicsScalar + Send + Sync,
+{
+    par_narrow_phase(&par_find_pairs(shapes), |a, b| {
+        shapes[a].get_collision(&shapes[b])
+    })
+}
+
+fn extents<N: FloatingPhysicsScalar>(bounds: &[(Vector2<N>, Vector2<N>)]) -> Vec<(N, N)> {
+    bounds.iter().map(|(min, max)| (min.x, max.x)).collect()
+}
+
+fn touches<N: FloatingPhysicsScalar>(
+    a: &(Vector2<N>, Vector2<N>),
+    b: &(Vector2<N>, Vector2<N>),
+) -> bool {
+    (0..2).all(|i| a.0[i] <= b.1[i] && b.0[i] <= a.1[i])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_collisions_test() {
+        let shapes = vec![
+            Collider2D::Circle(Circle {
+                center: Vector2::new(2.0, 0.0),
+                radius: 1.0,
+            }),
+            Collider2D::AABB(AxisAlignedBoundingBox {
+                start: Vector2::new(0.0, -0.5),
+                end: Vector2::new(1.5, 0.5f32),
+            }),
+            Collider2D::Circle(Circle {
+                center: Vector2::new(10.0, 0.0),
+                radius: 1.0,
+            }),
+            Collider2D::Circle(Circle {
+                center: Vector2::new(-0.5, 0.0),
+                radius: 0.75,
+            }),
+        ];
+        assert_eq!(find_pairs(&shapes), vec![(0, 1), (1, 3)]);
+
+        let collisions = find_collisions(&shapes);
+        assert_eq!(collisions.len(), 2);
+        assert_eq!((collisions[0].a, collisions[0].b), (0, 1));
+        assert_eq!(collisions[0].resolution.normal, Vector2::x());
+        assert_eq!(collisions[1].resolution.normal, Vector2::x());
+    }
+}
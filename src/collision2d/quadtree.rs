@@ -0,0 +1,186 @@
+use super::*;
+use crate::spatial_tree::*;
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+/// The 2D counterpart of `Octree`, splitting space into quarters around the
+/// items' bounding boxes.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct Quadtree<N: PhysicsScalar, T> {
+    tree: Tree<AxisAlignedBoundingBox<N>, T>,
+}
+
+impl<N: FloatingPhysicsScalar, T: Shape2D<N>> Quadtree<N, T> {
+    /// An empty tree covering `bounds`. A node splits once it holds more than
+    /// `leaf_capacity` items, up to `max_depth` levels below the root.
+    pub fn new(bounds: &AxisAlignedBoundingBox<N>, max_depth: usize, leaf_capacity: usize) -> Self {
+        Quadtree {
+            tree: Tree::new(*bounds, max_depth, leaf_capacity),
+        }
+    }
+    /// A tree fitted around all of `items`.
+    pub fn from_items<I: IntoIterator<Item = T>>(
+        items: I,
+        max_depth: usize,
+        leaf_capacity: usize,
+    ) -> Self {
+        Quadtree {
+            tree: Tree::build(items, max_depth, leaf_capacity, T::bounding_aabb),
+        }
+    }
+    /// Adds an item and returns its index in `items`. Items outside the tree's
+    /// bounds are still found, but are tested by every query.
+    pub fn insert(&mut self, item: T) -> usize {
+        let bounds = item.bounding_aabb();
+        self.tree.insert(item, bounds)
+    }
+    /// The items in the order they were added.
+    pub fn items(&self) -> &[T] {
+        self.tree.items()
+    }
+    pub fn bounds(&self) -> &AxisAlignedBoundingBox<N> {
+        self.tree.bounds()
+    }
+    fn collect(&self, indices: Vec<usize>) -> Vec<&T> {
+        indices.into_iter().map(|i| &self.items()[i]).collect()
+    }
+    /// The items whose boxes hold `point`.
+    pub fn query_point(&self, point: &Vector2<N>) -> Vec<&T> {
+        self.collect(self.tree.query(|b| b.contains_point(point)))
+    }
+    /// The items whose boxes overlap `aabb`.
+    pub fn query_aabb(&self, aabb: &AxisAlignedBoundingBox<N>) -> Vec<&T> {
+        self.collect(self.tree.query(|b| b.intersection(aabb).is_some()))
+    }
+    /// The items whose boxes overlap `circle`.
+    pub fn query_circle(&self, circle: &Circle<N>) -> Vec<&T> {
+        let r2 = circle.radius * circle.radius;
+        self.collect(
+            self.tree.query(|b| {
+                (b.closest_point(&circle.center) - circle.center).magnitude_squared() <= r2
+            }),
+        )
+    }
+    /// The items whose boxes a ray from `point` along `direction` enters within
+    /// `max_distance`, nearest first, with the distance to each box.
+    pub fn query_ray(
+        &self,
+        point: &Vector2<N>,
+        direction: &Vector2<N>,
+        max_distance: N,
+    ) -> Vec<(&T, N)> {
+        let entry = |b: &AxisAlignedBoundingBox<N>| {
+            let (min, max) = b.min_max();
+            slab_entry(
+                min.as_slice(),
+                max.as_slice(),
+                point.as_slice(),
+                direction.as_slice(),
+                max_distance,
+            )
+        };
+        let mut hits: Vec<_> = self
+            .tree
+            .query(|b| entry(b).is_some())
+            .into_iter()
+            .map(|i| {
+                let item = &self.items()[i];
+                (item, entry(&item.bounding_aabb()).unwrap())
+            })
+            .collect();
+        hits.sort_by(|a, b| n_ordering(a.1, b.1));
+        hits
+    }
+    /// The item whose box is closest to `point`, with the distance to it.
+    pub fn nearest(&self, point: &Vector2<N>) -> Option<(&T, N)> {
+        self.nearest_by(point, |item| {
+            (item.bounding_aabb().closest_point(point) - point).magnitude()
+        })
+    }
+    /// The item closest to `point` by `distance`, which must never be less
+    /// than the distance to the item's box.
+    pub fn nearest_by<F: Fn(&T) -> N>(&self, point: &Vector2<N>, distance: F) -> Option<(&T, N)> {
+        self.tree
+            .nearest(point, |i| distance(&self.items()[i]))
+            .map(|(i, d)| (&self.items()[i], d))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 6x6 grid of boxes half a unit wide, one per unit.
+    fn boxes() -> Quadtree<f32, AABB2D<f32>> {
+        Quadtree::from_items(
+            (0..36).map(|i| {
+                let start = Vector2::new((i % 6) as f32, (i / 6) as f32);
+                AABB2D {
+                    start,
+                    end: start + Vector2::new(0.5f32, 0.5f32),
+                }
+            }),
+            3,
+            1,
+        )
+    }
+
+    fn starts(found: Vec<&AABB2D<f32>>) -> Vec<(f32, f32)> {
+        found.into_iter().map(|b| (b.start.x, b.start.y)).collect()
+    }
+
+    #[test]
+    fn query_test() {
+        let tree = boxes();
+        assert_eq!(
+            starts(tree.query_point(&Vector2::new(2.25f32, 3.25f32))),
+            vec![(2f32, 3f32)]
+        );
+        assert!(tree.query_point(&Vector2::new(2.75f32, 3.25f32)).is_empty());
+        let aabb = AABB2D {
+            start: Vector2::new(0.75f32, 4.25f32),
+            end: Vector2::new(2.25f32, 5.75f32),
+        };
+        assert_eq!(
+            starts(tree.query_aabb(&aabb)),
+            vec![(1f32, 4f32), (2f32, 4f32), (1f32, 5f32), (2f32, 5f32)]
+        );
+        let circle = Circle {
+            center: Vector2::new(3.75f32, 0.75f32),
+            radius: 0.4f32,
+        };
+        assert_eq!(
+            starts(tree.query_circle(&circle)),
+            vec![(3f32, 0f32), (4f32, 0f32), (3f32, 1f32), (4f32, 1f32)]
+        );
+
+        let hits: Vec<_> = tree
+            .query_ray(&Vector2::new(0.25f32, 2.25f32), &Vector2::x(), 3f32)
+            .into_iter()
+            .map(|(b, d)| (b.start.x, d))
+            .collect();
+        assert_eq!(
+            hits,
+            vec![
+                (0f32, 0f32),
+                (1f32, 0.75f32),
+                (2f32, 1.75f32),
+                (3f32, 2.75f32)
+            ]
+        );
+    }
+    #[test]
+    fn nearest_test() {
+        let tree = boxes();
+        let (nearest, distance) = tree.nearest(&Vector2::new(7f32, 2.25f32)).unwrap();
+        assert_eq!(
+            ((nearest.start.x, nearest.start.y), distance),
+            ((5f32, 2f32), 1.5f32)
+        );
+        assert!(Quadtree::<f32, AABB2D<f32>>::from_items(None, 3, 1)
+            .nearest(&Vector2::zeros())
+            .is_none());
+    }
+}
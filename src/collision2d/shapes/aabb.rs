@@ -1,10 +1,10 @@
 use super::*;
 use nalgebra::*;
 
-use std::cmp::*;
-use std::iter::FromIterator;
-use std::iter::Sum;
-use std::ops::*;
+use ::core::cmp::*;
+use ::core::iter::FromIterator;
+use ::core::iter::Sum;
+use ::core::ops::*;
 
 #[cfg(feature = "serde-serialize")]
 use serde::{Deserialize, Serialize};
@@ -40,10 +40,10 @@ impl<'a, N: FloatingPhysicsScalar> FromIterator<&'a Vector2<N>> for AxisAlignedB
         let mut end = Vector2::from_element(Bounded::min_value());
         for v in iter {
             for (s, point) in start.iter_mut().zip(v.iter()) {
-                *s = num_traits::clamp_min(*point, *s);
+                *s = n_min(*point, *s);
             }
             for (s, point) in end.iter_mut().zip(v.iter()) {
-                *s = num_traits::clamp_max(*point, *s);
+                *s = n_max(*point, *s);
             }
         }
         AxisAlignedBoundingBox { start, end }
@@ -54,6 +54,12 @@ impl<N: FloatingPhysicsScalar> Add for AxisAlignedBoundingBox<N> {
     type Output = AxisAlignedBoundingBox<N>;
 
     fn add(self, rhs: AxisAlignedBoundingBox<N>) -> Self::Output {
+        if self.is_empty() {
+            return rhs;
+        }
+        if rhs.is_empty() {
+            return self;
+        }
         let arr = [self.start, self.end, rhs.start, rhs.end];
         AxisAlignedBoundingBox {
             start: Vector2::new(n_min_iter(arr.iter(), 0), n_min_iter(arr.iter(), 1)),
@@ -71,6 +77,9 @@ impl<N: FloatingPhysicsScalar> AddAssign for AxisAlignedBoundingBox<N> {
 impl<N: FloatingPhysicsScalar> Mul<N> for AxisAlignedBoundingBox<N> {
     type Output = Self;
     fn mul(self, rhs: N) -> Self::Output {
+        if self.is_empty() {
+            return self;
+        }
         AxisAlignedBoundingBox {
             start: self.start * rhs,
             end: self.end * rhs,
@@ -80,16 +89,28 @@ impl<N: FloatingPhysicsScalar> Mul<N> for AxisAlignedBoundingBox<N> {
 
 impl<N: FloatingPhysicsScalar> MulAssign<N> for AxisAlignedBoundingBox<N> {
     fn mul_assign(&mut self, rhs: N) {
+        if self.is_empty() {
+            return;
+        }
         self.start *= rhs;
         self.end *= rhs;
     }
 }
 
 impl<N: FloatingPhysicsScalar> AxisAlignedBoundingBox<N> {
+    pub fn area(&self) -> N {
+        self.width() * self.height()
+    }
     pub fn width(&self) -> N {
+        if self.is_empty() {
+            return N::zero();
+        }
         Float::abs(self.start.x - self.end.x)
     }
     pub fn height(&self) -> N {
+        if self.is_empty() {
+            return N::zero();
+        }
         Float::abs(self.start.y - self.end.y)
     }
     pub fn half_width(&self) -> N {
@@ -98,7 +119,12 @@ impl<N: FloatingPhysicsScalar> AxisAlignedBoundingBox<N> {
     pub fn half_height(&self) -> N {
         self.height() * N::from_f64(0.5f64).unwrap()
     }
+    /// The lowest and highest corners. An empty box reports its center for
+    /// both, which `closest_point` then returns for every point.
     pub fn min_max(&self) -> (Vector2<N>, Vector2<N>) {
+        if self.is_empty() {
+            return (self.center(), self.center());
+        }
         let min_vec = Vector2::new(
             n_min(self.start.x, self.end.x),
             n_min(self.start.y, self.end.y),
@@ -116,6 +142,98 @@ impl<N: FloatingPhysicsScalar> AxisAlignedBoundingBox<N> {
             clamp(point.y, min_vec.y, max_vec.y),
         )
     }
+    pub fn contains_point(&self, point: &Vector2<N>) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        let (min_vec, max_vec) = self.min_max();
+        min_vec.iter().zip(point.iter()).all(|(a, p)| a <= p)
+            && max_vec.iter().zip(point.iter()).all(|(a, p)| p <= a)
+    }
+    pub fn project_point(&self, point: &Vector2<N>) -> PointProjection<Vector2<N>> {
+        if !self.contains_point(point) {
+            return PointProjection {
+                point: self.closest_point(point),
+                is_inside: false,
+            };
+        }
+
+        let (min_vec, max_vec) = self.min_max();
+        let to_min = point - min_vec;
+        let to_max = max_vec - point;
+        let (min_index, &min_dist) = min_component(&to_min);
+        let (max_index, &max_dist) = min_component(&to_max);
+        let mut projected = *point;
+        if min_dist < max_dist {
+            projected[min_index] = min_vec[min_index];
+        } else {
+            projected[max_index] = max_vec[max_index];
+        }
+        PointProjection {
+            point: projected,
+            is_inside: true,
+        }
+    }
+    /// A box containing nothing. It is the identity for `Add` and `Sum`, has
+    /// no extent, and stays empty when moved or expanded.
+    pub fn empty() -> Self {
+        AxisAlignedBoundingBox {
+            start: Vector2::from_element(Bounded::max_value()),
+            end: Vector2::from_element(Bounded::min_value()),
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        *self == Self::empty()
+    }
+    pub fn perimeter(&self) -> N {
+        (self.width() + self.height()) * N::from_f64(2.0).unwrap()
+    }
+    pub fn intersection(&self, aabb: &AxisAlignedBoundingBox<N>) -> Option<Self> {
+        if self.is_empty() || aabb.is_empty() {
+            return None;
+        }
+        let (min1, max1) = self.min_max();
+        let (min2, max2) = aabb.min_max();
+        let start = min1.zip_map(&min2, n_max);
+        let end = max1.zip_map(&max2, n_min);
+        if start.iter().zip(end.iter()).all(|(s, e)| s <= e) {
+            Some(AxisAlignedBoundingBox { start, end })
+        } else {
+            None
+        }
+    }
+    pub fn contains_aabb(&self, aabb: &AxisAlignedBoundingBox<N>) -> bool {
+        if aabb.is_empty() {
+            return true;
+        }
+        let (min_vec, max_vec) = aabb.min_max();
+        self.contains_point(&min_vec) && self.contains_point(&max_vec)
+    }
+    #[must_use]
+    pub fn expand_by(&self, margin: N) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
+        let (min_vec, max_vec) = self.min_max();
+        let margin = Vector2::from_element(margin);
+        AxisAlignedBoundingBox {
+            start: min_vec - margin,
+            end: max_vec + margin,
+        }
+    }
+    #[must_use]
+    pub fn expand_to_point(&self, point: &Vector2<N>) -> Self {
+        *self
+            + AxisAlignedBoundingBox {
+                start: *point,
+                end: *point,
+            }
+    }
+    /// The box swept over a displacement of `velocity`.
+    #[must_use]
+    pub fn swept(&self, velocity: &Vector2<N>) -> Self {
+        *self + self.translate(velocity)
+    }
     pub fn largest_dim(&self) -> (usize, N) {
         [self.width(), self.height()]
             .iter()
@@ -136,6 +254,9 @@ impl<N: FloatingPhysicsScalar> AxisAlignedBoundingBox<N> {
         &self,
         aabb: &AxisAlignedBoundingBox<N>,
     ) -> Option<CollisionResolution<Vector2<N>, N>> {
+        if self.is_empty() || aabb.is_empty() {
+            return None;
+        }
         let n = self.center() - aabb.center();
         let overlap = Vector2::new(
             self.half_width() + aabb.half_width() - Float::abs(n.x),
@@ -179,90 +300,102 @@ impl<N: FloatingPhysicsScalar> AxisAlignedBoundingBox<N> {
         &self,
         circle: &Circle<N>,
     ) -> Option<CollisionResolution<Vector2<N>, N>> {
-        let n = self.center() - circle.center();
-        let extent = Vector2::new(self.half_width(), self.half_height());
-        let mut closest = Vector2::new(
-            clamp(n.x, -extent.x, extent.x),
-            clamp(n.y, -extent.y, extent.y),
-        );
-        let inside = {
-            if n == closest {
-                let (index, _) = min_component(&n.abs());
-                match index {
-                    0 => {
-                        closest.x = extent.x
-                            * if closest.x > N::zero() {
-                                N::one()
-                            } else {
-                                -N::one()
-                            };
-                    }
-                    1 => {
-                        closest.y = extent.y
-                            * if closest.y > N::zero() {
-                                N::one()
-                            } else {
-                                -N::one()
-                            };
-                    }
-                    _ => (),
-                }
-                true
-            } else {
-                false
-            }
-        };
-
-        let normal = n - closest;
-        let d = normal.magnitude_squared();
+        if self.is_empty() {
+            return None;
+        }
         let r = circle.radius;
+        let closest = self.closest_point(&circle.center);
+        let n = closest - circle.center;
+        let d = n.magnitude_squared();
 
-        if d > r.pow(N::from_usize(2).unwrap()) && !inside {
-            return None;
+        if !is_zero(d) {
+            if d > r.pow(N::from_usize(2).unwrap()) {
+                return None;
+            }
+            let d = Float::sqrt(d);
+            return Some(CollisionResolution {
+                normal: n / d,
+                penetration: r - d,
+            });
         }
 
-        let d = Float::sqrt(d);
+        // The circle's center is inside the box so push out through the nearest face.
+        let (min_vec, max_vec) = self.min_max();
+        let to_min = circle.center - min_vec;
+        let to_max = max_vec - circle.center;
+        let (min_index, &min_dist) = min_component(&to_min);
+        let (max_index, &max_dist) = min_component(&to_max);
+        let mut normal = Vector2::zeros();
+        let dist = if min_dist < max_dist {
+            normal[min_index] = N::one();
+            min_dist
+        } else {
+            normal[max_index] = -N::one();
+            max_dist
+        };
         Some(CollisionResolution {
-            normal: n.normalize(),
-            penetration: r - d,
+            normal,
+            penetration: r + dist,
         })
     }
 }
 
+impl<N: FloatingPhysicsScalar> MassProperties<N> for AxisAlignedBoundingBox<N> {
+    type Vector = Vector2<N>;
+    type Inertia = N;
+
+    fn mass_properties(&self, density: N) -> MassData<Vector2<N>, N, N> {
+        let mass = self.area() * density;
+        let (w, h) = (self.width(), self.height());
+        MassData {
+            mass,
+            center_of_mass: self.center(),
+            inertia: mass * (w * w + h * h) / N::from_f64(12.0).unwrap(),
+        }
+    }
+}
+
 impl<N: FloatingPhysicsScalar> Shape2D<N> for AxisAlignedBoundingBox<N> {
     fn bounding_aabb(&self) -> AxisAlignedBoundingBox<N> {
         *self
     }
     fn bounding_sphere(&self) -> Circle<N> {
+        // The corners are furthest from the centre.
+        let (min_vec, max_vec) = self.min_max();
         Circle {
             center: self.center(),
-            radius: [self.width(), self.height()]
-                .iter()
-                .max_by(|a, b| match a.partial_cmp(b) {
-                    Some(o) => o,
-                    None => Ordering::Equal,
-                })
-                .cloned()
-                .unwrap(),
+            radius: (max_vec - min_vec).magnitude() * N::from_f64(0.5).unwrap(),
         }
     }
     fn center(&self) -> Vector2<N> {
+        if self.is_empty() {
+            return Vector2::zeros();
+        }
         Vector2::new(
             (self.start.x + self.end.x) * N::from_f64(0.5f64).unwrap(),
             (self.start.y + self.end.y) * N::from_f64(0.5f64).unwrap(),
         )
     }
     fn translate(&self, point: &Vector2<N>) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
         AxisAlignedBoundingBox {
             start: self.start + point,
             end: self.end + point,
         }
     }
     fn translate_mut(&mut self, point: &Vector2<N>) {
+        if self.is_empty() {
+            return;
+        }
         self.start += point;
         self.end += point;
     }
     fn set_center(&self, point: &Vector2<N>) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
         let offset = Vector2::new(self.half_width(), self.half_height());
         AxisAlignedBoundingBox {
             start: point - offset,
@@ -270,6 +403,9 @@ impl<N: FloatingPhysicsScalar> Shape2D<N> for AxisAlignedBoundingBox<N> {
         }
     }
     fn set_center_mut(&mut self, point: &Vector2<N>) {
+        if self.is_empty() {
+            return;
+        }
         let offset = Vector2::new(self.half_width(), self.half_height());
         self.start = point - offset;
         self.end = point + offset;
@@ -278,10 +414,7 @@ impl<N: FloatingPhysicsScalar> Shape2D<N> for AxisAlignedBoundingBox<N> {
 
 impl<N: FloatingPhysicsScalar> Sum for AxisAlignedBoundingBox<N> {
     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
-        let mut aabb = AABB2D {
-            start: Vector2::<N>::zeros(),
-            end: Vector2::<N>::zeros(),
-        };
+        let mut aabb = AxisAlignedBoundingBox::empty();
         for a in iter.into_iter() {
             aabb += a;
         }
@@ -291,10 +424,7 @@ impl<N: FloatingPhysicsScalar> Sum for AxisAlignedBoundingBox<N> {
 
 impl<'a, N: FloatingPhysicsScalar> Sum<&'a Self> for AxisAlignedBoundingBox<N> {
     fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
-        let mut aabb = AABB2D {
-            start: Vector2::<N>::zeros(),
-            end: Vector2::<N>::zeros(),
-        };
+        let mut aabb = AxisAlignedBoundingBox::empty();
         for a in iter.into_iter() {
             aabb += *a;
         }
@@ -321,16 +451,16 @@ mod tests {
         .iter()
         .sum();
 
-        aabb.start.relative_eq(
+        assert!(aabb.start.relative_eq(
             &Vector2::<f32>::new(-560f32, -1f32),
-            std::f32::EPSILON,
-            std::f32::EPSILON,
-        );
-        aabb.end.relative_eq(
+            f32::EPSILON,
+            f32::EPSILON,
+        ));
+        assert!(aabb.end.relative_eq(
             &Vector2::<f32>::new(258f32, 505f32),
-            std::f32::EPSILON,
-            std::f32::EPSILON,
-        );
+            f32::EPSILON,
+            f32::EPSILON,
+        ));
     }
     #[test]
     fn dim_test() {
@@ -353,4 +483,129 @@ mod tests {
         assert_eq!(aabb2.start, start * 0.5f32);
         assert_eq!(aabb2.end, end * 0.5f32);
     }
+    #[test]
+    fn mass_test() {
+        let aabb = AABB2D {
+            start: Vector2::<f32>::new(1f32, 2f32),
+            end: Vector2::<f32>::new(4f32, 6f32),
+        };
+        let mass = aabb.mass_properties(2f32);
+
+        assert_eq!(mass.mass, 24f32);
+        assert_eq!(mass.center_of_mass, aabb.center());
+        assert_eq!(mass.inertia, 50f32);
+    }
+    #[test]
+    fn sum_excludes_origin_test() {
+        let boxes = [
+            AABB2D {
+                start: Vector2::<f32>::new(1f32, 2f32),
+                end: Vector2::<f32>::new(2f32, 3f32),
+            },
+            AABB2D {
+                start: Vector2::<f32>::new(5f32, 1f32),
+                end: Vector2::<f32>::new(6f32, 2f32),
+            },
+        ];
+        let aabb: AABB2D<f32> = boxes.iter().sum();
+
+        assert_eq!(aabb.start, Vector2::new(1f32, 1f32));
+        assert_eq!(aabb.end, Vector2::new(6f32, 3f32));
+        assert!(::core::iter::empty::<AABB2D<f32>>()
+            .sum::<AABB2D<f32>>()
+            .is_empty());
+        assert_eq!(boxes[0] + AABB2D::empty(), boxes[0]);
+    }
+    #[test]
+    fn empty_test() {
+        let empty = AABB2D::<f32>::empty();
+        let a = AABB2D {
+            start: Vector2::<f32>::new(0f32, 0f32),
+            end: Vector2::<f32>::new(2f32, 2f32),
+        };
+
+        assert!(!empty.contains_point(&Vector2::zeros()));
+        assert!(!empty.contains_aabb(&a));
+        assert!(a.contains_aabb(&empty));
+        assert!(empty.contains_aabb(&empty));
+        assert!(empty.intersection(&a).is_none());
+        assert!(a.intersection(&empty).is_none());
+        assert!(empty.expand_by(1f32).is_empty());
+        assert!(empty.translate(&Vector2::new(1f32, 2f32)).is_empty());
+        assert!(empty.set_center(&Vector2::new(1f32, 2f32)).is_empty());
+        assert!((empty * 2f32).is_empty());
+        assert_eq!(empty.width(), 0f32);
+        assert_eq!(empty.height(), 0f32);
+        assert_eq!(empty.area(), 0f32);
+        assert_eq!(empty.perimeter(), 0f32);
+        assert_eq!(empty.center(), Vector2::zeros());
+    }
+    #[test]
+    fn empty_min_max_test() {
+        let empty = AABB2D::<f32>::empty();
+        assert_eq!(empty.min_max(), (Vector2::zeros(), Vector2::zeros()));
+    }
+    #[test]
+    fn empty_closest_point_test() {
+        let empty = AABB2D::<f32>::empty();
+        let point = Vector2::new(1f32, 2f32);
+        assert_eq!(empty.closest_point(&point), Vector2::zeros());
+        assert!(!empty.project_point(&point).is_inside);
+    }
+    #[test]
+    fn empty_aabb_collision_test() {
+        let empty = AABB2D::<f32>::empty();
+        let a = AABB2D {
+            start: Vector2::new(-1f32, -1f32),
+            end: Vector2::new(1f32, 1f32),
+        };
+        assert!(empty.get_aabb_collision(&a).is_none());
+        assert!(a.get_aabb_collision(&empty).is_none());
+        assert!(empty.get_aabb_collision(&empty).is_none());
+    }
+    #[test]
+    fn empty_circle_collision_test() {
+        let empty = AABB2D::<f32>::empty();
+        let circle = Circle {
+            center: Vector2::new(5f32, 5f32),
+            radius: 1f32,
+        };
+        assert!(empty.get_circle_collision(&circle).is_none());
+        assert!(empty
+            .get_circle_collision(&circle.set_center(&Vector2::zeros()))
+            .is_none());
+    }
+    #[test]
+    fn empty_bounding_circle_test() {
+        let circle = AABB2D::<f32>::empty().bounding_sphere();
+        assert_eq!(circle.center, Vector2::zeros());
+        assert_eq!(circle.radius, 0f32);
+    }
+    #[test]
+    fn set_operations_test() {
+        let a = AABB2D {
+            start: Vector2::<f32>::new(0f32, 0f32),
+            end: Vector2::<f32>::new(2f32, 2f32),
+        };
+        let b = AABB2D {
+            start: Vector2::<f32>::new(3f32, 1f32),
+            end: Vector2::<f32>::new(1f32, 3f32),
+        };
+
+        let overlap = a.intersection(&b).unwrap();
+        assert_eq!(overlap.start, Vector2::new(1f32, 1f32));
+        assert_eq!(overlap.end, Vector2::new(2f32, 2f32));
+        assert!(a
+            .intersection(&b.translate(&Vector2::new(5f32, 0f32)))
+            .is_none());
+
+        assert!(a.contains_aabb(&overlap));
+        assert!(!a.contains_aabb(&b));
+        assert_eq!(
+            a.expand_to_point(&Vector2::new(-1f32, 4f32)).min_max(),
+            (Vector2::new(-1f32, 0f32), Vector2::new(2f32, 4f32))
+        );
+        assert_eq!(a.expand_by(0.5f32).perimeter(), 12f32);
+        assert_eq!(a.swept(&Vector2::new(1f32, 0f32)).area(), 6f32);
+    }
 }
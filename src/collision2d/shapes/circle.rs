@@ -13,6 +13,24 @@ pub struct Circle<N: PhysicsScalar> {
 }
 
 impl<N: FloatingPhysicsScalar> Circle<N> {
+    pub fn area(&self) -> N {
+        N::pi() * self.radius * self.radius
+    }
+    pub fn contains_point(&self, point: &Vector2<N>) -> bool {
+        (point - self.center).magnitude_squared() <= self.radius * self.radius
+    }
+    pub fn project_point(&self, point: &Vector2<N>) -> PointProjection<Vector2<N>> {
+        let n = point - self.center;
+        let direction = if is_zero(n.magnitude_squared()) {
+            Vector2::x()
+        } else {
+            n.normalize()
+        };
+        PointProjection {
+            point: self.center + direction * self.radius,
+            is_inside: self.contains_point(point),
+        }
+    }
     pub fn get_circle_collision(
         &self,
         circle: &Circle<N>,
@@ -39,6 +57,93 @@ impl<N: FloatingPhysicsScalar> Circle<N> {
     }
 }
 
+#[cfg(feature = "alloc")]
+impl<N: FloatingPhysicsScalar> Circle<N> {
+    /// The smallest circle holding every point, by Welzl's algorithm over the
+    /// shuffled points as in `Sphere::from_points`.
+    pub fn from_points<I: IntoIterator<Item = Vector2<N>>>(points: I) -> Self {
+        let mut points: Vec<_> = points.into_iter().collect();
+        shuffle(&mut points);
+        smallest_ball(&points)
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Circle<N> {
+    fn through_two(a: &Vector2<N>, b: &Vector2<N>) -> Self {
+        Circle {
+            center: (a + b) * N::from_f64(0.5).unwrap(),
+            radius: (a - b).magnitude() * N::from_f64(0.5).unwrap(),
+        }
+    }
+    /// The circumcircle, or the circle through the two points furthest apart
+    /// when the three are collinear.
+    fn through_three(a: &Vector2<N>, b: &Vector2<N>, c: &Vector2<N>) -> Self {
+        let (ab, ac) = (b - a, c - a);
+        let (b2, c2) = (ab.magnitude_squared(), ac.magnitude_squared());
+        let cross = ab.perp(&ac);
+        if cross * cross <= b2 * c2 * N::epsilon() {
+            return [
+                Self::through_two(a, b),
+                Self::through_two(a, c),
+                Self::through_two(b, c),
+            ]
+            .iter()
+            .copied()
+            .max_by(|x, y| n_ordering(x.radius, y.radius))
+            .unwrap();
+        }
+        let offset = Vector2::new(ac.y * b2 - ab.y * c2, ab.x * c2 - ac.x * b2)
+            / (cross * N::from_f64(2.0).unwrap());
+        Circle {
+            center: a + offset,
+            radius: offset.magnitude(),
+        }
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Ball<N> for Circle<N> {
+    type Point = Vector2<N>;
+    const MAX_BOUNDARY: usize = 3;
+
+    fn radius(&self) -> N {
+        self.radius
+    }
+    fn with_radius(&self, radius: N) -> Self {
+        Circle { radius, ..*self }
+    }
+    fn distance(&self, point: &Vector2<N>) -> N {
+        (point - self.center).magnitude()
+    }
+    fn through(boundary: &[Vector2<N>]) -> Self {
+        match *boundary {
+            [] => Circle {
+                center: Vector2::zeros(),
+                radius: N::zero(),
+            },
+            [a] => Circle {
+                center: a,
+                radius: N::zero(),
+            },
+            [a, b] => Self::through_two(&a, &b),
+            [a, b, c, ..] => Self::through_three(&a, &b, &c),
+        }
+    }
+}
+
+impl<N: FloatingPhysicsScalar> MassProperties<N> for Circle<N> {
+    type Vector = Vector2<N>;
+    type Inertia = N;
+
+    fn mass_properties(&self, density: N) -> MassData<Vector2<N>, N, N> {
+        let mass = self.area() * density;
+        MassData {
+            mass,
+            center_of_mass: self.center,
+            inertia: mass * self.radius * self.radius * N::from_f64(0.5).unwrap(),
+        }
+    }
+}
+
 impl<N: FloatingPhysicsScalar> Shape2D<N> for Circle<N> {
     fn bounding_aabb(&self) -> AxisAlignedBoundingBox<N> {
         AxisAlignedBoundingBox {
@@ -71,3 +176,48 @@ impl<N: FloatingPhysicsScalar> Shape2D<N> for Circle<N> {
         self.center = *point;
     }
 }
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_points_test() {
+        // The corners of a square, with its centre and a point on an edge.
+        let mut points: Vec<_> = (0..4)
+            .map(|i| Vector2::from_fn(|k, _| if i & (1 << k) != 0 { 1f64 } else { -1f64 }))
+            .collect();
+        points.push(Vector2::zeros());
+        points.push(Vector2::new(1f64, 0.5f64));
+        let circle = Circle::from_points(points);
+        assert!(circle.center.magnitude() < 1e-9);
+        assert!((circle.radius - 2f64.sqrt()).abs() < 1e-9);
+
+        // An equilateral triangle needs all three points.
+        let circle = Circle::from_points(vec![
+            Vector2::new(0f64, 1f64),
+            Vector2::new(0.75f64.sqrt(), -0.5f64),
+            Vector2::new(-(0.75f64.sqrt()), -0.5f64),
+        ]);
+        assert!(circle.center.magnitude() < 1e-9);
+        assert!((circle.radius - 1f64).abs() < 1e-9);
+
+        let single = Circle::from_points(Some(Vector2::new(1f64, 2f64)));
+        assert_eq!(single.center, Vector2::new(1f64, 2f64));
+        assert_eq!(single.radius, 0f64);
+        assert_eq!(Circle::<f64>::from_points(None).radius, 0f64);
+    }
+    #[test]
+    fn mass_test() {
+        let circle = Circle {
+            center: Vector2::new(1f64, 2f64),
+            radius: 2f64,
+        };
+        let mass = circle.mass_properties(3f64);
+
+        let expected = 12f64 * std::f64::consts::PI;
+        assert!((mass.mass - expected).abs() < 1e-9);
+        assert_eq!(mass.center_of_mass, circle.center);
+        assert!((mass.inertia - 2f64 * expected).abs() < 1e-9);
+    }
+}
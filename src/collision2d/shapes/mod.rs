@@ -4,4 +4,9 @@ pub use aabb::*;
 pub mod circle;
 pub use circle::*;
 
+#[cfg(feature = "alloc")]
+pub mod tile_map;
+#[cfg(feature = "alloc")]
+pub use tile_map::*;
+
 use super::*;
\ No newline at end of file
@@ -0,0 +1,524 @@
+use super::*;
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+/// What fills a tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum Tile {
+    #[default]
+    Empty,
+    Solid,
+    /// A platform that only blocks from above, so shapes can jump up through
+    /// it and land on top. Up is `+y`.
+    OneWay,
+}
+
+/// A block of tiles of the same kind, from `start` up to but not including
+/// `end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct TileRect {
+    pub start: [usize; 2],
+    pub end: [usize; 2],
+    pub tile: Tile,
+}
+
+/// A static collider for a grid of tiles. Runs of tiles of the same kind are
+/// merged into as few rectangles as a row-then-column sweep finds, and faces
+/// shared with solid neighbours are ignored, so shapes slide across the map
+/// without catching on the seams between tiles.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct TileMap2D<N: PhysicsScalar> {
+    pub origin: Vector2<N>,
+    pub tile_size: N,
+    /// How far a shape can sink into a one-way platform from above and still
+    /// be pushed back on top. Anything deeper is passing through from below.
+    pub one_way_depth: N,
+    dimensions: [usize; 2],
+    /// Row by row, starting from the bottom.
+    tiles: Vec<Tile>,
+    rects: Vec<TileRect>,
+    /// The index into `rects` of the rectangle covering each tile.
+    owners: Vec<usize>,
+}
+
+/// Where a ray first enters a blocking tile.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TileHit<N: PhysicsScalar> {
+    pub tile: [usize; 2],
+    /// The distance along the ray in multiples of its direction.
+    pub distance: N,
+    /// The normal of the edge the ray entered through, averaged over both
+    /// edges when it enters through a corner, or zero when the ray starts
+    /// inside a solid tile or on its boundary.
+    pub normal: Vector2<N>,
+}
+
+impl<N: FloatingPhysicsScalar> TileMap2D<N> {
+    /// An empty map with `dimensions` tiles along x and y. Shapes can sink a
+    /// quarter of a tile into one-way platforms.
+    pub fn new(origin: &Vector2<N>, tile_size: N, dimensions: [usize; 2]) -> Self {
+        let count = dimensions[0] * dimensions[1];
+        TileMap2D {
+            origin: *origin,
+            tile_size,
+            one_way_depth: tile_size * N::from_f64(0.25).unwrap(),
+            dimensions,
+            tiles: vec![Tile::Empty; count],
+            rects: Vec::new(),
+            owners: vec![usize::MAX; count],
+        }
+    }
+    /// A map filled by `tile`, which is called once for every position.
+    pub fn from_fn<F: FnMut([usize; 2]) -> Tile>(
+        origin: &Vector2<N>,
+        tile_size: N,
+        dimensions: [usize; 2],
+        mut tile: F,
+    ) -> Self {
+        let mut map = Self::new(origin, tile_size, dimensions);
+        for y in 0..dimensions[1] {
+            for x in 0..dimensions[0] {
+                map.tiles[x + y * dimensions[0]] = tile([x, y]);
+            }
+        }
+        map.merge();
+        map
+    }
+    pub fn dimensions(&self) -> [usize; 2] {
+        self.dimensions
+    }
+    /// The tile at `position`. Positions outside the map are empty.
+    pub fn tile(&self, position: [usize; 2]) -> Tile {
+        if position[0] < self.dimensions[0] && position[1] < self.dimensions[1] {
+            self.tiles[position[0] + position[1] * self.dimensions[0]]
+        } else {
+            Tile::Empty
+        }
+    }
+    /// Changes one tile and merges the map again. Use `from_fn` to build a
+    /// whole map at once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is outside the map.
+    pub fn set_tile(&mut self, position: [usize; 2], tile: Tile) {
+        assert!(
+            position[0] < self.dimensions[0] && position[1] < self.dimensions[1],
+            "tile outside the map"
+        );
+        self.tiles[position[0] + position[1] * self.dimensions[0]] = tile;
+        self.merge();
+    }
+    /// The merged rectangles, in tiles.
+    pub fn rects(&self) -> &[TileRect] {
+        &self.rects
+    }
+    /// The area covered by `rect`.
+    pub fn rect_aabb(&self, rect: &TileRect) -> AxisAlignedBoundingBox<N> {
+        let to_world = |tiles: [usize; 2]| {
+            self.origin
+                + Vector2::new(
+                    N::from_usize(tiles[0]).unwrap(),
+                    N::from_usize(tiles[1]).unwrap(),
+                ) * self.tile_size
+        };
+        AxisAlignedBoundingBox {
+            start: to_world(rect.start),
+            end: to_world(rect.end),
+        }
+    }
+    /// The tile holding `point`, if it is inside the map.
+    pub fn tile_at(&self, point: &Vector2<N>) -> Option<[usize; 2]> {
+        let mut tile = [0; 2];
+        for (i, t) in tile.iter_mut().enumerate() {
+            let index = Float::floor((point[i] - self.origin[i]) / self.tile_size);
+            *t = index.to_usize().filter(|t| *t < self.dimensions[i])?;
+        }
+        Some(tile)
+    }
+    /// Joins each row into runs of the same tile, then stacks runs that span
+    /// the same columns in consecutive rows.
+    fn merge(&mut self) {
+        let [width, height] = self.dimensions;
+        self.rects.clear();
+        // Rectangles that can still grow upwards.
+        let mut open: Vec<usize> = Vec::new();
+        for y in 0..height {
+            let mut still_open = Vec::new();
+            let mut x = 0;
+            while x < width {
+                let tile = self.tiles[x + y * width];
+                let start = x;
+                while x < width && self.tiles[x + y * width] == tile {
+                    x += 1;
+                }
+                if tile == Tile::Empty {
+                    continue;
+                }
+                let below = open.iter().copied().find(|&i| {
+                    let rect = &self.rects[i];
+                    rect.start[0] == start && rect.end[0] == x && rect.tile == tile
+                });
+                match below {
+                    Some(i) => {
+                        self.rects[i].end[1] = y + 1;
+                        still_open.push(i);
+                    }
+                    None => {
+                        still_open.push(self.rects.len());
+                        self.rects.push(TileRect {
+                            start: [start, y],
+                            end: [x, y + 1],
+                            tile,
+                        });
+                    }
+                }
+            }
+            open = still_open;
+        }
+
+        for owner in self.owners.iter_mut() {
+            *owner = usize::MAX;
+        }
+        for (i, rect) in self.rects.iter().enumerate() {
+            for y in rect.start[1]..rect.end[1] {
+                for x in rect.start[0]..rect.end[0] {
+                    self.owners[x + y * width] = i;
+                }
+            }
+        }
+    }
+    /// The range of tiles along `axis` that overlap `min..=max`.
+    fn tile_range(&self, axis: usize, min: N, max: N) -> core::ops::Range<usize> {
+        let low = Float::floor((min - self.origin[axis]) / self.tile_size);
+        let high = Float::floor((max - self.origin[axis]) / self.tile_size);
+        let last = N::from_usize(self.dimensions[axis]).unwrap() - N::one();
+        if high < N::zero() || low > last {
+            0..0
+        } else {
+            let low = n_max(low, N::zero()).to_usize().unwrap();
+            let high = n_min(high, last).to_usize().unwrap();
+            low..high + 1
+        }
+    }
+    /// The rectangles that overlap `aabb`.
+    pub fn rects_in(&self, aabb: &AxisAlignedBoundingBox<N>) -> Vec<&TileRect> {
+        let (min, max) = aabb.min_max();
+        let mut found: Vec<usize> = self
+            .tile_range(1, min.y, max.y)
+            .flat_map(|y| {
+                self.tile_range(0, min.x, max.x)
+                    .map(move |x| self.owners[x + y * self.dimensions[0]])
+            })
+            .filter(|&owner| owner != usize::MAX)
+            .collect();
+        found.sort_unstable();
+        found.dedup();
+        found.into_iter().map(|i| &self.rects[i]).collect()
+    }
+    /// Whether every tile just past the `sign` side of `rect` along `axis`,
+    /// between `min` and `max` on the other axis, is solid.
+    fn is_face_covered(&self, rect: &TileRect, axis: usize, sign: N, min: N, max: N) -> bool {
+        let row = if sign > N::zero() {
+            rect.end[axis]
+        } else if rect.start[axis] > 0 {
+            rect.start[axis] - 1
+        } else {
+            return false;
+        };
+        let other = 1 - axis;
+        let tiles = self.tile_range(other, min, max);
+        let tiles = tiles.start.max(rect.start[other])..tiles.end.min(rect.end[other]);
+        !tiles.is_empty()
+            && tiles.into_iter().all(|t| {
+                let mut position = [0; 2];
+                position[axis] = row;
+                position[other] = t;
+                self.tile(position) == Tile::Solid
+            })
+    }
+    /// Collides `bounds` with every rectangle it touches and keeps the deepest
+    /// result, flipped to push the map.
+    ///
+    /// When a resolution would push through a face covered by solid tiles, the
+    /// shallowest exposed face is used instead. One-way platforms only push up,
+    /// and only shapes that have sunk at most `one_way_depth` into them.
+    fn deepest<F>(
+        &self,
+        bounds: &AxisAlignedBoundingBox<N>,
+        mut collide: F,
+    ) -> Option<CollisionResolution<Vector2<N>, N>>
+    where
+        F: FnMut(&AxisAlignedBoundingBox<N>) -> Option<CollisionResolution<Vector2<N>, N>>,
+    {
+        let (shape_min, shape_max) = bounds.min_max();
+        self.rects_in(bounds)
+            .into_iter()
+            .filter_map(|rect| {
+                let aabb = self.rect_aabb(rect);
+                let res = collide(&aabb)?;
+                let (min, max) = aabb.min_max();
+                let overlap = aabb.intersection(bounds).map_or_else(
+                    || {
+                        let point = aabb.closest_point(&bounds.center());
+                        (point, point)
+                    },
+                    |o| o.min_max(),
+                );
+                // How far the shape must move along each face normal to leave.
+                let exposed = |axis: usize, sign: N| {
+                    let other = 1 - axis;
+                    if self.is_face_covered(rect, axis, sign, overlap.0[other], overlap.1[other]) {
+                        None
+                    } else if sign > N::zero() {
+                        Some(max[axis] - shape_min[axis])
+                    } else {
+                        Some(shape_max[axis] - min[axis])
+                    }
+                };
+
+                if rect.tile == Tile::OneWay {
+                    let penetration = exposed(1, N::one())?;
+                    return if penetration > N::zero() && penetration <= self.one_way_depth {
+                        Some(CollisionResolution {
+                            normal: Vector2::y(),
+                            penetration,
+                        })
+                    } else {
+                        None
+                    };
+                }
+
+                let blocked = (0..2).any(|axis| {
+                    !is_zero(res.normal[axis]) && exposed(axis, res.normal[axis]).is_none()
+                });
+                if !blocked {
+                    return Some(res);
+                }
+                (0..4)
+                    .filter_map(|face| {
+                        let (axis, sign) =
+                            (face / 2, if face % 2 == 0 { N::one() } else { -N::one() });
+                        let penetration = exposed(axis, sign)?;
+                        Some(CollisionResolution {
+                            normal: Vector2::ith(axis, sign),
+                            penetration,
+                        })
+                    })
+                    .filter(|r| r.penetration > N::zero())
+                    .min_by(|a, b| n_ordering(a.penetration, b.penetration))
+            })
+            .max_by(|a, b| n_ordering(a.penetration, b.penetration))
+            .map(|r| r.flip())
+    }
+    pub fn get_aabb_collision(
+        &self,
+        aabb: &AxisAlignedBoundingBox<N>,
+    ) -> Option<CollisionResolution<Vector2<N>, N>> {
+        self.deepest(aabb, |rect| aabb.get_aabb_collision(rect))
+    }
+    pub fn get_circle_collision(
+        &self,
+        circle: &Circle<N>,
+    ) -> Option<CollisionResolution<Vector2<N>, N>> {
+        self.deepest(&circle.bounding_aabb(), |rect| {
+            rect.get_circle_collision(circle).map(|r| r.flip())
+        })
+    }
+    /// Steps through the tiles along a ray and returns the first one within
+    /// `max_distance` that blocks it. One-way platforms only block rays coming
+    /// down onto their top edge. A ray that only grazes a solid tile along an
+    /// edge or at a corner hits it.
+    pub fn cast_ray(
+        &self,
+        point: &Vector2<N>,
+        direction: &Vector2<N>,
+        max_distance: N,
+    ) -> Option<TileHit<N>> {
+        let step = grid_walk(
+            self.origin.as_slice(),
+            self.tile_size,
+            self.dimensions,
+            point.as_slice(),
+            direction.as_slice(),
+            max_distance,
+            |step| match self.tile(step.cell) {
+                Tile::Solid => true,
+                Tile::OneWay => step.entered[1] && direction[1] < N::zero(),
+                Tile::Empty => false,
+            },
+        )?;
+        let normal = Vector2::from_fn(|i, _| {
+            if step.entered[i] {
+                -Float::signum(direction[i])
+            } else {
+                N::zero()
+            }
+        });
+        Some(TileHit {
+            tile: step.cell,
+            distance: step.distance,
+            normal: normal.try_normalize(N::zero()).unwrap_or(normal),
+        })
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Shape2D<N> for TileMap2D<N> {
+    fn bounding_aabb(&self) -> AxisAlignedBoundingBox<N> {
+        let size = Vector2::new(
+            N::from_usize(self.dimensions[0]).unwrap(),
+            N::from_usize(self.dimensions[1]).unwrap(),
+        );
+        AxisAlignedBoundingBox {
+            start: self.origin,
+            end: self.origin + size * self.tile_size,
+        }
+    }
+    fn bounding_sphere(&self) -> Circle<N> {
+        self.bounding_aabb().bounding_sphere()
+    }
+    fn center(&self) -> Vector2<N> {
+        self.bounding_aabb().center()
+    }
+    fn translate(&self, point: &Vector2<N>) -> Self {
+        let mut map = self.clone();
+        map.translate_mut(point);
+        map
+    }
+    fn set_center(&self, point: &Vector2<N>) -> Self {
+        let mut map = self.clone();
+        map.set_center_mut(point);
+        map
+    }
+    fn translate_mut(&mut self, point: &Vector2<N>) {
+        self.origin += point;
+    }
+    fn set_center_mut(&mut self, point: &Vector2<N>) {
+        let offset = point - self.center();
+        self.translate_mut(&offset);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A floor with a one-way platform on the left and a step on the right
+    /// whose left face is split between two rectangles:
+    ///
+    /// ```text
+    /// 3 .....##.
+    /// 2 .===.##.
+    /// 1 .....###
+    /// 0 ########
+    /// ```
+    fn map() -> TileMap2D<f64> {
+        TileMap2D::from_fn(&Vector2::zeros(), 1f64, [8, 4], |[x, y]| match (x, y) {
+            (_, 0) | (5..=7, 1) | (5..=6, _) => Tile::Solid,
+            (1..=3, 2) => Tile::OneWay,
+            _ => Tile::Empty,
+        })
+    }
+
+    #[test]
+    fn merge_test() {
+        let mut map = map();
+        let rect = |start, end, tile| TileRect { start, end, tile };
+        assert_eq!(
+            map.rects(),
+            &[
+                rect([0, 0], [8, 1], Tile::Solid),
+                rect([5, 1], [8, 2], Tile::Solid),
+                rect([1, 2], [4, 3], Tile::OneWay),
+                rect([5, 2], [7, 4], Tile::Solid),
+            ][..]
+        );
+        map.set_tile([1, 2], Tile::Empty);
+        assert_eq!(map.rects()[2], rect([2, 2], [4, 3], Tile::OneWay));
+        assert_eq!(map.tile([1, 2]), Tile::Empty);
+        assert_eq!(map.tile([9, 2]), Tile::Empty);
+        assert_eq!(map.tile_at(&Vector2::new(7.5f64, 3.5f64)), Some([7, 3]));
+        assert_eq!(map.tile_at(&Vector2::new(8.5f64, 3.5f64)), None);
+    }
+    #[test]
+    fn seam_test() {
+        let map = map();
+        // Pressed against the step where its two rectangles meet. The lower
+        // one's top is covered by the upper one, so both push out sideways.
+        let aabb = AABB2D {
+            start: Vector2::new(4f64, 1.8f64),
+            end: Vector2::new(5.3f64, 2.02f64),
+        };
+        let res = map.get_aabb_collision(&aabb).unwrap().flip();
+        assert_eq!(res.normal, -Vector2::x());
+        assert!((res.penetration - 0.3f64).abs() < 1e-9);
+
+        let circle = Circle {
+            center: Vector2::new(4.7f64, 1.2f64),
+            radius: 0.5f64,
+        };
+        let res = map.get_circle_collision(&circle).unwrap().flip();
+        assert_eq!(res.normal, Vector2::y());
+        assert!((res.penetration - 0.3f64).abs() < 1e-9);
+        assert!(map
+            .get_circle_collision(&circle.translate(&Vector2::new(-2f64, 0.5f64)))
+            .is_none());
+    }
+    #[test]
+    fn one_way_test() {
+        let map = map();
+        let landing = AABB2D {
+            start: Vector2::new(1.5f64, 2.9f64),
+            end: Vector2::new(2.5f64, 3.9f64),
+        };
+        let res = map.get_aabb_collision(&landing).unwrap().flip();
+        assert_eq!(res.normal, Vector2::y());
+        assert!((res.penetration - 0.1f64).abs() < 1e-9);
+
+        let jumping = landing.translate(&Vector2::new(0f64, -0.5f64));
+        assert!(map.get_aabb_collision(&jumping).is_none());
+    }
+    #[test]
+    fn ray_test() {
+        let map = map();
+        let hit = map
+            .cast_ray(&Vector2::new(0.5f64, 2.5f64), &Vector2::x(), 10f64)
+            .unwrap();
+        assert_eq!((hit.tile, hit.distance), ([5, 2], 4.5f64));
+        assert_eq!(hit.normal, -Vector2::x());
+        assert!(map
+            .cast_ray(&Vector2::new(0.5f64, 2.5f64), &Vector2::x(), 4f64)
+            .is_none());
+
+        let down = map
+            .cast_ray(&Vector2::new(2.5f64, 5f64), &-Vector2::y(), 10f64)
+            .unwrap();
+        assert_eq!((down.tile, down.distance), ([2, 2], 2f64));
+        assert_eq!(down.normal, Vector2::y());
+        assert!(map
+            .cast_ray(&Vector2::new(2.5f64, 2.2f64), &Vector2::y(), 10f64)
+            .is_none());
+
+        let inside = map
+            .cast_ray(&Vector2::new(0.5f64, 0.5f64), &Vector2::y(), 10f64)
+            .unwrap();
+        assert_eq!((inside.tile, inside.normal), ([0, 0], Vector2::zeros()));
+
+        // Sliding along the floor touches it, but not the platform's top.
+        let along = map
+            .cast_ray(&Vector2::new(-1f64, 1f64), &Vector2::x(), 10f64)
+            .unwrap();
+        assert_eq!((along.tile, along.distance), ([0, 0], 1f64));
+        assert!(map
+            .cast_ray(&Vector2::new(0.5f64, 3f64), &Vector2::x(), 4f64)
+            .is_none());
+        let corner = map
+            .cast_ray(&Vector2::new(0f64, 4f64), &Vector2::new(1f64, -1f64), 10f64)
+            .unwrap();
+        assert_eq!((corner.tile, corner.distance), ([1, 2], 1f64));
+    }
+}
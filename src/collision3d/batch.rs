@@ -0,0 +1,479 @@
+use super::*;
+use core::iter::FromIterator;
+
+/// Number of shapes tested together before resolving the hits. Small enough to
+/// keep the scratch arrays on the stack and wide enough for any SIMD unit.
+const LANES: usize = 64;
+
+/// A resolution tagged with the index of the shape it was computed against.
+pub type IndexedResolution<N> = (usize, CollisionResolution<Vector3<N>, N>);
+
+/// Spheres stored as one array per component so that testing one shape against
+/// all of them compiles to straight-line, vectorisable loops.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SphereBatch<N: PhysicsScalar> {
+    pub x: Vec<N>,
+    pub y: Vec<N>,
+    pub z: Vec<N>,
+    pub radius: Vec<N>,
+}
+
+/// Boxes stored as one array per component of their corners. Boxes are
+/// normalised on insertion so `min` is never greater than `max`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AABBBatch<N: PhysicsScalar> {
+    pub min_x: Vec<N>,
+    pub min_y: Vec<N>,
+    pub min_z: Vec<N>,
+    pub max_x: Vec<N>,
+    pub max_y: Vec<N>,
+    pub max_z: Vec<N>,
+}
+
+/// Marks the spheres that touch a sphere at `center` with `radius`.
+fn sphere_mask<N: FloatingPhysicsScalar>(
+    center: &Vector3<N>,
+    radius: N,
+    (x, y, z, r): (&[N], &[N], &[N], &[N]),
+    mask: &mut [bool],
+) {
+    let n = mask.len();
+    let (x, y, z, r) = (&x[..n], &y[..n], &z[..n], &r[..n]);
+    for i in 0..n {
+        let (dx, dy, dz) = (x[i] - center.x, y[i] - center.y, z[i] - center.z);
+        let reach = r[i] + radius;
+        mask[i] = dx * dx + dy * dy + dz * dz <= reach * reach;
+    }
+}
+
+/// Marks the boxes that are within `radius` of `center`.
+fn aabb_sphere_mask<N: FloatingPhysicsScalar>(
+    center: &Vector3<N>,
+    radius: N,
+    (min, max): ([&[N]; 3], [&[N]; 3]),
+    mask: &mut [bool],
+) {
+    let n = mask.len();
+    let (min, max) = (
+        [&min[0][..n], &min[1][..n], &min[2][..n]],
+        [&max[0][..n], &max[1][..n], &max[2][..n]],
+    );
+    for i in 0..n {
+        let mut distance = N::zero();
+        for axis in 0..3 {
+            let c = center[axis];
+            let closest = n_min(n_max(c, min[axis][i]), max[axis][i]);
+            distance += (closest - c) * (closest - c);
+        }
+        mask[i] = distance <= radius * radius;
+    }
+}
+
+/// Marks the boxes whose interiors overlap the box from `start` to `end`.
+fn aabb_mask<N: FloatingPhysicsScalar>(
+    start: &Vector3<N>,
+    end: &Vector3<N>,
+    (min, max): ([&[N]; 3], [&[N]; 3]),
+    mask: &mut [bool],
+) {
+    let n = mask.len();
+    let (min, max) = (
+        [&min[0][..n], &min[1][..n], &min[2][..n]],
+        [&max[0][..n], &max[1][..n], &max[2][..n]],
+    );
+    for i in 0..n {
+        // Non short-circuiting so every lane does the same work.
+        mask[i] = (min[0][i] < end.x)
+            & (min[1][i] < end.y)
+            & (min[2][i] < end.z)
+            & (start.x < max[0][i])
+            & (start.y < max[1][i])
+            & (start.z < max[2][i]);
+    }
+}
+
+/// Scratch component arrays for one chunk of boxes taken from a slice.
+struct AABBLanes<N: PhysicsScalar> {
+    min: [[N; LANES]; 3],
+    max: [[N; LANES]; 3],
+}
+
+impl<N: FloatingPhysicsScalar> AABBLanes<N> {
+    fn new() -> Self {
+        AABBLanes {
+            min: [[N::zero(); LANES]; 3],
+            max: [[N::zero(); LANES]; 3],
+        }
+    }
+    fn gather(&mut self, boxes: &[AxisAlignedBoundingBox<N>]) -> ([&[N]; 3], [&[N]; 3]) {
+        for (i, aabb) in boxes.iter().enumerate() {
+            let (start, end) = aabb.min_max();
+            for axis in 0..3 {
+                self.min[axis][i] = start[axis];
+                self.max[axis][i] = end[axis];
+            }
+        }
+        let len = boxes.len();
+        (
+            [
+                &self.min[0][..len],
+                &self.min[1][..len],
+                &self.min[2][..len],
+            ],
+            [
+                &self.max[0][..len],
+                &self.max[1][..len],
+                &self.max[2][..len],
+            ],
+        )
+    }
+}
+
+/// Runs `mask` over `len` shapes in chunks and calls `hit` for every index it marks.
+fn for_each_hit<M, H>(len: usize, mut mask: M, mut hit: H)
+where
+    M: FnMut(core::ops::Range<usize>, &mut [bool]),
+    H: FnMut(usize),
+{
+    let mut scratch = [false; LANES];
+    let mut start = 0;
+    while start < len {
+        let end = (start + LANES).min(len);
+        let scratch = &mut scratch[..end - start];
+        mask(start..end, scratch);
+        for (i, _) in scratch.iter().enumerate().filter(|(_, &m)| m) {
+            hit(start + i);
+        }
+        start = end;
+    }
+}
+
+impl<N: FloatingPhysicsScalar> SphereBatch<N> {
+    pub fn new() -> Self {
+        SphereBatch {
+            x: Vec::new(),
+            y: Vec::new(),
+            z: Vec::new(),
+            radius: Vec::new(),
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.radius.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.radius.is_empty()
+    }
+    pub fn push(&mut self, sphere: &Sphere<N>) {
+        self.x.push(sphere.center.x);
+        self.y.push(sphere.center.y);
+        self.z.push(sphere.center.z);
+        self.radius.push(sphere.radius);
+    }
+    pub fn get(&self, index: usize) -> Sphere<N> {
+        Sphere {
+            center: Vector3::new(self.x[index], self.y[index], self.z[index]),
+            radius: self.radius[index],
+        }
+    }
+    /// Appends the index and resolution of every sphere `sphere` collides with.
+    /// Each resolution is the one `sphere.get_sphere_collision` returns.
+    pub fn get_sphere_collisions(&self, sphere: &Sphere<N>, out: &mut Vec<IndexedResolution<N>>) {
+        for_each_hit(
+            self.len(),
+            |range, mask| {
+                let lanes = (
+                    &self.x[range.clone()],
+                    &self.y[range.clone()],
+                    &self.z[range.clone()],
+                    &self.radius[range],
+                );
+                sphere_mask(&sphere.center, sphere.radius, lanes, mask)
+            },
+            |i| {
+                if let Some(res) = sphere.get_sphere_collision(&self.get(i)) {
+                    out.push((i, res));
+                }
+            },
+        );
+    }
+}
+
+impl<N: FloatingPhysicsScalar> From<&[Sphere<N>]> for SphereBatch<N> {
+    fn from(spheres: &[Sphere<N>]) -> Self {
+        spheres.iter().copied().collect()
+    }
+}
+
+impl<N: FloatingPhysicsScalar> FromIterator<Sphere<N>> for SphereBatch<N> {
+    fn from_iter<T: IntoIterator<Item = Sphere<N>>>(iter: T) -> Self {
+        let mut batch = SphereBatch::new();
+        for sphere in iter {
+            batch.push(&sphere);
+        }
+        batch
+    }
+}
+
+impl<N: FloatingPhysicsScalar> AABBBatch<N> {
+    pub fn new() -> Self {
+        AABBBatch {
+            min_x: Vec::new(),
+            min_y: Vec::new(),
+            min_z: Vec::new(),
+            max_x: Vec::new(),
+            max_y: Vec::new(),
+            max_z: Vec::new(),
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.min_x.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.min_x.is_empty()
+    }
+    pub fn push(&mut self, aabb: &AxisAlignedBoundingBox<N>) {
+        let (start, end) = aabb.min_max();
+        self.min_x.push(start.x);
+        self.min_y.push(start.y);
+        self.min_z.push(start.z);
+        self.max_x.push(end.x);
+        self.max_y.push(end.y);
+        self.max_z.push(end.z);
+    }
+    pub fn get(&self, index: usize) -> AxisAlignedBoundingBox<N> {
+        AxisAlignedBoundingBox {
+            start: Vector3::new(self.min_x[index], self.min_y[index], self.min_z[index]),
+            end: Vector3::new(self.max_x[index], self.max_y[index], self.max_z[index]),
+        }
+    }
+    fn lanes(&self, range: core::ops::Range<usize>) -> ([&[N]; 3], [&[N]; 3]) {
+        (
+            [
+                &self.min_x[range.clone()],
+                &self.min_y[range.clone()],
+                &self.min_z[range.clone()],
+            ],
+            [
+                &self.max_x[range.clone()],
+                &self.max_y[range.clone()],
+                &self.max_z[range],
+            ],
+        )
+    }
+    /// Appends the index of every box whose interior overlaps `aabb`.
+    pub fn get_aabb_overlaps(&self, aabb: &AxisAlignedBoundingBox<N>, out: &mut Vec<usize>) {
+        let (start, end) = aabb.min_max();
+        for_each_hit(
+            self.len(),
+            |range, mask| aabb_mask(&start, &end, self.lanes(range), mask),
+            |i| out.push(i),
+        );
+    }
+    /// Appends the index and resolution of every box `aabb` collides with.
+    /// Each resolution is the one `aabb.get_aabb_collision` returns.
+    pub fn get_aabb_collisions(
+        &self,
+        aabb: &AxisAlignedBoundingBox<N>,
+        out: &mut Vec<IndexedResolution<N>>,
+    ) {
+        let (start, end) = aabb.min_max();
+        for_each_hit(
+            self.len(),
+            |range, mask| aabb_mask(&start, &end, self.lanes(range), mask),
+            |i| {
+                if let Some(res) = aabb.get_aabb_collision(&self.get(i)) {
+                    out.push((i, res));
+                }
+            },
+        );
+    }
+    /// Appends the index and resolution of every box `sphere` collides with.
+    /// Each resolution pushes the sphere away from the box.
+    pub fn get_sphere_collisions(&self, sphere: &Sphere<N>, out: &mut Vec<IndexedResolution<N>>) {
+        for_each_hit(
+            self.len(),
+            |range, mask| aabb_sphere_mask(&sphere.center, sphere.radius, self.lanes(range), mask),
+            |i| {
+                if let Some(res) = self.get(i).get_sphere_collision(sphere) {
+                    out.push((i, res.flip()));
+                }
+            },
+        );
+    }
+}
+
+impl<N: FloatingPhysicsScalar> From<&[AxisAlignedBoundingBox<N>]> for AABBBatch<N> {
+    fn from(boxes: &[AxisAlignedBoundingBox<N>]) -> Self {
+        boxes.iter().copied().collect()
+    }
+}
+
+impl<N: FloatingPhysicsScalar> FromIterator<AxisAlignedBoundingBox<N>> for AABBBatch<N> {
+    fn from_iter<T: IntoIterator<Item = AxisAlignedBoundingBox<N>>>(iter: T) -> Self {
+        let mut batch = AABBBatch::new();
+        for aabb in iter {
+            batch.push(&aabb);
+        }
+        batch
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Sphere<N> {
+    /// Appends the index and resolution of every sphere in `others` that `self`
+    /// collides with. Spheres are gathered into small component arrays first;
+    /// build a `SphereBatch` once instead when testing the same set repeatedly.
+    pub fn get_sphere_collisions(&self, others: &[Sphere<N>], out: &mut Vec<IndexedResolution<N>>) {
+        let empty = [N::zero(); LANES];
+        let (mut x, mut y, mut z, mut r) = (empty, empty, empty, empty);
+        for_each_hit(
+            others.len(),
+            |range, mask| {
+                for (i, s) in others[range].iter().enumerate() {
+                    x[i] = s.center.x;
+                    y[i] = s.center.y;
+                    z[i] = s.center.z;
+                    r[i] = s.radius;
+                }
+                let len = mask.len();
+                let lanes = (&x[..len], &y[..len], &z[..len], &r[..len]);
+                sphere_mask(&self.center, self.radius, lanes, mask)
+            },
+            |i| {
+                if let Some(res) = self.get_sphere_collision(&others[i]) {
+                    out.push((i, res));
+                }
+            },
+        );
+    }
+    /// Appends the index and resolution of every box in `others` that `self`
+    /// collides with. Each resolution pushes the sphere away from the box.
+    pub fn get_aabb_collisions(
+        &self,
+        others: &[AxisAlignedBoundingBox<N>],
+        out: &mut Vec<IndexedResolution<N>>,
+    ) {
+        let mut lanes = AABBLanes::new();
+        for_each_hit(
+            others.len(),
+            |range, mask| {
+                let lanes = lanes.gather(&others[range]);
+                aabb_sphere_mask(&self.center, self.radius, lanes, mask)
+            },
+            |i| {
+                if let Some(res) = others[i].get_sphere_collision(self) {
+                    out.push((i, res.flip()));
+                }
+            },
+        );
+    }
+}
+
+impl<N: FloatingPhysicsScalar> AxisAlignedBoundingBox<N> {
+    /// Appends the index of every box in `others` whose interior overlaps `self`.
+    pub fn get_aabb_overlaps(&self, others: &[AxisAlignedBoundingBox<N>], out: &mut Vec<usize>) {
+        let (start, end) = self.min_max();
+        let mut lanes = AABBLanes::new();
+        for_each_hit(
+            others.len(),
+            |range, mask| aabb_mask(&start, &end, lanes.gather(&others[range]), mask),
+            |i| out.push(i),
+        );
+    }
+    /// Appends the index and resolution of every box in `others` that `self`
+    /// collides with.
+    pub fn get_aabb_collisions(
+        &self,
+        others: &[AxisAlignedBoundingBox<N>],
+        out: &mut Vec<IndexedResolution<N>>,
+    ) {
+        let (start, end) = self.min_max();
+        let mut lanes = AABBLanes::new();
+        for_each_hit(
+            others.len(),
+            |range, mask| aabb_mask(&start, &end, lanes.gather(&others[range]), mask),
+            |i| {
+                if let Some(res) = self.get_aabb_collision(&others[i]) {
+                    out.push((i, res));
+                }
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A small linear congruential generator so the tests need no dependencies.
+    fn random(seed: &mut u64) -> f64 {
+        *seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (*seed >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn random_vector(seed: &mut u64) -> Vector3<f64> {
+        Vector3::new(random(seed), random(seed), random(seed)) * 10.0
+    }
+
+    #[test]
+    fn batch_matches_pairwise_test() {
+        let mut seed = 7;
+        let spheres: Vec<Sphere<f64>> = (0..100)
+            .map(|_| Sphere {
+                center: random_vector(&mut seed),
+                radius: random(&mut seed) + 0.5,
+            })
+            .collect();
+        let boxes: Vec<AxisAlignedBoundingBox<f64>> = (0..100)
+            .map(|_| AxisAlignedBoundingBox {
+                start: random_vector(&mut seed),
+                end: random_vector(&mut seed) * 0.3,
+            })
+            .collect();
+        let sphere_batch = SphereBatch::from(&spheres[..]);
+        let aabb_batch = AABBBatch::from(&boxes[..]);
+
+        for sphere in spheres.iter().take(10) {
+            let expected: Vec<_> = spheres
+                .iter()
+                .enumerate()
+                .filter_map(|(i, s)| sphere.get_sphere_collision(s).map(|r| (i, r)))
+                .collect();
+            let mut out = Vec::new();
+            sphere.get_sphere_collisions(&spheres, &mut out);
+            assert_eq!(out, expected);
+            out.clear();
+            sphere_batch.get_sphere_collisions(sphere, &mut out);
+            assert_eq!(out, expected);
+
+            let mut expected = Vec::new();
+            sphere.get_aabb_collisions(&boxes, &mut expected);
+            assert!(!expected.is_empty());
+            out.clear();
+            aabb_batch.get_sphere_collisions(sphere, &mut out);
+            assert_eq!(out, expected);
+        }
+
+        for aabb in boxes.iter().take(10) {
+            let mut expected = Vec::new();
+            aabb.get_aabb_collisions(&boxes, &mut expected);
+            let mut out = Vec::new();
+            aabb_batch.get_aabb_collisions(aabb, &mut out);
+            assert_eq!(out, expected);
+
+            let mut overlaps = Vec::new();
+            aabb_batch.get_aabb_overlaps(aabb, &mut overlaps);
+            assert_eq!(
+                overlaps,
+                expected.iter().map(|(i, _)| *i).collect::<Vec<_>>()
+            );
+            overlaps.clear();
+            aabb.get_aabb_overlaps(&boxes, &mut overlaps);
+            assert_eq!(
+                overlaps,
+                expected.iter().map(|(i, _)| *i).collect::<Vec<_>>()
+            );
+        }
+    }
+}
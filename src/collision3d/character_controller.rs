@@ -0,0 +1,103 @@
+use super::*;
+
+/// Moves a shape through static 3D geometry; see
+/// `character_controller::CharacterController`.
+pub type CharacterController<N> = crate::character_controller::CharacterController<N, Vector3<N>>;
+
+pub type CharacterMove<S, N> = crate::character_controller::CharacterMove<S, Vector3<N>>;
+
+impl<N: FloatingPhysicsScalar> CharacterController<N> {
+    pub fn move_capsule(
+        &self,
+        capsule: &Capsule<N>,
+        displacement: &Vector3<N>,
+        colliders: &[Collider3D<N>],
+    ) -> CharacterMove<Capsule<N>, N> {
+        self.move_shape(capsule, displacement, colliders, |capsule, collider| {
+            collider.get_capsule_collision(capsule).map(|r| r.flip())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn controller() -> CharacterController<f32> {
+        CharacterController {
+            up: Vector3::y(),
+            max_slope: core::f32::consts::FRAC_PI_4,
+            step_height: 0.5,
+            ground_probe: 0.05,
+            max_iterations: 4,
+        }
+    }
+
+    fn capsule() -> Capsule<f32> {
+        Capsule {
+            start: Vector3::new(0.0, 0.5, 0.0),
+            end: Vector3::new(0.0, 1.5, 0.0),
+            radius: 0.5,
+        }
+    }
+
+    fn floor() -> Collider3D<f32> {
+        Collider3D::AABB(AABB3D {
+            start: Vector3::new(-10.0, -1.0, -10.0),
+            end: Vector3::new(10.0, 0.0, 10.0),
+        })
+    }
+
+    #[test]
+    fn falls_onto_ground_test() {
+        let result =
+            controller().move_capsule(&capsule(), &Vector3::new(0.0, -0.25, 0.0), &[floor()]);
+        assert!(result.grounded);
+        assert!(!result.hit_wall);
+        assert!((result.shape.start.y - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn slides_along_wall_test() {
+        let wall = Collider3D::AABB(AABB3D {
+            start: Vector3::new(0.75, 0.0, -10.0),
+            end: Vector3::new(5.0, 10.0, 10.0),
+        });
+        let result =
+            controller().move_capsule(&capsule(), &Vector3::new(1.0, 0.0, 1.0), &[floor(), wall]);
+        assert!(result.hit_wall);
+        assert!(result.shape.start.x <= 0.25 + 1e-4);
+        assert!((result.shape.start.z - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn steps_up_ledge_test() {
+        let ledge = Collider3D::AABB(AABB3D {
+            start: Vector3::new(0.75, 0.0, -10.0),
+            end: Vector3::new(5.0, 0.3, 10.0),
+        });
+        let result =
+            controller().move_capsule(&capsule(), &Vector3::new(1.0, 0.0, 0.0), &[floor(), ledge]);
+        assert!(result.grounded);
+        assert!((result.shape.start.x - 1.0).abs() < 1e-4);
+        assert!((result.shape.start.y - 0.8).abs() < 1e-4);
+    }
+
+    #[test]
+    fn walkable_wall_test() {
+        let wall = Collider3D::AABB(AABB3D {
+            start: Vector3::new(0.75, 0.0, -10.0),
+            end: Vector3::new(5.0, 10.0, 10.0),
+        });
+        for &max_slope in &[core::f32::consts::FRAC_PI_2, core::f32::consts::PI] {
+            let controller = CharacterController {
+                max_slope,
+                ..controller()
+            };
+            let result =
+                controller.move_capsule(&capsule(), &Vector3::new(1.0, 0.0, 0.0), &[floor(), wall]);
+            assert!(result.shape.start.iter().all(|v| v.is_finite()));
+            assert!(result.shape.start.x <= 0.25 + 1e-4);
+        }
+    }
+}
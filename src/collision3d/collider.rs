@@ -0,0 +1,234 @@
+use super::*;
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum Collider3D<N: PhysicsScalar> {
+    AABB(AxisAlignedBoundingBox<N>),
+    Sphere(Sphere<N>),
+    Capsule(Capsule<N>),
+    Triangle(Triangle<N>),
+    Plane(Plane<N>),
+}
+
+impl<N: FloatingPhysicsScalar> Collider3D<N> {
+    /// Returns a resolution that pushes `self` away from `other`, or `None` when
+    /// the shapes are apart or the pair has no collision routine. Triangles
+    /// are one-sided, and everything behind a plane is solid as in
+    /// `Plane::contains_point`.
+    pub fn get_collision(
+        &self,
+        other: &Collider3D<N>,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        match other {
+            Collider3D::AABB(b) => self.get_aabb_collision(b),
+            Collider3D::Sphere(b) => self.get_sphere_collision(b),
+            Collider3D::Capsule(b) => self.get_capsule_collision(b),
+            Collider3D::Triangle(b) => match self {
+                Collider3D::AABB(a) => a.get_triangle_collision(b, FaceCulling::OneSided),
+                Collider3D::Sphere(a) => a.get_triangle_collision(b, FaceCulling::OneSided),
+                Collider3D::Capsule(a) => a.get_triangle_collision(b, FaceCulling::OneSided),
+                Collider3D::Triangle(a) => a.get_triangle_collision(b),
+                Collider3D::Plane(_) => None,
+            },
+            Collider3D::Plane(b) => match self {
+                Collider3D::AABB(a) => a.get_plane_collision(b, FaceCulling::HalfSpace),
+                Collider3D::Sphere(a) => a.get_plane_collision(b, FaceCulling::HalfSpace),
+                Collider3D::Capsule(a) => a.get_plane_collision(b, FaceCulling::HalfSpace),
+                Collider3D::Triangle(_) | Collider3D::Plane(_) => None,
+            },
+        }
+    }
+    pub fn get_aabb_collision(
+        &self,
+        aabb: &AxisAlignedBoundingBox<N>,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        match self {
+            Collider3D::AABB(a) => a.get_aabb_collision(aabb),
+            Collider3D::Sphere(a) => aabb.get_sphere_collision(a).map(|r| r.flip()),
+            Collider3D::Capsule(a) => aabb.get_capsule_collision(a).map(|r| r.flip()),
+            Collider3D::Triangle(a) => aabb
+                .get_triangle_collision(a, FaceCulling::OneSided)
+                .map(|r| r.flip()),
+            Collider3D::Plane(a) => aabb
+                .get_plane_collision(a, FaceCulling::HalfSpace)
+                .map(|r| r.flip()),
+        }
+    }
+    pub fn get_sphere_collision(
+        &self,
+        sphere: &Sphere<N>,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        match self {
+            Collider3D::AABB(a) => a.get_sphere_collision(sphere),
+            Collider3D::Sphere(a) => a.get_sphere_collision(sphere),
+            Collider3D::Capsule(a) => sphere.get_capsule_collision(a).map(|r| r.flip()),
+            Collider3D::Triangle(a) => sphere
+                .get_triangle_collision(a, FaceCulling::OneSided)
+                .map(|r| r.flip()),
+            Collider3D::Plane(a) => sphere
+                .get_plane_collision(a, FaceCulling::HalfSpace)
+                .map(|r| r.flip()),
+        }
+    }
+    pub fn get_capsule_collision(
+        &self,
+        capsule: &Capsule<N>,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        match self {
+            Collider3D::AABB(a) => a.get_capsule_collision(capsule),
+            Collider3D::Sphere(a) => a.get_capsule_collision(capsule),
+            Collider3D::Capsule(a) => a.get_capsule_collision(capsule),
+            Collider3D::Triangle(a) => capsule
+                .get_triangle_collision(a, FaceCulling::OneSided)
+                .map(|r| r.flip()),
+            Collider3D::Plane(a) => capsule
+                .get_plane_collision(a, FaceCulling::HalfSpace)
+                .map(|r| r.flip()),
+        }
+    }
+    pub fn contains_point(&self, point: &Vector3<N>) -> bool {
+        match self {
+            Collider3D::AABB(a) => a.contains_point(point),
+            Collider3D::Sphere(a) => a.contains_point(point),
+            Collider3D::Capsule(a) => a.contains_point(point),
+            Collider3D::Triangle(_) => false,
+            Collider3D::Plane(a) => a.contains_point(point),
+        }
+    }
+    pub fn project_point(&self, point: &Vector3<N>) -> PointProjection<Vector3<N>> {
+        match self {
+            Collider3D::AABB(a) => a.project_point(point),
+            Collider3D::Sphere(a) => a.project_point(point),
+            Collider3D::Capsule(a) => a.project_point(point),
+            Collider3D::Triangle(a) => PointProjection {
+                point: a.closest_point(point).0,
+                is_inside: false,
+            },
+            Collider3D::Plane(a) => a.project_point(point),
+        }
+    }
+    /// The collider as a convex shape, which everything but a plane is.
+    pub(crate) fn support_map(&self) -> Option<&dyn SupportMap<N>> {
+        match self {
+            Collider3D::AABB(a) => Some(a),
+            Collider3D::Sphere(a) => Some(a),
+            Collider3D::Capsule(a) => Some(a),
+            Collider3D::Triangle(a) => Some(a),
+            Collider3D::Plane(_) => None,
+        }
+    }
+    /// Sweeps `self` along `displacement` and returns where it first comes
+    /// within `skin` of `other`, as `shape_cast` does. Everything behind a
+    /// plane is solid, and planes never hit each other.
+    pub fn shape_cast(
+        &self,
+        displacement: &Vector3<N>,
+        other: &Collider3D<N>,
+        skin: N,
+    ) -> Option<ShapeCastHit<N>> {
+        match (self, other) {
+            (Collider3D::Plane(_), Collider3D::Plane(_)) => None,
+            (Collider3D::Plane(a), _) => {
+                plane_shape_cast(other.support_map()?, &-displacement, a, skin).map(|hit| {
+                    ShapeCastHit {
+                        point1: hit.point2,
+                        point2: hit.point1,
+                        normal: -hit.normal,
+                        ..hit
+                    }
+                })
+            }
+            (_, Collider3D::Plane(b)) => {
+                plane_shape_cast(self.support_map()?, displacement, b, skin)
+            }
+            _ => shape_cast(
+                self.support_map()?,
+                displacement,
+                other.support_map()?,
+                skin,
+            ),
+        }
+    }
+}
+
+/// Planes are unbounded and report no mass.
+impl<N: FloatingPhysicsScalar> MassProperties<N> for Collider3D<N> {
+    type Vector = Vector3<N>;
+    type Inertia = Matrix3<N>;
+
+    fn mass_properties(&self, density: N) -> MassData<Vector3<N>, Matrix3<N>, N> {
+        match self {
+            Collider3D::AABB(a) => a.mass_properties(density),
+            Collider3D::Sphere(a) => a.mass_properties(density),
+            Collider3D::Capsule(a) => a.mass_properties(density),
+            Collider3D::Triangle(a) => a.mass_properties(density),
+            Collider3D::Plane(_) => MassData {
+                mass: N::zero(),
+                center_of_mass: Vector3::zeros(),
+                inertia: Matrix3::zeros(),
+            },
+        }
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Shape3D<N> for Collider3D<N> {
+    fn bounding_aabb(&self) -> AxisAlignedBoundingBox<N> {
+        match self {
+            Collider3D::AABB(a) => a.bounding_aabb(),
+            Collider3D::Sphere(a) => a.bounding_aabb(),
+            Collider3D::Capsule(a) => a.bounding_aabb(),
+            Collider3D::Triangle(a) => a.bounding_aabb(),
+            Collider3D::Plane(a) => a.bounding_aabb(),
+        }
+    }
+    fn bounding_sphere(&self) -> Sphere<N> {
+        match self {
+            Collider3D::AABB(a) => a.bounding_sphere(),
+            Collider3D::Sphere(a) => a.bounding_sphere(),
+            Collider3D::Capsule(a) => a.bounding_sphere(),
+            Collider3D::Triangle(a) => a.bounding_sphere(),
+            Collider3D::Plane(a) => a.bounding_sphere(),
+        }
+    }
+    fn center(&self) -> Vector3<N> {
+        match self {
+            Collider3D::AABB(a) => a.center(),
+            Collider3D::Sphere(a) => a.center(),
+            Collider3D::Capsule(a) => a.center(),
+            Collider3D::Triangle(a) => a.center(),
+            Collider3D::Plane(a) => a.center(),
+        }
+    }
+    fn translate(&self, point: &Vector3<N>) -> Self {
+        let mut c = *self;
+        c.translate_mut(point);
+        c
+    }
+    fn set_center(&self, point: &Vector3<N>) -> Self {
+        let mut c = *self;
+        c.set_center_mut(point);
+        c
+    }
+    fn translate_mut(&mut self, point: &Vector3<N>) {
+        match self {
+            Collider3D::AABB(a) => a.translate_mut(point),
+            Collider3D::Sphere(a) => a.translate_mut(point),
+            Collider3D::Capsule(a) => a.translate_mut(point),
+            Collider3D::Triangle(a) => a.translate_mut(point),
+            Collider3D::Plane(a) => a.translate_mut(point),
+        }
+    }
+    fn set_center_mut(&mut self, point: &Vector3<N>) {
+        match self {
+            Collider3D::AABB(a) => a.set_center_mut(point),
+            Collider3D::Sphere(a) => a.set_center_mut(point),
+            Collider3D::Capsule(a) => a.set_center_mut(point),
+            Collider3D::Triangle(a) => a.set_center_mut(point),
+            Collider3D::Plane(a) => a.set_center_mut(point),
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use super::*;
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+/// Which sides of a plane or triangle a shape collides with. The side a shape
+/// is on is the side its centre is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub enum FaceCulling {
+    /// Only shapes in front of the face collide, and are pushed out along its
+    /// normal until they are clear of its plane.
+    #[default]
+    OneSided,
+    /// Shapes on either side collide. Planes push them back out of the side
+    /// they are on and triangles take whichever push is shortest.
+    DoubleSided,
+    /// Everything behind the face is solid, so shapes behind it still collide
+    /// and are pushed out to the front, however deep they are. Behind a
+    /// triangle means inside the prism swept back from it along its normal.
+    HalfSpace,
+}
+
+impl FaceCulling {
+    /// Resolves a shape against a plane. `extent` is how far the shape reaches
+    /// along a unit vector.
+    pub(crate) fn plane_collision<N, F>(
+        self,
+        plane: &Plane<N>,
+        center: &Vector3<N>,
+        extent: F,
+    ) -> Option<CollisionResolution<Vector3<N>, N>>
+    where
+        N: FloatingPhysicsScalar,
+        F: Fn(&Vector3<N>) -> N,
+    {
+        let behind = plane.distance(center) < N::zero();
+        let (normal, penetration) = match self {
+            FaceCulling::OneSided if behind => return None,
+            FaceCulling::DoubleSided if behind => (-plane.normal, extent(&plane.normal) + plane.d),
+            _ => (plane.normal, extent(&-plane.normal) - plane.d),
+        };
+        if penetration < N::zero() {
+            return None;
+        }
+        Some(CollisionResolution {
+            normal,
+            penetration,
+        })
+    }
+}
+
+/// A triangle swept back along its normal by `back`, the solid a triangle
+/// stands for under `FaceCulling::HalfSpace`.
+struct Prism<'a, N: FloatingPhysicsScalar> {
+    triangle: &'a Triangle<N>,
+    back: Vector3<N>,
+}
+
+impl<N: FloatingPhysicsScalar> SupportMap<N> for Prism<'_, N> {
+    fn support_point(&self, direction: &Vector3<N>) -> Vector3<N> {
+        let point = self.triangle.support_point(direction);
+        if self.back.dot(direction) > N::zero() {
+            point + self.back
+        } else {
+            point
+        }
+    }
+}
+
+/// Resolves a convex shape against a triangle under `FaceCulling::HalfSpace`.
+/// The shape collides when it reaches into the prism behind the triangle and
+/// is pushed out to the front of the triangle's plane.
+pub(crate) fn prism_collision<N, S, F>(
+    triangle: &Triangle<N>,
+    shape: &S,
+    center: &Vector3<N>,
+    extent: F,
+) -> Option<CollisionResolution<Vector3<N>, N>>
+where
+    N: FloatingPhysicsScalar,
+    S: SupportMap<N> + ?Sized,
+    F: Fn(&Vector3<N>) -> N,
+{
+    let plane = Plane::from(triangle);
+    let res = FaceCulling::HalfSpace.plane_collision(&plane, center, extent)?;
+    // Twice as deep as the shape reaches behind the plane, so the far end of
+    // the prism is clear of it.
+    let prism = Prism {
+        triangle,
+        back: plane.normal * (res.penetration * -N::from_f64(2.0).unwrap()),
+    };
+    match closest_points(shape, &prism) {
+        Some(_) => None,
+        None => Some(res),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plane_collision_test() {
+        let plane = Plane::from_point(&Vector3::z(), &Vector3::zeros());
+        let sphere = |z: f64| Sphere {
+            center: Vector3::new(0f64, 0f64, z),
+            radius: 1f64,
+        };
+        let collide = |culling: FaceCulling, z: f64| {
+            culling
+                .plane_collision(&plane, &sphere(z).center, |d| sphere(z).extent(d))
+                .map(|r| (r.normal.z, r.penetration))
+        };
+
+        assert_eq!(
+            collide(FaceCulling::OneSided, 0.25f64),
+            Some((1f64, 0.75f64))
+        );
+        assert_eq!(collide(FaceCulling::OneSided, -0.25f64), None);
+        assert_eq!(collide(FaceCulling::OneSided, 1.5f64), None);
+
+        assert_eq!(
+            collide(FaceCulling::DoubleSided, 0.25f64),
+            Some((1f64, 0.75f64))
+        );
+        assert_eq!(
+            collide(FaceCulling::DoubleSided, -0.25f64),
+            Some((-1f64, 0.75f64))
+        );
+        assert_eq!(collide(FaceCulling::DoubleSided, -1.5f64), None);
+
+        assert_eq!(
+            collide(FaceCulling::HalfSpace, -0.25f64),
+            Some((1f64, 1.25f64))
+        );
+        assert_eq!(collide(FaceCulling::HalfSpace, -5f64), Some((1f64, 6f64)));
+        assert_eq!(collide(FaceCulling::HalfSpace, 1.5f64), None);
+    }
+
+    #[test]
+    fn triangle_collision_test() {
+        let triangle = Triangle {
+            point1: Vector3::new(-2f64, -2f64, 0f64),
+            point2: Vector3::new(2f64, -2f64, 0f64),
+            point3: Vector3::new(0f64, 2f64, 0f64),
+        };
+        let sphere = |z: f64| Sphere {
+            center: Vector3::new(0f64, 0f64, z),
+            radius: 1f64,
+        };
+
+        let res = sphere(-0.25f64)
+            .get_triangle_collision(&triangle, FaceCulling::HalfSpace)
+            .unwrap();
+        assert_eq!(res.normal, Vector3::z());
+        assert!((res.penetration - 1.25f64).abs() < 1e-9);
+
+        // Everything in the prism behind the triangle is solid, however deep.
+        let res = sphere(-5f64)
+            .get_triangle_collision(&triangle, FaceCulling::HalfSpace)
+            .unwrap();
+        assert_eq!(res.normal, Vector3::z());
+        assert!((res.penetration - 6f64).abs() < 1e-9);
+        let behind = Vector3::new(0f64, 0f64, -5f64);
+        let res = AxisAlignedBoundingBox {
+            start: behind - Vector3::repeat(0.5f64),
+            end: behind + Vector3::repeat(0.5f64),
+        }
+        .get_triangle_collision(&triangle, FaceCulling::HalfSpace)
+        .unwrap();
+        assert!((res.penetration - 5.5f64).abs() < 1e-9);
+        let res = Capsule {
+            start: behind - Vector3::x(),
+            end: behind + Vector3::x(),
+            radius: 0.5f64,
+        }
+        .get_triangle_collision(&triangle, FaceCulling::HalfSpace)
+        .unwrap();
+        assert!((res.penetration - 5.5f64).abs() < 1e-9);
+        let res = Cylinder {
+            center: behind,
+            half_height: 0.5f64,
+            radius: 0.5f64,
+            axis: Axis::Z,
+        }
+        .get_triangle_collision(&triangle, FaceCulling::HalfSpace)
+        .unwrap();
+        assert!((res.penetration - 5.5f64).abs() < 1e-9);
+
+        // Beside the prism or in front of the triangle is empty.
+        let beside = Sphere {
+            center: Vector3::new(5f64, 0f64, -5f64),
+            radius: 1f64,
+        };
+        assert!(beside
+            .get_triangle_collision(&triangle, FaceCulling::HalfSpace)
+            .is_none());
+        assert!(sphere(1.5f64)
+            .get_triangle_collision(&triangle, FaceCulling::HalfSpace)
+            .is_none());
+    }
+}
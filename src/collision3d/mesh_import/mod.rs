@@ -0,0 +1,99 @@
+//! Loaders that turn Wavefront OBJ and STL files into triangle colliders.
+
+pub mod obj;
+pub use obj::*;
+
+pub mod stl;
+pub use stl::*;
+
+use super::*;
+
+use core::fmt;
+use std::io;
+use std::string::{String, ToString};
+
+#[derive(Debug)]
+pub enum MeshImportError {
+    Io(io::Error),
+    /// A malformed line in a text file. Lines are numbered from 1.
+    Line {
+        line: usize,
+        message: String,
+    },
+    /// Malformed data in a binary file at a byte offset.
+    Offset {
+        offset: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for MeshImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MeshImportError::Io(e) => write!(f, "{}", e),
+            MeshImportError::Line { line, message } => write!(f, "line {}: {}", line, message),
+            MeshImportError::Offset { offset, message } => {
+                write!(f, "byte {}: {}", offset, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MeshImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MeshImportError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for MeshImportError {
+    fn from(e: io::Error) -> Self {
+        MeshImportError::Io(e)
+    }
+}
+
+/// Splits a polygon into a fan of triangles around its first vertex, dropping
+/// any triangle with no area.
+pub(crate) fn triangulate_fan<N: FloatingPhysicsScalar>(
+    polygon: &[Vector3<N>],
+    triangles: &mut Vec<Triangle<N>>,
+) {
+    if polygon.len() < 3 {
+        return;
+    }
+    for i in 1..(polygon.len() - 1) {
+        let triangle = Triangle {
+            point1: polygon[0],
+            point2: polygon[i],
+            point3: polygon[i + 1],
+        };
+        if !is_degenerate(&triangle) {
+            triangles.push(triangle);
+        }
+    }
+}
+
+fn is_degenerate<N: FloatingPhysicsScalar>(triangle: &Triangle<N>) -> bool {
+    let cross = (triangle.point2 - triangle.point1).cross(&(triangle.point3 - triangle.point1));
+    is_zero(cross.magnitude_squared())
+}
+
+pub(crate) fn parse_number<N: FloatingPhysicsScalar>(
+    token: Option<&str>,
+    line: usize,
+) -> Result<N, MeshImportError> {
+    let token = token.ok_or_else(|| MeshImportError::Line {
+        line,
+        message: "expected a number".to_string(),
+    })?;
+    token
+        .parse::<f64>()
+        .ok()
+        .and_then(N::from_f64)
+        .ok_or_else(|| MeshImportError::Line {
+            line,
+            message: format!("invalid number '{}'", token),
+        })
+}
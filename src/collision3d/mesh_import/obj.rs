@@ -0,0 +1,108 @@
+use super::*;
+
+use std::io::BufRead;
+
+/// Reads the faces of a Wavefront OBJ file. Polygons with more than three
+/// vertices are split into triangle fans and triangles with no area are
+/// dropped. Statements other than `v` and `f` are ignored.
+pub fn read_obj<N: FloatingPhysicsScalar, R: BufRead>(
+    reader: R,
+) -> Result<Vec<Triangle<N>>, MeshImportError> {
+    let mut vertices: Vec<Vector3<N>> = Vec::new();
+    let mut triangles = Vec::new();
+    let mut polygon = Vec::new();
+
+    for (index, text) in reader.lines().enumerate() {
+        let line = index + 1;
+        let text = text?;
+        let text = match text.find('#') {
+            Some(comment) => &text[..comment],
+            None => &text[..],
+        };
+        let mut tokens = text.split_whitespace();
+        match tokens.next() {
+            Some("v") => {
+                let x = parse_number(tokens.next(), line)?;
+                let y = parse_number(tokens.next(), line)?;
+                let z = parse_number(tokens.next(), line)?;
+                vertices.push(Vector3::new(x, y, z));
+            }
+            Some("f") => {
+                polygon.clear();
+                for token in tokens {
+                    let vertex = parse_index(token, vertices.len(), line)?;
+                    polygon.push(vertices[vertex]);
+                }
+                if polygon.len() < 3 {
+                    return Err(MeshImportError::Line {
+                        line,
+                        message: "a face needs at least three vertices".to_string(),
+                    });
+                }
+                triangulate_fan(&polygon, &mut triangles);
+            }
+            _ => (),
+        }
+    }
+    Ok(triangles)
+}
+
+/// Resolves the position index of a face vertex such as `3`, `3/1`, `3//2`
+/// or `-1` into a zero based index.
+fn parse_index(token: &str, count: usize, line: usize) -> Result<usize, MeshImportError> {
+    let position = token.split('/').next().unwrap_or("");
+    let error = |message: String| MeshImportError::Line { line, message };
+    let index: isize = position
+        .parse()
+        .map_err(|_| error(format!("invalid vertex index '{}'", token)))?;
+    let resolved = if index < 0 {
+        count as isize + index
+    } else {
+        index - 1
+    };
+    if resolved < 0 || resolved as usize >= count {
+        return Err(error(format!(
+            "vertex index {} is out of range for {} vertices",
+            index, count
+        )));
+    }
+    Ok(resolved as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_obj_test() {
+        let text = "# a unit quad and a degenerate triangle
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vn 0 0 1
+f 1//1 2//1 3//1 4//1
+f -4 -3 -4
+";
+        let triangles = read_obj::<f32, _>(text.as_bytes()).unwrap();
+
+        assert_eq!(triangles.len(), 2);
+        assert_eq!(triangles[1].point3, Vector3::new(0f32, 1f32, 0f32));
+        assert_eq!(triangles[0].normal(), Vector3::z());
+    }
+
+    #[test]
+    fn read_obj_error_test() {
+        let text = "v 0 0 0\nv 1 0 0\nf 1 2 3\n";
+        match read_obj::<f32, _>(text.as_bytes()) {
+            Err(MeshImportError::Line { line, .. }) => assert_eq!(line, 3),
+            r => panic!("Unexpected result {:?}", r),
+        }
+
+        let text = "v 0 zero 0\n";
+        match read_obj::<f32, _>(text.as_bytes()) {
+            Err(MeshImportError::Line { line, .. }) => assert_eq!(line, 1),
+            r => panic!("Unexpected result {:?}", r),
+        }
+    }
+}
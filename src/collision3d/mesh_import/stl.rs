@@ -0,0 +1,212 @@
+use super::*;
+
+use core::convert::TryInto;
+use std::io::Read;
+
+const HEADER_LENGTH: usize = 80;
+const FACET_LENGTH: usize = 50;
+
+/// Reads an ASCII or binary STL file. Facets with no area are dropped and the
+/// stored facet normals are ignored in favour of the vertex winding.
+pub fn read_stl<N: FloatingPhysicsScalar, R: Read>(
+    mut reader: R,
+) -> Result<Vec<Triangle<N>>, MeshImportError> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+
+    // Binary files may also begin with "solid", so trust the facet count first.
+    let is_binary =
+        bytes.len() >= HEADER_LENGTH + 4 && binary_length(facet_count(&bytes)) == Some(bytes.len());
+    if is_binary || !bytes.starts_with(b"solid") {
+        read_binary(&bytes)
+    } else {
+        read_ascii(&bytes)
+    }
+}
+
+fn facet_count(bytes: &[u8]) -> usize {
+    u32::from_le_bytes(bytes[HEADER_LENGTH..HEADER_LENGTH + 4].try_into().unwrap()) as usize
+}
+
+/// The length of a binary file holding `count` facets, or `None` when it
+/// would not fit in memory anyway.
+fn binary_length(count: usize) -> Option<usize> {
+    count
+        .checked_mul(FACET_LENGTH)
+        .and_then(|length| length.checked_add(HEADER_LENGTH + 4))
+}
+
+fn read_binary<N: FloatingPhysicsScalar>(
+    bytes: &[u8],
+) -> Result<Vec<Triangle<N>>, MeshImportError> {
+    if bytes.len() < HEADER_LENGTH + 4 {
+        return Err(MeshImportError::Offset {
+            offset: bytes.len(),
+            message: "missing binary STL header".to_string(),
+        });
+    }
+    let count = facet_count(bytes);
+    // The count is untrusted, so check it against the data before allocating.
+    if binary_length(count).is_none_or(|length| bytes.len() < length) {
+        return Err(MeshImportError::Offset {
+            offset: bytes.len(),
+            message: format!("expected {} facets", count),
+        });
+    }
+    let mut triangles = Vec::with_capacity(count);
+    let read_vector = |offset: usize| -> Result<Vector3<N>, MeshImportError> {
+        let mut v = Vector3::zeros();
+        for i in 0..3 {
+            let start = offset + i * 4;
+            // In bounds, as the length was checked against the facet count.
+            let value = f32::from_le_bytes(bytes[start..start + 4].try_into().unwrap());
+            v[i] = N::from_f32(value).ok_or_else(|| MeshImportError::Offset {
+                offset: start,
+                message: format!("invalid number {}", value),
+            })?;
+        }
+        Ok(v)
+    };
+
+    for facet in 0..count {
+        // Skip the stored normal; the trailing two bytes are an attribute count.
+        let offset = HEADER_LENGTH + 4 + facet * FACET_LENGTH + 12;
+        let polygon = [
+            read_vector(offset)?,
+            read_vector(offset + 12)?,
+            read_vector(offset + 24)?,
+        ];
+        triangulate_fan(&polygon, &mut triangles);
+    }
+    Ok(triangles)
+}
+
+fn read_ascii<N: FloatingPhysicsScalar>(bytes: &[u8]) -> Result<Vec<Triangle<N>>, MeshImportError> {
+    let text = core::str::from_utf8(bytes).map_err(|e| MeshImportError::Offset {
+        offset: e.valid_up_to(),
+        message: "ASCII STL is not valid UTF-8".to_string(),
+    })?;
+    let mut triangles = Vec::new();
+    let mut polygon = Vec::new();
+    let mut in_loop = false;
+
+    for (index, text) in text.lines().enumerate() {
+        let line = index + 1;
+        let error = |message: &str| MeshImportError::Line {
+            line,
+            message: message.to_string(),
+        };
+        let mut tokens = text.split_whitespace();
+        match tokens.next() {
+            Some("outer") => {
+                if in_loop {
+                    return Err(error("nested 'outer loop'"));
+                }
+                in_loop = true;
+                polygon.clear();
+            }
+            Some("vertex") => {
+                if !in_loop {
+                    return Err(error("'vertex' outside of a loop"));
+                }
+                let x = parse_number(tokens.next(), line)?;
+                let y = parse_number(tokens.next(), line)?;
+                let z = parse_number(tokens.next(), line)?;
+                polygon.push(Vector3::new(x, y, z));
+            }
+            Some("endloop") => {
+                if !in_loop {
+                    return Err(error("'endloop' without 'outer loop'"));
+                }
+                if polygon.len() < 3 {
+                    return Err(error("a facet needs at least three vertices"));
+                }
+                in_loop = false;
+                triangulate_fan(&polygon, &mut triangles);
+            }
+            Some("solid") | Some("facet") | Some("endfacet") | Some("endsolid") | None => (),
+            Some(token) => return Err(error(&format!("unexpected '{}'", token))),
+        }
+    }
+    if in_loop {
+        return Err(MeshImportError::Line {
+            line: text.lines().count(),
+            message: "unterminated 'outer loop'".to_string(),
+        });
+    }
+    Ok(triangles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_ascii_test() {
+        let text = "solid test
+  facet normal 0 0 1
+    outer loop
+      vertex 0 0 0
+      vertex 1 0 0
+      vertex 0 1 0
+    endloop
+  endfacet
+  facet normal 0 0 1
+    outer loop
+      vertex 0 0 0
+      vertex 1 0 0
+      vertex 2 0 0
+    endloop
+  endfacet
+endsolid test
+";
+        let triangles = read_stl::<f64, _>(text.as_bytes()).unwrap();
+        assert_eq!(triangles.len(), 1);
+        assert_eq!(triangles[0].point2, Vector3::new(1f64, 0f64, 0f64));
+
+        let text = "solid test\nfacet normal 0 0 1\nouter loop\nvertex 0 0\n";
+        match read_stl::<f64, _>(text.as_bytes()) {
+            Err(MeshImportError::Line { line, .. }) => assert_eq!(line, 4),
+            r => panic!("Unexpected result {:?}", r),
+        }
+    }
+
+    #[test]
+    fn read_binary_test() {
+        // The header deliberately starts with "solid" like many exporters write.
+        let mut bytes = b"solid binary".to_vec();
+        bytes.resize(HEADER_LENGTH, 0);
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        for value in &[
+            0f32, 0f32, 1f32, 0f32, 0f32, 0f32, 1f32, 0f32, 0f32, 0f32, 1f32, 0f32,
+        ] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(&[0, 0]);
+
+        let triangles = read_stl::<f32, _>(&bytes[..]).unwrap();
+        assert_eq!(triangles.len(), 1);
+        assert_eq!(triangles[0].normal(), Vector3::z());
+
+        bytes.truncate(bytes.len() - 10);
+        bytes[0] = b'x';
+        match read_stl::<f32, _>(&bytes[..]) {
+            Err(MeshImportError::Offset { offset, .. }) => assert_eq!(offset, bytes.len()),
+            r => panic!("Unexpected result {:?}", r),
+        }
+    }
+
+    #[test]
+    fn read_truncated_binary_test() {
+        // A short file claiming far more facets than it holds must not
+        // allocate room for all of them.
+        let mut bytes = vec![0; HEADER_LENGTH];
+        bytes.extend_from_slice(&0x0FFF_FFFFu32.to_le_bytes());
+        bytes.extend_from_slice(&[0; 10]);
+        match read_stl::<f32, _>(&bytes[..]) {
+            Err(MeshImportError::Offset { offset, .. }) => assert_eq!(offset, bytes.len()),
+            r => panic!("Unexpected result {:?}", r),
+        }
+        assert_eq!(binary_length(usize::MAX), None);
+    }
+}
@@ -1,4 +1,42 @@
 pub mod shapes;
 pub use shapes::*;
 
+pub mod collider;
+pub use collider::*;
+
+pub mod support_map;
+pub use support_map::*;
+
+pub mod face_culling;
+pub use face_culling::*;
+
+pub mod sdf;
+pub use sdf::*;
+
+pub mod character_controller;
+pub use character_controller::*;
+
+#[cfg(feature = "alloc")]
+pub mod pairs;
+#[cfg(feature = "alloc")]
+pub use pairs::*;
+
+#[cfg(feature = "alloc")]
+pub mod tessellation;
+#[cfg(feature = "alloc")]
+pub use tessellation::*;
+
+#[cfg(feature = "alloc")]
+pub mod batch;
+#[cfg(feature = "alloc")]
+pub use batch::*;
+
+#[cfg(feature = "alloc")]
+pub mod octree;
+#[cfg(feature = "alloc")]
+pub use octree::*;
+
+#[cfg(feature = "mesh-import")]
+pub mod mesh_import;
+
 use super::*;
\ No newline at end of file
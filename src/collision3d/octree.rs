@@ -0,0 +1,197 @@
+use super::*;
+use crate::spatial_tree::*;
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+/// A static scene index that splits space into eighths around the items'
+/// bounding boxes. Queries return the items whose boxes pass the test, so the
+/// caller still runs the exact collision routine on each.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct Octree<N: PhysicsScalar, T> {
+    tree: Tree<AxisAlignedBoundingBox<N>, T>,
+}
+
+impl<N: FloatingPhysicsScalar, T: Shape3D<N>> Octree<N, T> {
+    /// An empty tree covering `bounds`. A node splits once it holds more than
+    /// `leaf_capacity` items, up to `max_depth` levels below the root.
+    pub fn new(bounds: &AxisAlignedBoundingBox<N>, max_depth: usize, leaf_capacity: usize) -> Self {
+        Octree {
+            tree: Tree::new(*bounds, max_depth, leaf_capacity),
+        }
+    }
+    /// A tree fitted around all of `items`.
+    pub fn from_items<I: IntoIterator<Item = T>>(
+        items: I,
+        max_depth: usize,
+        leaf_capacity: usize,
+    ) -> Self {
+        Octree {
+            tree: Tree::build(items, max_depth, leaf_capacity, T::bounding_aabb),
+        }
+    }
+    /// Adds an item and returns its index in `items`. Items outside the tree's
+    /// bounds are still found, but are tested by every query.
+    pub fn insert(&mut self, item: T) -> usize {
+        let bounds = item.bounding_aabb();
+        self.tree.insert(item, bounds)
+    }
+    /// The items in the order they were added.
+    pub fn items(&self) -> &[T] {
+        self.tree.items()
+    }
+    pub fn bounds(&self) -> &AxisAlignedBoundingBox<N> {
+        self.tree.bounds()
+    }
+    fn collect(&self, indices: Vec<usize>) -> Vec<&T> {
+        indices.into_iter().map(|i| &self.items()[i]).collect()
+    }
+    /// The items whose boxes hold `point`.
+    pub fn query_point(&self, point: &Vector3<N>) -> Vec<&T> {
+        self.collect(self.tree.query(|b| b.contains_point(point)))
+    }
+    /// The items whose boxes overlap `aabb`.
+    pub fn query_aabb(&self, aabb: &AxisAlignedBoundingBox<N>) -> Vec<&T> {
+        self.collect(self.tree.query(|b| b.intersection(aabb).is_some()))
+    }
+    /// The items whose boxes overlap `sphere`.
+    pub fn query_sphere(&self, sphere: &Sphere<N>) -> Vec<&T> {
+        let r2 = sphere.radius * sphere.radius;
+        self.collect(
+            self.tree.query(|b| {
+                (b.closest_point(&sphere.center) - sphere.center).magnitude_squared() <= r2
+            }),
+        )
+    }
+    /// The items whose boxes may be inside `frustum`, for culling.
+    pub fn query_frustum(&self, frustum: &Frustum<N>) -> Vec<&T> {
+        self.collect(self.tree.query(|b| frustum.intersects_aabb(b)))
+    }
+    /// The items whose boxes the ray enters within `max_distance`, nearest
+    /// first, with the distance to each box.
+    pub fn query_ray(&self, ray: &Ray<N>, max_distance: N) -> Vec<(&T, N)> {
+        let entry =
+            |b: &AxisAlignedBoundingBox<N>| ray.intersects_aabb(b).filter(|d| *d <= max_distance);
+        let mut hits: Vec<_> = self
+            .tree
+            .query(|b| entry(b).is_some())
+            .into_iter()
+            .map(|i| {
+                let item = &self.items()[i];
+                (item, entry(&item.bounding_aabb()).unwrap())
+            })
+            .collect();
+        hits.sort_by(|a, b| n_ordering(a.1, b.1));
+        hits
+    }
+    /// The item whose box is closest to `point`, with the distance to it.
+    pub fn nearest(&self, point: &Vector3<N>) -> Option<(&T, N)> {
+        self.nearest_by(point, |item| {
+            (item.bounding_aabb().closest_point(point) - point).magnitude()
+        })
+    }
+    /// The item closest to `point` by `distance`, such as the distance to the
+    /// item's surface. `distance` must never be less than the distance to the
+    /// item's box, which is what lets whole branches be skipped.
+    pub fn nearest_by<F: Fn(&T) -> N>(&self, point: &Vector3<N>, distance: F) -> Option<(&T, N)> {
+        self.tree
+            .nearest(point, |i| distance(&self.items()[i]))
+            .map(|(i, d)| (&self.items()[i], d))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A row of unit spheres along x, one every two units.
+    fn spheres() -> Octree<f64, Sphere<f64>> {
+        Octree::from_items(
+            (0..32).map(|i| Sphere {
+                center: Vector3::new(i as f64 * 2f64, 0f64, 0f64),
+                radius: 1f64,
+            }),
+            4,
+            2,
+        )
+    }
+
+    fn centers(found: Vec<&Sphere<f64>>) -> Vec<f64> {
+        found.into_iter().map(|s| s.center.x).collect()
+    }
+
+    #[test]
+    fn query_test() {
+        let tree = spheres();
+        assert_eq!(
+            centers(tree.query_point(&Vector3::new(3f64, 0f64, 0f64))),
+            vec![2f64, 4f64]
+        );
+        let aabb = AxisAlignedBoundingBox {
+            start: Vector3::new(9.5f64, -1f64, -1f64),
+            end: Vector3::new(14.5f64, 1f64, 1f64),
+        };
+        assert_eq!(centers(tree.query_aabb(&aabb)), vec![10f64, 12f64, 14f64]);
+        let sphere = Sphere {
+            center: Vector3::new(21f64, 1.5f64, 0f64),
+            radius: 1f64,
+        };
+        assert_eq!(centers(tree.query_sphere(&sphere)), vec![20f64, 22f64]);
+        assert!(tree
+            .query_sphere(&sphere.translate(&Vector3::new(0f64, 1f64, 0f64)))
+            .is_empty());
+
+        let ray = Ray::new(&Vector3::new(61f64, 0f64, 0f64), &-Vector3::x());
+        let hits: Vec<_> = tree
+            .query_ray(&ray, 4f64)
+            .into_iter()
+            .map(|(s, d)| (s.center.x, d))
+            .collect();
+        assert_eq!(
+            hits,
+            vec![(60f64, 0f64), (62f64, 0f64), (58f64, 2f64), (56f64, 4f64)]
+        );
+    }
+    #[test]
+    fn frustum_test() {
+        let projection = Orthographic3::new(-3.5f64, 3.5f64, -1f64, 1f64, 0f64, 10f64);
+        let view = Isometry3::look_at_rh(
+            &Point3::new(10f64, 0f64, 5f64),
+            &Point3::new(10f64, 0f64, 0f64),
+            &Vector3::y(),
+        );
+        let frustum =
+            Frustum::from_view_projection(&(projection.as_matrix() * view.to_homogeneous()));
+        assert_eq!(
+            centers(spheres().query_frustum(&frustum)),
+            vec![6f64, 8f64, 10f64, 12f64, 14f64]
+        );
+    }
+    #[test]
+    fn nearest_test() {
+        let mut tree = spheres();
+        let (nearest, distance) = tree.nearest(&Vector3::new(6.5f64, 3f64, 0f64)).unwrap();
+        assert_eq!((nearest.center.x, distance), (6f64, 2f64));
+
+        let (nearest, distance) = tree
+            .nearest_by(&Vector3::new(6.5f64, 3f64, 0f64), |s| {
+                (s.center - Vector3::new(6.5f64, 3f64, 0f64)).magnitude() - s.radius
+            })
+            .unwrap();
+        assert_eq!(nearest.center.x, 6f64);
+        assert!((distance - (9.25f64.sqrt() - 1f64)).abs() < 1e-9);
+
+        // Outside the fitted bounds.
+        let far = Sphere {
+            center: Vector3::new(-20f64, 0f64, 0f64),
+            radius: 1f64,
+        };
+        assert_eq!(tree.insert(far), 32);
+        assert_eq!(
+            tree.nearest(&Vector3::new(-30f64, 0f64, 0f64)).unwrap().0,
+            &far
+        );
+        assert_eq!(tree.query_point(&far.center), vec![&far]);
+    }
+}
@@ -0,0 +1,111 @@
+use super::*;
+use crate::broad_phase::*;
+
+/// Pairs whose bounding boxes touch, as `(a, b)` with `a < b`, in ascending order.
+pub fn find_pairs<N: FloatingPhysicsScalar>(shapes: &[Collider3D<N>]) -> Vec<(usize, usize)> {
+    let bounds: Vec<_> = shapes.iter().map(|s| s.bounding_aabb().min_max()).collect();
+    sweep_pairs(&extents(&bounds), |a, b| touches(&bounds[a], &bounds[b]))
+}
+
+/// Runs `get_collision` on every pair from `find_pairs`. The resolution of each
+/// result pushes shape `a` away from shape `b`.
+pub fn find_collisions<N: FloatingPhysicsScalar>(
+    shapes: &[Collider3D<N>],
+) -> Vec<PairCollision<Vector3<N>, N>> {
+    narrow_phase(&find_pairs(shapes), |a, b| {
+        shapes[a].get_collision(&shapes[b])
+    })
+}
+
+/// The parallel version of `find_pairs`. The result is identical whatever the
+/// number of threads.
+#[cfg(feature = "rayon")]
+pub fn par_find_pairs<N>(shapes: &[Collider3D<N>]) -> Vec<(usize, usize)>
+where
+    N: FloatingPhysicsScalar + Send + Sync,
+{
+    use rayon::prelude::*;
+
+    let bounds: Vec<_> = shapes
+        .par_iter()
+        .map(|s| s.bounding_aabb().min_max())
+        .collect();
+    par_sweep_pairs(&extents(&bounds), |a, b| touches(&bounds[a], &bounds[b]))
+}
+
+/// The parallel version of `find_collisions`. The result is identical whatever
+/// the number of threads.
+#[cfg(feature = "rayon")]
+pub fn par_find_collisions<N>(shapes: &[Collider3D<N>]) -> Vec<PairCollision<Vector3<N>, N>>
+where
+    N: FloatingPhysicsScalar + Send + Sync,
+{
+    par_narrow_phase(&par_find_pairs(shapes), |a, b| {
+        shapes[a].get_collision(&shapes[b])
+    })
+}
+
+fn extents<N: FloatingPhysicsScalar>(bounds: &[(Vector3<N>, Vector3<N>)]) -> Vec<(N, N)> {
+    bounds.iter().map(|(min, max)| (min.x, max.x)).collect()
+}
+
+fn touches<N: FloatingPhysicsScalar>(
+    a: &(Vector3<N>, Vector3<N>),
+    b: &(Vector3<N>, Vector3<N>),
+) -> bool {
+    (0..3).all(|i| a.0[i] <= b.1[i] && b.0[i] <= a.1[i])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shapes() -> Vec<Collider3D<f64>> {
+        let mut shapes = Vec::new();
+        for i in 0..20 {
+            let x = i as f64 * 0.75;
+            shapes.push(Collider3D::Sphere(Sphere {
+                center: Vector3::new(x, 0.0, (i % 3) as f64),
+                radius: 0.5,
+            }));
+            shapes.push(Collider3D::AABB(AxisAlignedBoundingBox {
+                start: Vector3::new(x, -1.0, 0.0),
+                end: Vector3::new(x + 0.25, -0.25, 0.5),
+            }));
+        }
+        shapes
+    }
+
+    #[test]
+    fn find_collisions_test() {
+        let shapes = shapes();
+        let mut expected = Vec::new();
+        for a in 0..shapes.len() {
+            for b in (a + 1)..shapes.len() {
+                if let Some(resolution) = shapes[a].get_collision(&shapes[b]) {
+                    expected.push(PairCollision { a, b, resolution });
+                }
+            }
+        }
+        assert!(!expected.is_empty());
+        assert_eq!(find_collisions(&shapes), expected);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_find_collisions_test() {
+        let shapes = shapes();
+        let expected = find_collisions(&shapes);
+        for threads in 1..5 {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            assert_eq!(
+                pool.install(|| par_find_pairs(&shapes)),
+                find_pairs(&shapes)
+            );
+            assert_eq!(pool.install(|| par_find_collisions(&shapes)), expected);
+        }
+    }
+}
@@ -0,0 +1,381 @@
+use super::*;
+
+/// A signed distance field: the distance from any point to a surface,
+/// negative inside it.
+pub trait Sdf3D<N: FloatingPhysicsScalar> {
+    fn signed_distance(&self, point: &Vector3<N>) -> N;
+
+    /// The direction in which the distance grows fastest, pointing out of the
+    /// surface. The default takes central differences of `signed_distance`.
+    fn gradient(&self, point: &Vector3<N>) -> Vector3<N> {
+        let step = Float::cbrt(N::epsilon()) * n_max(point.amax(), N::one());
+        let gradient = Vector3::from_fn(|i, _| {
+            let offset = Vector3::ith(i, step);
+            self.signed_distance(&(point + offset)) - self.signed_distance(&(point - offset))
+        });
+        gradient.try_normalize(N::zero()).unwrap_or_else(Vector3::x)
+    }
+}
+
+impl<N: FloatingPhysicsScalar, T: Sdf3D<N> + ?Sized> Sdf3D<N> for &T {
+    fn signed_distance(&self, point: &Vector3<N>) -> N {
+        (**self).signed_distance(point)
+    }
+    fn gradient(&self, point: &Vector3<N>) -> Vector3<N> {
+        (**self).gradient(point)
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Sdf3D<N> for Sphere<N> {
+    fn signed_distance(&self, point: &Vector3<N>) -> N {
+        (point - self.center).magnitude() - self.radius
+    }
+    fn gradient(&self, point: &Vector3<N>) -> Vector3<N> {
+        (point - self.center)
+            .try_normalize(N::zero())
+            .unwrap_or_else(Vector3::x)
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Sdf3D<N> for AxisAlignedBoundingBox<N> {
+    fn signed_distance(&self, point: &Vector3<N>) -> N {
+        let (min, max) = self.min_max();
+        let half = (max - min) * N::from_f64(0.5).unwrap();
+        let q = (point - self.center()).abs() - half;
+        let outside = q.sup(&Vector3::zeros()).magnitude();
+        outside + n_min(q.max(), N::zero())
+    }
+    fn gradient(&self, point: &Vector3<N>) -> Vector3<N> {
+        let (min, max) = self.min_max();
+        let half = (max - min) * N::from_f64(0.5).unwrap();
+        let offset = point - self.center();
+        let q = offset.abs() - half;
+        let sign = offset.map(|v| if v < N::zero() { -N::one() } else { N::one() });
+        let outside = q.sup(&Vector3::zeros());
+        if outside.iter().any(|v| *v > N::zero()) {
+            outside.component_mul(&sign).normalize()
+        } else {
+            // Inside, the nearest face is the one with the largest q.
+            let i = q.imax();
+            Vector3::ith(i, sign[i])
+        }
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Sdf3D<N> for Capsule<N> {
+    fn signed_distance(&self, point: &Vector3<N>) -> N {
+        (point - self.closest_point(point)).magnitude() - self.radius
+    }
+    fn gradient(&self, point: &Vector3<N>) -> Vector3<N> {
+        let closest = self.closest_point(point);
+        (point - closest)
+            .try_normalize(N::zero())
+            .unwrap_or_else(|| (self.project_point(point).point - closest).normalize())
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Sdf3D<N> for Cylinder<N> {
+    fn signed_distance(&self, point: &Vector3<N>) -> N {
+        let axis = self.axis.to_num();
+        let mut radial = point - self.center;
+        let along = radial[axis];
+        radial[axis] = N::zero();
+        let q = Vector2::new(
+            radial.magnitude() - self.radius,
+            Float::abs(along) - self.half_height,
+        );
+        q.sup(&Vector2::zeros()).magnitude() + n_min(q.max(), N::zero())
+    }
+    fn gradient(&self, point: &Vector3<N>) -> Vector3<N> {
+        let axis = self.axis.to_num();
+        let mut radial = point - self.center;
+        let along = radial[axis];
+        radial[axis] = N::zero();
+        let distance = radial.magnitude();
+        let q = Vector2::new(distance - self.radius, Float::abs(along) - self.half_height);
+        let outward = if is_zero(distance) {
+            Vector3::ith((axis + 1) % 3, N::one())
+        } else {
+            radial / distance
+        };
+        let up = Vector3::ith(
+            axis,
+            if along < N::zero() {
+                -N::one()
+            } else {
+                N::one()
+            },
+        );
+        if q.x > N::zero() || q.y > N::zero() {
+            let q = q.sup(&Vector2::zeros());
+            (outward * q.x + up * q.y).normalize()
+        } else if q.x > q.y {
+            outward
+        } else {
+            up
+        }
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Sdf3D<N> for Plane<N> {
+    fn signed_distance(&self, point: &Vector3<N>) -> N {
+        self.distance(point)
+    }
+    fn gradient(&self, _point: &Vector3<N>) -> Vector3<N> {
+        self.normal
+    }
+}
+
+/// Everything inside either field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Union<A, B> {
+    pub a: A,
+    pub b: B,
+}
+
+impl<A, B> Union<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Union { a, b }
+    }
+}
+
+impl<N: FloatingPhysicsScalar, A: Sdf3D<N>, B: Sdf3D<N>> Sdf3D<N> for Union<A, B> {
+    fn signed_distance(&self, point: &Vector3<N>) -> N {
+        n_min(self.a.signed_distance(point), self.b.signed_distance(point))
+    }
+    fn gradient(&self, point: &Vector3<N>) -> Vector3<N> {
+        if self.a.signed_distance(point) <= self.b.signed_distance(point) {
+            self.a.gradient(point)
+        } else {
+            self.b.gradient(point)
+        }
+    }
+}
+
+/// Everything inside both fields.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Intersection<A, B> {
+    pub a: A,
+    pub b: B,
+}
+
+impl<A, B> Intersection<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Intersection { a, b }
+    }
+}
+
+impl<N: FloatingPhysicsScalar, A: Sdf3D<N>, B: Sdf3D<N>> Sdf3D<N> for Intersection<A, B> {
+    fn signed_distance(&self, point: &Vector3<N>) -> N {
+        n_max(self.a.signed_distance(point), self.b.signed_distance(point))
+    }
+    fn gradient(&self, point: &Vector3<N>) -> Vector3<N> {
+        if self.a.signed_distance(point) >= self.b.signed_distance(point) {
+            self.a.gradient(point)
+        } else {
+            self.b.gradient(point)
+        }
+    }
+}
+
+/// Everything inside `a` but not inside `b`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Difference<A, B> {
+    pub a: A,
+    pub b: B,
+}
+
+impl<A, B> Difference<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Difference { a, b }
+    }
+}
+
+impl<N: FloatingPhysicsScalar, A: Sdf3D<N>, B: Sdf3D<N>> Sdf3D<N> for Difference<A, B> {
+    fn signed_distance(&self, point: &Vector3<N>) -> N {
+        n_max(
+            self.a.signed_distance(point),
+            -self.b.signed_distance(point),
+        )
+    }
+    fn gradient(&self, point: &Vector3<N>) -> Vector3<N> {
+        if self.a.signed_distance(point) >= -self.b.signed_distance(point) {
+            self.a.gradient(point)
+        } else {
+            -self.b.gradient(point)
+        }
+    }
+}
+
+/// A union that rounds off the seam where the fields meet, blending over a
+/// distance of about `k`. The sign of `k` is ignored, and zero gives the plain
+/// `Union`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmoothUnion<A, B, N> {
+    pub a: A,
+    pub b: B,
+    pub k: N,
+}
+
+impl<A, B, N> SmoothUnion<A, B, N> {
+    pub fn new(a: A, b: B, k: N) -> Self {
+        SmoothUnion { a, b, k }
+    }
+}
+
+impl<N: FloatingPhysicsScalar, A: Sdf3D<N>, B: Sdf3D<N>> SmoothUnion<A, B, N> {
+    /// Both distances at `point` and how much of `a` goes into the blend,
+    /// from zero to one.
+    fn blend(&self, point: &Vector3<N>) -> (N, N, N) {
+        let a = self.a.signed_distance(point);
+        let b = self.b.signed_distance(point);
+        let half = N::from_f64(0.5).unwrap();
+        let k = Float::abs(self.k);
+        let h = if is_zero(k) {
+            if a <= b {
+                N::one()
+            } else {
+                N::zero()
+            }
+        } else {
+            num_traits::clamp(half + half * (b - a) / k, N::zero(), N::one())
+        };
+        (a, b, h)
+    }
+}
+
+impl<N: FloatingPhysicsScalar, A: Sdf3D<N>, B: Sdf3D<N>> Sdf3D<N> for SmoothUnion<A, B, N> {
+    fn signed_distance(&self, point: &Vector3<N>) -> N {
+        let (a, b, h) = self.blend(point);
+        // Weighting both ends keeps a sharp union exact.
+        a * h + b * (N::one() - h) - Float::abs(self.k) * h * (N::one() - h)
+    }
+    fn gradient(&self, point: &Vector3<N>) -> Vector3<N> {
+        // The terms from the derivative of the blend cancel out.
+        let (_, _, h) = self.blend(point);
+        let gradient = self.b.gradient(point) * (N::one() - h) + self.a.gradient(point) * h;
+        gradient.try_normalize(N::zero()).unwrap_or_else(Vector3::x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shape_distance_test() {
+        let point = Vector3::new(3f64, 0.5f64, 0f64);
+        let sphere = Sphere {
+            center: Vector3::zeros(),
+            radius: 1f64,
+        };
+        let aabb = AxisAlignedBoundingBox {
+            start: Vector3::new(-1f64, -1f64, -1f64),
+            end: Vector3::new(1f64, 1f64, 1f64),
+        };
+        let capsule = Capsule {
+            start: Vector3::new(0f64, -1f64, 0f64),
+            end: Vector3::new(0f64, 1f64, 0f64),
+            radius: 0.5f64,
+        };
+        let cylinder = Cylinder {
+            center: Vector3::zeros(),
+            half_height: 1f64,
+            radius: 1f64,
+            axis: Axis::Y,
+        };
+        let plane = Plane::from_point(&Vector3::x(), &Vector3::new(1f64, 0f64, 0f64));
+
+        assert!((sphere.signed_distance(&point) - (9.25f64.sqrt() - 1f64)).abs() < 1e-9);
+        assert_eq!(aabb.signed_distance(&point), 2f64);
+        assert_eq!(aabb.gradient(&point), Vector3::x());
+        assert_eq!(capsule.signed_distance(&point), 2.5f64);
+        assert_eq!(cylinder.signed_distance(&point), 2f64);
+        assert_eq!(plane.signed_distance(&point), 2f64);
+
+        let inside = Vector3::new(0.25f64, 0.5f64, 0f64);
+        assert_eq!(aabb.signed_distance(&inside), -0.5f64);
+        assert_eq!(aabb.gradient(&inside), Vector3::y());
+        assert_eq!(capsule.signed_distance(&inside), -0.25f64);
+        assert_eq!(cylinder.signed_distance(&inside), -0.5f64);
+        assert_eq!(cylinder.gradient(&inside), Vector3::y());
+
+        let corner = Vector3::new(2f64, 2f64, 1f64);
+        assert!((aabb.signed_distance(&corner) - 2f64.sqrt()).abs() < 1e-9);
+        let gradient = Vector3::new(1f64, 1f64, 0f64).normalize();
+        assert!((aabb.gradient(&corner) - gradient).magnitude() < 1e-9);
+        assert!((Sdf3D::gradient(&Union::new(aabb, aabb), &corner) - gradient).magnitude() < 1e-6);
+    }
+    #[test]
+    fn csg_test() {
+        let a = Sphere {
+            center: Vector3::new(-1f64, 0f64, 0f64),
+            radius: 1.5f64,
+        };
+        let b = Sphere {
+            center: Vector3::new(1f64, 0f64, 0f64),
+            radius: 1.5f64,
+        };
+        let left = Vector3::new(-2f64, 0f64, 0f64);
+        let middle = Vector3::zeros();
+
+        assert_eq!(Union::new(a, b).signed_distance(&left), -0.5f64);
+        assert_eq!(Intersection::new(a, b).signed_distance(&left), 1.5f64);
+        assert_eq!(Intersection::new(a, b).signed_distance(&middle), -0.5f64);
+        assert_eq!(Difference::new(a, b).signed_distance(&left), -0.5f64);
+        assert_eq!(Difference::new(a, b).signed_distance(&middle), 0.5f64);
+        assert_eq!(Difference::new(a, b).gradient(&middle), Vector3::x());
+
+        let smooth = SmoothUnion::new(a, b, 0.5f64);
+        assert_eq!(smooth.signed_distance(&left), -0.5f64);
+        assert_eq!(smooth.signed_distance(&middle), -0.625f64);
+        assert!(smooth.gradient(&middle).magnitude() > 0.99f64);
+        let negative = SmoothUnion::new(a, b, -0.5f64);
+        assert_eq!(negative.signed_distance(&middle), -0.625f64);
+        let sharp = SmoothUnion::new(a, b, 0f64);
+        assert_eq!(sharp.signed_distance(&left), -0.5f64);
+        assert_eq!(sharp.signed_distance(&middle), -0.5f64);
+
+        // The default gradient agrees with the analytic one.
+        struct Field(Sphere<f64>);
+        impl Sdf3D<f64> for Field {
+            fn signed_distance(&self, point: &Vector3<f64>) -> f64 {
+                self.0.signed_distance(point)
+            }
+        }
+        let point = Vector3::new(1f64, 2f64, 3f64);
+        assert!((Field(a).gradient(&point) - a.gradient(&point)).magnitude() < 1e-6);
+    }
+    #[test]
+    fn collision_test() {
+        let floor = Plane::from_point(&Vector3::y(), &Vector3::zeros());
+        let pillar = Cylinder {
+            center: Vector3::new(2f64, 1f64, 0f64),
+            half_height: 1f64,
+            radius: 0.5f64,
+            axis: Axis::Y,
+        };
+        let level = Union::new(floor, pillar);
+
+        let sphere = Sphere {
+            center: Vector3::new(0f64, 0.75f64, 0f64),
+            radius: 1f64,
+        };
+        let res = sphere.get_sdf_collision(&level).unwrap();
+        assert_eq!(res.normal, Vector3::y());
+        assert_eq!(res.penetration, 0.25f64);
+        assert!(sphere
+            .get_sdf_collision(&sphere.translate(&Vector3::new(0f64, 3f64, 0f64)))
+            .is_none());
+
+        // Only the far end of the capsule reaches the pillar.
+        let capsule = Capsule {
+            start: Vector3::new(-2f64, 3f64, 0f64),
+            end: Vector3::new(1.25f64, 1.5f64, 0f64),
+            radius: 0.5f64,
+        };
+        let res = capsule.get_sdf_collision(&level).unwrap();
+        assert!((res.normal + Vector3::x()).magnitude() < 1e-6);
+        assert!((res.penetration - 0.25f64).abs() < 1e-6);
+    }
+}
@@ -2,11 +2,11 @@ use super::*;
 use nalgebra::*;
 
 use crate::collision2d::AABB2D;
+use ::core::cmp::*;
+use ::core::iter::FromIterator;
+use ::core::iter::Sum;
+use ::core::ops::*;
 use arrayvec::*;
-use std::cmp::*;
-use std::iter::FromIterator;
-use std::iter::Sum;
-use std::ops::*;
 
 #[cfg(feature = "serde-serialize")]
 use serde::{Deserialize, Serialize};
@@ -42,10 +42,10 @@ impl<'a, N: FloatingPhysicsScalar> FromIterator<&'a Vector3<N>> for AxisAlignedB
         let mut end = Vector3::from_element(Bounded::min_value());
         for v in iter {
             for (s, point) in start.iter_mut().zip(v.iter()) {
-                *s = num_traits::clamp_min(*point, *s);
+                *s = n_min(*point, *s);
             }
             for (s, point) in end.iter_mut().zip(v.iter()) {
-                *s = num_traits::clamp_max(*point, *s);
+                *s = n_max(*point, *s);
             }
         }
         AxisAlignedBoundingBox { start, end }
@@ -56,6 +56,12 @@ impl<N: FloatingPhysicsScalar> Add for AxisAlignedBoundingBox<N> {
     type Output = AxisAlignedBoundingBox<N>;
 
     fn add(self, rhs: AxisAlignedBoundingBox<N>) -> Self::Output {
+        if self.is_empty() {
+            return rhs;
+        }
+        if rhs.is_empty() {
+            return self;
+        }
         let arr = [self.start, self.end, rhs.start, rhs.end];
         AxisAlignedBoundingBox {
             start: Vector3::new(
@@ -81,6 +87,9 @@ impl<N: FloatingPhysicsScalar> AddAssign for AxisAlignedBoundingBox<N> {
 impl<N: FloatingPhysicsScalar> Mul<N> for AxisAlignedBoundingBox<N> {
     type Output = Self;
     fn mul(self, rhs: N) -> Self::Output {
+        if self.is_empty() {
+            return self;
+        }
         AxisAlignedBoundingBox {
             start: self.start * rhs,
             end: self.end * rhs,
@@ -90,6 +99,9 @@ impl<N: FloatingPhysicsScalar> Mul<N> for AxisAlignedBoundingBox<N> {
 
 impl<N: FloatingPhysicsScalar> MulAssign<N> for AxisAlignedBoundingBox<N> {
     fn mul_assign(&mut self, rhs: N) {
+        if self.is_empty() {
+            return;
+        }
         self.start *= rhs;
         self.end *= rhs;
     }
@@ -105,13 +117,25 @@ impl<N: FloatingPhysicsScalar> From<AxisAlignedBoundingBox<N>> for Matrix4<N> {
 }
 
 impl<N: FloatingPhysicsScalar> AxisAlignedBoundingBox<N> {
+    pub fn volume(&self) -> N {
+        self.width() * self.height() * self.depth()
+    }
     pub fn width(&self) -> N {
+        if self.is_empty() {
+            return N::zero();
+        }
         Float::abs(self.start.x - self.end.x)
     }
     pub fn height(&self) -> N {
+        if self.is_empty() {
+            return N::zero();
+        }
         Float::abs(self.start.y - self.end.y)
     }
     pub fn depth(&self) -> N {
+        if self.is_empty() {
+            return N::zero();
+        }
         Float::abs(self.start.z - self.end.z)
     }
     pub fn half_width(&self) -> N {
@@ -123,7 +147,12 @@ impl<N: FloatingPhysicsScalar> AxisAlignedBoundingBox<N> {
     pub fn half_depth(&self) -> N {
         self.depth() * N::from_f64(0.5).unwrap()
     }
+    /// The lowest and highest corners. An empty box reports its center for
+    /// both, which `closest_point` then returns for every point.
     pub fn min_max(&self) -> (Vector3<N>, Vector3<N>) {
+        if self.is_empty() {
+            return (self.center(), self.center());
+        }
         let min_vec = Vector3::new(
             n_min(self.start.x, self.end.x),
             n_min(self.start.y, self.end.y),
@@ -144,6 +173,99 @@ impl<N: FloatingPhysicsScalar> AxisAlignedBoundingBox<N> {
             clamp(point.z, min_vec.z, max_vec.z),
         )
     }
+    pub fn contains_point(&self, point: &Vector3<N>) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        let (min_vec, max_vec) = self.min_max();
+        min_vec.iter().zip(point.iter()).all(|(a, p)| a <= p)
+            && max_vec.iter().zip(point.iter()).all(|(a, p)| p <= a)
+    }
+    pub fn project_point(&self, point: &Vector3<N>) -> PointProjection<Vector3<N>> {
+        if !self.contains_point(point) {
+            return PointProjection {
+                point: self.closest_point(point),
+                is_inside: false,
+            };
+        }
+
+        let (min_vec, max_vec) = self.min_max();
+        let to_min = point - min_vec;
+        let to_max = max_vec - point;
+        let (min_index, &min_dist) = min_component(&to_min);
+        let (max_index, &max_dist) = min_component(&to_max);
+        let mut projected = *point;
+        if min_dist < max_dist {
+            projected[min_index] = min_vec[min_index];
+        } else {
+            projected[max_index] = max_vec[max_index];
+        }
+        PointProjection {
+            point: projected,
+            is_inside: true,
+        }
+    }
+    /// A box containing nothing. It is the identity for `Add` and `Sum`, has
+    /// no extent, and stays empty when moved or expanded.
+    pub fn empty() -> Self {
+        AxisAlignedBoundingBox {
+            start: Vector3::from_element(Bounded::max_value()),
+            end: Vector3::from_element(Bounded::min_value()),
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        *self == Self::empty()
+    }
+    pub fn surface_area(&self) -> N {
+        let (w, h, d) = (self.width(), self.height(), self.depth());
+        (w * h + w * d + h * d) * N::from_f64(2.0).unwrap()
+    }
+    pub fn intersection(&self, aabb: &AxisAlignedBoundingBox<N>) -> Option<Self> {
+        if self.is_empty() || aabb.is_empty() {
+            return None;
+        }
+        let (min1, max1) = self.min_max();
+        let (min2, max2) = aabb.min_max();
+        let start = min1.zip_map(&min2, n_max);
+        let end = max1.zip_map(&max2, n_min);
+        if start.iter().zip(end.iter()).all(|(s, e)| s <= e) {
+            Some(AxisAlignedBoundingBox { start, end })
+        } else {
+            None
+        }
+    }
+    pub fn contains_aabb(&self, aabb: &AxisAlignedBoundingBox<N>) -> bool {
+        if aabb.is_empty() {
+            return true;
+        }
+        let (min_vec, max_vec) = aabb.min_max();
+        self.contains_point(&min_vec) && self.contains_point(&max_vec)
+    }
+    #[must_use]
+    pub fn expand_by(&self, margin: N) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
+        let (min_vec, max_vec) = self.min_max();
+        let margin = Vector3::from_element(margin);
+        AxisAlignedBoundingBox {
+            start: min_vec - margin,
+            end: max_vec + margin,
+        }
+    }
+    #[must_use]
+    pub fn expand_to_point(&self, point: &Vector3<N>) -> Self {
+        *self
+            + AxisAlignedBoundingBox {
+                start: *point,
+                end: *point,
+            }
+    }
+    /// The box swept over a displacement of `velocity`.
+    #[must_use]
+    pub fn swept(&self, velocity: &Vector3<N>) -> Self {
+        *self + self.translate(velocity)
+    }
     pub fn largest_dim(&self) -> (usize, N) {
         [self.width(), self.height(), self.depth()]
             .iter()
@@ -164,6 +286,9 @@ impl<N: FloatingPhysicsScalar> AxisAlignedBoundingBox<N> {
         &self,
         aabb: &AxisAlignedBoundingBox<N>,
     ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        if self.is_empty() || aabb.is_empty() {
+            return None;
+        }
         let n = self.center() - aabb.center();
         let overlap = Vector3::new(
             self.half_width() + aabb.half_width() - Float::abs(n.x),
@@ -202,162 +327,234 @@ impl<N: FloatingPhysicsScalar> AxisAlignedBoundingBox<N> {
         &self,
         sphere: &Sphere<N>,
     ) -> Option<CollisionResolution<Vector3<N>, N>> {
-        let n = self.center() - sphere.center();
-        let extent = Vector3::new(self.half_width(), self.half_height(), self.half_depth());
-        let mut closest = Vector3::new(
-            clamp(n.x, -extent.x, extent.x),
-            clamp(n.y, -extent.y, extent.y),
-            clamp(n.z, -extent.z, extent.z),
-        );
-        let inside = {
-            if n == closest {
-                let (index, _) = min_component(&n.abs());
-                match index {
-                    0 => {
-                        closest.x = extent.x
-                            * if closest.x > N::zero() {
-                                N::one()
-                            } else {
-                                -N::one()
-                            };
-                    }
-                    1 => {
-                        closest.y = extent.y
-                            * if closest.y > N::zero() {
-                                N::one()
-                            } else {
-                                -N::one()
-                            };
-                    }
-                    2 => {
-                        closest.z = extent.z
-                            * if closest.z > N::zero() {
-                                N::one()
-                            } else {
-                                -N::one()
-                            };
-                    }
-                    _ => (),
-                }
-                true
-            } else {
-                false
-            }
-        };
-
-        let normal = n - closest;
-        let d = normal.magnitude_squared();
+        if self.is_empty() {
+            return None;
+        }
         let r = sphere.radius;
+        let closest = self.closest_point(&sphere.center);
+        let n = closest - sphere.center;
+        let d = n.magnitude_squared();
 
-        if d > r.pow(N::from_usize(2).unwrap()) && !inside {
-            return None;
+        if !is_zero(d) {
+            if d > r.pow(N::from_usize(2).unwrap()) {
+                return None;
+            }
+            let d = Float::sqrt(d);
+            return Some(CollisionResolution {
+                normal: n / d,
+                penetration: r - d,
+            });
         }
 
-        let d = Float::sqrt(d);
+        // The sphere's center is inside the box so push out through the nearest face.
+        let (min_vec, max_vec) = self.min_max();
+        let to_min = sphere.center - min_vec;
+        let to_max = max_vec - sphere.center;
+        let (min_index, &min_dist) = min_component(&to_min);
+        let (max_index, &max_dist) = min_component(&to_max);
+        let mut normal = Vector3::zeros();
+        let dist = if min_dist < max_dist {
+            normal[min_index] = N::one();
+            min_dist
+        } else {
+            normal[max_index] = -N::one();
+            max_dist
+        };
         Some(CollisionResolution {
-            normal: n.normalize(),
-            penetration: r - d,
+            normal,
+            penetration: r + dist,
         })
     }
-    fn get_plane_collision_with_closure<T, Init, Fold>(
+    pub fn get_plane_collision(
         &self,
         plane: &Plane<N>,
-        init: Init,
-        fold: Fold,
-    ) -> Option<T>
-    where
-        Init: FnOnce() -> T,
-        Fold: FnMut(T, (&Vector3<N>, N)) -> T,
-    {
-        let corners = self.corners();
-        let distances: ArrayVec<[N; 8]> = corners
-            .iter()
-            .map(|corner| plane.distance(corner))
-            .collect();
-
-        let value: i32 = distances
-            .iter()
-            .map(|dist| if *dist > N::zero() { 1 } else { -1 })
-            .sum();
-        if value.abs() == 8 {
+        culling: FaceCulling,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        if self.is_empty() {
             return None;
         }
-
-        Some(corners.iter().zip(distances.into_iter()).fold(init(), fold))
+        culling.plane_collision(plane, &self.center(), |d| self.extent(d))
     }
-    pub fn get_plane_collision(
-        &self,
-        plane: &Plane<N>,
-    ) -> Option<CollisionResolution<Vector3<N>, N>> {
-        let init = || CollisionResolution {
-            normal: plane.normal,
-            penetration: N::zero(),
-        };
-        let fold = |mut result: CollisionResolution<Vector3<N>, N>,
-                    (corner, distance): (&Vector3<N>, N)| {
-            let point = corner - plane.normal * distance;
-            if (point - corner).dot(&plane.normal) > N::zero() {
-                result.penetration = n_max(result.penetration, Float::abs(distance));
-            }
-            result
+    /// Whether the box and the triangle overlap, by the separating axis test
+    /// without working out how deep. Suited to voxelising meshes and culling
+    /// pairs before `get_triangle_collision`.
+    pub fn intersects_triangle(&self, triangle: &Triangle<N>) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        let center = self.center();
+        let half = Vector3::new(self.half_width(), self.half_height(), self.half_depth());
+        let points = [
+            triangle.point1 - center,
+            triangle.point2 - center,
+            triangle.point3 - center,
+        ];
+        let separates = |axis: &Vector3<N>| {
+            let radius = half.dot(&axis.abs());
+            let projected = [
+                points[0].dot(axis),
+                points[1].dot(axis),
+                points[2].dot(axis),
+            ];
+            let low = n_min(projected[0], n_min(projected[1], projected[2]));
+            let high = n_max(projected[0], n_max(projected[1], projected[2]));
+            low > radius || high < -radius
         };
-
-        match self.get_plane_collision_with_closure(plane, init, fold) {
-            Some(result) => {
-                if is_zero(result.penetration) {
-                    None
-                } else {
-                    Some(result)
-                }
-            }
-            None => None,
+        // The box's faces first, as they reject most pairs.
+        if (0..3).any(|i| separates(&Vector3::ith(i, N::one()))) {
+            return false;
         }
+        let edges = [
+            points[1] - points[0],
+            points[2] - points[1],
+            points[0] - points[2],
+        ];
+        if separates(&edges[0].cross(&edges[1])) {
+            return false;
+        }
+        !(0..3).any(|i| {
+            edges
+                .iter()
+                .any(|edge| separates(&Vector3::ith(i, N::one()).cross(edge)))
+        })
     }
+    /// Returns a resolution that pushes the box out of the triangle. When
+    /// double-sided the box is pushed along whichever of the 13 separating
+    /// axes needs the shortest push: the box's faces, the triangle's normal
+    /// and the directions across each box axis and triangle edge.
     pub fn get_triangle_collision(
         &self,
         triangle: &Triangle<N>,
+        culling: FaceCulling,
     ) -> Option<CollisionResolution<Vector3<N>, N>> {
-        let plane = triangle.to_plane();
-        let init = || {
-            (
-                CollisionResolution {
-                    normal: plane.normal,
-                    penetration: N::zero(),
-                },
-                false,
-            )
-        };
-        let fold = |(mut result, mut inside): (CollisionResolution<Vector3<N>, N>, bool),
-                    (corner, distance): (&Vector3<N>, N)| {
-            let point = corner - plane.normal * distance;
-            if triangle.contains(&point) {
-                inside = true;
+        if self.is_empty() {
+            return None;
+        }
+        if culling == FaceCulling::HalfSpace {
+            return prism_collision(triangle, self, &self.center(), |d| self.extent(d));
+        }
+        if !self.intersects_triangle(triangle) {
+            return None;
+        }
+        if culling != FaceCulling::DoubleSided {
+            let plane = Plane::from(triangle);
+            return culling.plane_collision(&plane, &self.center(), |d| self.extent(d));
+        }
+        let edges = [
+            triangle.point2 - triangle.point1,
+            triangle.point3 - triangle.point2,
+            triangle.point1 - triangle.point3,
+        ];
+        let mut axes = [Vector3::zeros(); 13];
+        axes[12] = edges[0].cross(&edges[1]);
+        for i in 0..3 {
+            axes[i] = Vector3::ith(i, N::one());
+            for (j, edge) in edges.iter().enumerate() {
+                axes[3 + i * 3 + j] = Vector3::ith(i, N::one()).cross(edge);
             }
-            if (point - corner).dot(&plane.normal) > N::zero() {
-                result.penetration = n_max(result.penetration, Float::abs(distance));
+        }
+        Some(min_push(self, triangle, &axes))
+    }
+    /// Returns the closest points between the box and the segment from `start`
+    /// to `end`, the first on the box. They are the same point when the segment
+    /// passes through the box.
+    pub fn closest_points_to_segment(
+        &self,
+        start: &Vector3<N>,
+        end: &Vector3<N>,
+    ) -> (Vector3<N>, Vector3<N>) {
+        let (min_vec, max_vec) = self.min_max();
+        let direction = end - start;
+        let point_at = |t: N| start + direction * t;
+
+        // Between the points where the segment crosses a face plane the same
+        // axes are clamped, so the squared distance is a quadratic whose
+        // minimum can be solved for directly.
+        let mut breaks: ArrayVec<[N; 8]> = ArrayVec::new();
+        breaks.push(N::zero());
+        breaks.push(N::one());
+        for i in 0..3 {
+            if is_zero(direction[i]) {
+                continue;
             }
-            (result, inside)
-        };
-        match self.get_plane_collision_with_closure(&plane, init, fold) {
-            Some((result, inside)) => {
-                if !inside || is_zero(result.penetration) {
-                    None
-                } else {
-                    Some(result)
+            for bound in [min_vec[i], max_vec[i]].iter() {
+                let t = (*bound - start[i]) / direction[i];
+                if N::zero() < t && t < N::one() {
+                    breaks.push(t);
                 }
             }
-            None => None,
         }
+        breaks.sort_unstable_by(|a, b| n_ordering(*a, *b));
+
+        let mut candidates: ArrayVec<[N; 16]> = breaks.iter().cloned().collect();
+        for pair in breaks.windows(2) {
+            let middle = point_at((pair[0] + pair[1]) * N::from_f64(0.5).unwrap());
+            let (mut numerator, mut denominator) = (N::zero(), N::zero());
+            for i in 0..3 {
+                let bound = if middle[i] < min_vec[i] {
+                    min_vec[i]
+                } else if middle[i] > max_vec[i] {
+                    max_vec[i]
+                } else {
+                    continue;
+                };
+                numerator += (start[i] - bound) * direction[i];
+                denominator += direction[i] * direction[i];
+            }
+            if !is_zero(denominator) {
+                candidates.push(clamp(-numerator / denominator, pair[0], pair[1]));
+            }
+        }
+
+        let on_segment = candidates
+            .iter()
+            .map(|t| {
+                let p = point_at(*t);
+                (p, (self.closest_point(&p) - p).magnitude_squared())
+            })
+            .min_by(|(_, a), (_, b)| n_ordering(*a, *b))
+            .map(|(p, _)| p)
+            .unwrap();
+        (self.closest_point(&on_segment), on_segment)
+    }
+    /// How far the box reaches along the unit vector `direction`.
+    pub(crate) fn extent(&self, direction: &Vector3<N>) -> N {
+        let half = Vector3::new(self.half_width(), self.half_height(), self.half_depth());
+        self.center().dot(direction) + half.dot(&direction.abs())
     }
     pub fn get_capsule_collision(
         &self,
         capsule: &Capsule<N>,
     ) -> Option<CollisionResolution<Vector3<N>, N>> {
-        let center = capsule.closest_point(&self.center());
-        self.get_sphere_collision(&Sphere {
-            center,
-            radius: capsule.radius,
-        })
+        if self.is_empty() {
+            return None;
+        }
+        let (on_box, on_segment) = self.closest_points_to_segment(&capsule.start, &capsule.end);
+        let n = on_box - on_segment;
+        let len = n.magnitude_squared();
+        if len > capsule.radius * capsule.radius {
+            return None;
+        }
+        if !is_zero(len) {
+            let d = Float::sqrt(len);
+            return Some(CollisionResolution {
+                normal: n / d,
+                penetration: capsule.radius - d,
+            });
+        }
+
+        // The segment passes through the box. The shortest way out is along a
+        // face normal or perpendicular to both the segment and a box edge.
+        let axis = capsule.end - capsule.start;
+        let axes = [
+            Vector3::x(),
+            Vector3::y(),
+            Vector3::z(),
+            axis.cross(&Vector3::x()),
+            axis.cross(&Vector3::y()),
+            axis.cross(&Vector3::z()),
+        ];
+        Some(min_push(self, capsule, &axes))
     }
     pub fn corners(&self) -> [Vector3<N>; 8] {
         let (start_x, start_y, start_z) = (self.start.x, self.start.y, self.start.z);
@@ -396,24 +593,42 @@ impl<N: FloatingPhysicsScalar> AxisAlignedBoundingBox<N> {
     }
 }
 
+impl<N: FloatingPhysicsScalar> MassProperties<N> for AxisAlignedBoundingBox<N> {
+    type Vector = Vector3<N>;
+    type Inertia = Matrix3<N>;
+
+    fn mass_properties(&self, density: N) -> MassData<Vector3<N>, Matrix3<N>, N> {
+        let mass = self.volume() * density;
+        let (w, h, d) = (self.width(), self.height(), self.depth());
+        let k = mass / N::from_f64(12.0).unwrap();
+        MassData {
+            mass,
+            center_of_mass: self.center(),
+            inertia: Matrix3::from_diagonal(&Vector3::new(
+                k * (h * h + d * d),
+                k * (w * w + d * d),
+                k * (w * w + h * h),
+            )),
+        }
+    }
+}
+
 impl<N: FloatingPhysicsScalar> Shape3D<N> for AxisAlignedBoundingBox<N> {
     fn bounding_aabb(&self) -> AxisAlignedBoundingBox<N> {
         *self
     }
     fn bounding_sphere(&self) -> Sphere<N> {
+        // The corners are furthest from the centre.
+        let (min_vec, max_vec) = self.min_max();
         Sphere {
             center: self.center(),
-            radius: [self.width(), self.height(), self.depth()]
-                .iter()
-                .max_by(|a, b| match a.partial_cmp(b) {
-                    Some(o) => o,
-                    None => Ordering::Equal,
-                })
-                .cloned()
-                .unwrap(),
+            radius: (max_vec - min_vec).magnitude() * N::from_f64(0.5).unwrap(),
         }
     }
     fn center(&self) -> Vector3<N> {
+        if self.is_empty() {
+            return Vector3::zeros();
+        }
         Vector3::new(
             (self.start.x + self.end.x) * N::from_f64(0.5f64).unwrap(),
             (self.start.y + self.end.y) * N::from_f64(0.5f64).unwrap(),
@@ -421,16 +636,25 @@ impl<N: FloatingPhysicsScalar> Shape3D<N> for AxisAlignedBoundingBox<N> {
         )
     }
     fn translate(&self, point: &Vector3<N>) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
         AxisAlignedBoundingBox {
             start: self.start + point,
             end: self.end + point,
         }
     }
     fn translate_mut(&mut self, point: &Vector3<N>) {
+        if self.is_empty() {
+            return;
+        }
         self.start += point;
         self.end += point;
     }
     fn set_center(&self, point: &Vector3<N>) -> Self {
+        if self.is_empty() {
+            return *self;
+        }
         let offset = Vector3::new(self.half_width(), self.half_height(), self.half_depth());
         AxisAlignedBoundingBox {
             start: point - offset,
@@ -438,6 +662,9 @@ impl<N: FloatingPhysicsScalar> Shape3D<N> for AxisAlignedBoundingBox<N> {
         }
     }
     fn set_center_mut(&mut self, point: &Vector3<N>) {
+        if self.is_empty() {
+            return;
+        }
         let offset = Vector3::new(self.half_width(), self.half_height(), self.half_depth());
         self.start = point - offset;
         self.end = point + offset;
@@ -446,10 +673,7 @@ impl<N: FloatingPhysicsScalar> Shape3D<N> for AxisAlignedBoundingBox<N> {
 
 impl<N: FloatingPhysicsScalar> Sum for AxisAlignedBoundingBox<N> {
     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
-        let mut aabb = AABB3D {
-            start: Vector3::<N>::zeros(),
-            end: Vector3::<N>::zeros(),
-        };
+        let mut aabb = AxisAlignedBoundingBox::empty();
         for a in iter.into_iter() {
             aabb += a;
         }
@@ -459,10 +683,7 @@ impl<N: FloatingPhysicsScalar> Sum for AxisAlignedBoundingBox<N> {
 
 impl<'a, N: FloatingPhysicsScalar> Sum<&'a Self> for AxisAlignedBoundingBox<N> {
     fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
-        let mut aabb = AABB3D {
-            start: Vector3::<N>::zeros(),
-            end: Vector3::<N>::zeros(),
-        };
+        let mut aabb = AxisAlignedBoundingBox::empty();
         for a in iter.into_iter() {
             aabb += *a;
         }
@@ -489,16 +710,16 @@ mod tests {
         .iter()
         .sum();
 
-        aabb.start.relative_eq(
+        assert!(aabb.start.relative_eq(
             &Vector3::<f32>::new(-560f32, -1f32, -285f32),
-            std::f32::EPSILON,
-            std::f32::EPSILON,
-        );
-        aabb.end.relative_eq(
+            f32::EPSILON,
+            f32::EPSILON,
+        ));
+        assert!(aabb.end.relative_eq(
             &Vector3::<f32>::new(258f32, 505f32, 500f32),
-            std::f32::EPSILON,
-            std::f32::EPSILON,
-        );
+            f32::EPSILON,
+            f32::EPSILON,
+        ));
     }
     #[test]
     fn dim_test() {
@@ -511,6 +732,16 @@ mod tests {
         assert_eq!(aabb.smallest_dim(), (0, 50f32));
     }
     #[test]
+    fn bounding_sphere_test() {
+        let aabb = AxisAlignedBoundingBox {
+            start: Vector3::new(0f64, 0f64, 0f64),
+            end: Vector3::new(2f64, 4f64, 4f64),
+        };
+        let sphere = aabb.bounding_sphere();
+        assert_eq!(sphere.center, Vector3::new(1f64, 2f64, 2f64));
+        assert_eq!(sphere.radius, 3f64);
+    }
+    #[test]
     fn mul_test() {
         let start = Vector3::<f32>::new(1f32, 2f32, 5f32);
         let end = Vector3::<f32>::new(0f32, 10f32, -2f32);
@@ -521,4 +752,276 @@ mod tests {
         assert_eq!(aabb2.start, start * 0.5f32);
         assert_eq!(aabb2.end, end * 0.5f32);
     }
+    #[test]
+    fn mass_test() {
+        let aabb = AABB3D {
+            start: Vector3::<f32>::new(1f32, 2f32, 3f32),
+            end: Vector3::<f32>::new(2f32, 4f32, 6f32),
+        };
+        let mass = aabb.mass_properties(2f32);
+
+        assert_eq!(mass.mass, 12f32);
+        assert_eq!(mass.center_of_mass, aabb.center());
+        assert_eq!(
+            mass.inertia,
+            Matrix3::from_diagonal(&Vector3::new(13f32, 10f32, 5f32))
+        );
+    }
+    #[test]
+    fn project_point_test() {
+        let aabb = AABB3D {
+            start: Vector3::<f32>::new(-1f32, -1f32, -1f32),
+            end: Vector3::<f32>::new(1f32, 2f32, 1f32),
+        };
+
+        assert!(aabb.contains_point(&Vector3::new(0f32, 2f32, 0f32)));
+        assert!(!aabb.contains_point(&Vector3::new(0f32, 2.5f32, 0f32)));
+        assert_eq!(
+            aabb.project_point(&Vector3::new(0.25f32, 1.5f32, 0f32)),
+            PointProjection {
+                point: Vector3::new(0.25f32, 2f32, 0f32),
+                is_inside: true,
+            }
+        );
+        assert_eq!(
+            aabb.project_point(&Vector3::new(3f32, 0f32, -4f32)),
+            PointProjection {
+                point: Vector3::new(1f32, 0f32, -1f32),
+                is_inside: false,
+            }
+        );
+    }
+    #[test]
+    fn sum_excludes_origin_test() {
+        let boxes = [
+            AABB3D {
+                start: Vector3::<f32>::new(1f32, 2f32, 3f32),
+                end: Vector3::<f32>::new(2f32, 3f32, 4f32),
+            },
+            AABB3D {
+                start: Vector3::<f32>::new(5f32, 1f32, 3f32),
+                end: Vector3::<f32>::new(6f32, 2f32, 3.5f32),
+            },
+        ];
+        let aabb: AABB3D<f32> = boxes.iter().sum();
+
+        assert_eq!(aabb.start, Vector3::new(1f32, 1f32, 3f32));
+        assert_eq!(aabb.end, Vector3::new(6f32, 3f32, 4f32));
+        assert!(::core::iter::empty::<AABB3D<f32>>()
+            .sum::<AABB3D<f32>>()
+            .is_empty());
+        assert_eq!(boxes[0] + AABB3D::empty(), boxes[0]);
+    }
+    #[test]
+    fn empty_test() {
+        let empty = AABB3D::<f32>::empty();
+        let a = AABB3D {
+            start: Vector3::<f32>::new(0f32, 0f32, 0f32),
+            end: Vector3::<f32>::new(2f32, 2f32, 2f32),
+        };
+
+        assert!(!empty.contains_point(&Vector3::zeros()));
+        assert!(!empty.contains_aabb(&a));
+        assert!(a.contains_aabb(&empty));
+        assert!(empty.contains_aabb(&empty));
+        assert!(empty.intersection(&a).is_none());
+        assert!(a.intersection(&empty).is_none());
+        assert!(empty.expand_by(1f32).is_empty());
+        assert!(empty.translate(&Vector3::new(1f32, 2f32, 3f32)).is_empty());
+        assert!(empty.set_center(&Vector3::new(1f32, 2f32, 3f32)).is_empty());
+        assert!((empty * 2f32).is_empty());
+        assert_eq!(empty.width(), 0f32);
+        assert_eq!(empty.height(), 0f32);
+        assert_eq!(empty.depth(), 0f32);
+        assert_eq!(empty.surface_area(), 0f32);
+        assert_eq!(empty.volume(), 0f32);
+        assert_eq!(empty.center(), Vector3::zeros());
+    }
+    #[test]
+    fn empty_min_max_test() {
+        let empty = AABB3D::<f32>::empty();
+        assert_eq!(empty.min_max(), (Vector3::zeros(), Vector3::zeros()));
+    }
+    #[test]
+    fn empty_closest_point_test() {
+        let empty = AABB3D::<f32>::empty();
+        let point = Vector3::new(1f32, 2f32, 3f32);
+        assert_eq!(empty.closest_point(&point), Vector3::zeros());
+        assert!(!empty.project_point(&point).is_inside);
+    }
+    #[test]
+    fn empty_aabb_collision_test() {
+        let empty = AABB3D::<f32>::empty();
+        let a = AABB3D {
+            start: Vector3::new(-1f32, -1f32, -1f32),
+            end: Vector3::new(1f32, 1f32, 1f32),
+        };
+        assert!(empty.get_aabb_collision(&a).is_none());
+        assert!(a.get_aabb_collision(&empty).is_none());
+        assert!(empty.get_aabb_collision(&empty).is_none());
+    }
+    #[test]
+    fn empty_sphere_collision_test() {
+        let empty = AABB3D::<f32>::empty();
+        let sphere = Sphere {
+            center: Vector3::new(5f32, 5f32, 5f32),
+            radius: 1f32,
+        };
+        assert!(empty.get_sphere_collision(&sphere).is_none());
+        assert!(empty
+            .get_sphere_collision(&sphere.set_center(&Vector3::zeros()))
+            .is_none());
+    }
+    #[test]
+    fn empty_plane_collision_test() {
+        let empty = AABB3D::<f32>::empty();
+        let plane = Plane::from_point(&Vector3::y(), &Vector3::new(0f32, 5f32, 0f32));
+        assert!(empty
+            .get_plane_collision(&plane, FaceCulling::HalfSpace)
+            .is_none());
+    }
+    #[test]
+    fn empty_triangle_collision_test() {
+        let empty = AABB3D::<f32>::empty();
+        let triangle = Triangle {
+            point1: Vector3::new(-1f32, 0f32, -1f32),
+            point2: Vector3::new(1f32, 0f32, -1f32),
+            point3: Vector3::new(0f32, 0f32, 1f32),
+        };
+        assert!(!empty.intersects_triangle(&triangle));
+        assert!(empty
+            .get_triangle_collision(&triangle, FaceCulling::DoubleSided)
+            .is_none());
+        assert!(empty
+            .get_triangle_collision(&triangle, FaceCulling::HalfSpace)
+            .is_none());
+    }
+    #[test]
+    fn empty_capsule_collision_test() {
+        let empty = AABB3D::<f32>::empty();
+        let capsule = Capsule {
+            start: Vector3::new(0f32, -1f32, 0f32),
+            end: Vector3::new(0f32, 1f32, 0f32),
+            radius: 0.5f32,
+        };
+        assert!(empty.get_capsule_collision(&capsule).is_none());
+    }
+    #[test]
+    fn empty_bounding_sphere_test() {
+        let sphere = AABB3D::<f32>::empty().bounding_sphere();
+        assert_eq!(sphere.center, Vector3::zeros());
+        assert_eq!(sphere.radius, 0f32);
+    }
+    #[test]
+    fn set_operations_test() {
+        let a = AABB3D {
+            start: Vector3::<f32>::new(0f32, 0f32, 0f32),
+            end: Vector3::<f32>::new(2f32, 2f32, 2f32),
+        };
+        let b = AABB3D {
+            start: Vector3::<f32>::new(3f32, 1f32, 1f32),
+            end: Vector3::<f32>::new(1f32, 3f32, 3f32),
+        };
+
+        let overlap = a.intersection(&b).unwrap();
+        assert_eq!(overlap.start, Vector3::new(1f32, 1f32, 1f32));
+        assert_eq!(overlap.end, Vector3::new(2f32, 2f32, 2f32));
+        assert!(a
+            .intersection(&b.translate(&Vector3::new(5f32, 0f32, 0f32)))
+            .is_none());
+
+        assert!(a.contains_aabb(&overlap));
+        assert!(!a.contains_aabb(&b));
+        assert!(a
+            .expand_by(1f32)
+            .contains_aabb(&b.translate(&Vector3::new(0f32, -1f32, -1f32))));
+        assert_eq!(
+            a.expand_to_point(&Vector3::new(-1f32, 1f32, 4f32))
+                .min_max(),
+            (
+                Vector3::new(-1f32, 0f32, 0f32),
+                Vector3::new(2f32, 2f32, 4f32)
+            )
+        );
+        assert_eq!(
+            a.swept(&Vector3::new(0f32, -3f32, 0f32)).min_max(),
+            (
+                Vector3::new(0f32, -3f32, 0f32),
+                Vector3::new(2f32, 2f32, 2f32)
+            )
+        );
+        assert_eq!(a.surface_area(), 24f32);
+        assert_eq!(a.volume(), 8f32);
+    }
+    #[test]
+    fn capsule_collision_test() {
+        let aabb = AABB3D {
+            start: Vector3::new(-1f64, -1f64, -1f64),
+            end: Vector3::new(1f64, 1f64, 1f64),
+        };
+
+        // Passes close to the edge along z while its midpoint is far away.
+        let capsule = Capsule {
+            start: Vector3::new(2.2f64, 0f64, 0f64),
+            end: Vector3::new(0f64, 2.2f64, 0f64),
+            radius: 0.25f64,
+        };
+        let (on_box, on_segment) = aabb.closest_points_to_segment(&capsule.start, &capsule.end);
+        assert!((on_box - Vector3::new(1f64, 1f64, 0f64)).magnitude() < 1e-9);
+        assert!((on_segment - Vector3::new(1.1f64, 1.1f64, 0f64)).magnitude() < 1e-9);
+        let res = aabb.get_capsule_collision(&capsule).unwrap();
+        let expected = Vector3::new(-1f64, -1f64, 0f64).normalize();
+        assert!((res.normal - expected).magnitude() < 1e-9);
+        assert!((res.penetration - (0.25 - 0.02f64.sqrt())).abs() < 1e-9);
+
+        // Runs straight through, so the box leaves through the nearest side.
+        let capsule = Capsule {
+            start: Vector3::new(-3f64, 0.25f64, 0f64),
+            end: Vector3::new(3f64, 0.25f64, 0f64),
+            radius: 0.5f64,
+        };
+        let res = aabb.get_capsule_collision(&capsule).unwrap();
+        assert_eq!(res.normal, -Vector3::y());
+        assert!((res.penetration - 1.25).abs() < 1e-9);
+    }
+    #[test]
+    fn triangle_collision_test() {
+        let aabb = AABB3D {
+            start: Vector3::new(-4f64, -1f64, -4f64),
+            end: Vector3::new(4f64, 1f64, 4f64),
+        };
+
+        // Small and flat in the middle of the top face, so no corner of the
+        // box projects inside it.
+        let triangle = Triangle {
+            point1: Vector3::new(0f64, 0.75f64, 0f64),
+            point2: Vector3::new(0f64, 0.75f64, 0.5f64),
+            point3: Vector3::new(0.5f64, 0.75f64, 0f64),
+        };
+        assert!(aabb.intersects_triangle(&triangle));
+        let res = aabb
+            .get_triangle_collision(&triangle, FaceCulling::DoubleSided)
+            .unwrap();
+        assert_eq!(res.normal, -Vector3::y());
+        assert!((res.penetration - 0.25f64).abs() < 1e-9);
+
+        // Upright and poking into the side, so the push is sideways rather
+        // than along the triangle's normal.
+        let triangle = Triangle {
+            point1: Vector3::new(3.75f64, 0f64, -1f64),
+            point2: Vector3::new(6f64, 0f64, 1f64),
+            point3: Vector3::new(6f64, 0f64, -1f64),
+        };
+        let res = aabb
+            .get_triangle_collision(&triangle, FaceCulling::DoubleSided)
+            .unwrap();
+        assert_eq!(res.normal, -Vector3::x());
+        assert!((res.penetration - 0.25f64).abs() < 1e-9);
+
+        let above = triangle.translate(&Vector3::new(0f64, 1.5f64, 0f64));
+        assert!(!aabb.intersects_triangle(&above));
+        assert!(aabb
+            .get_triangle_collision(&above, FaceCulling::DoubleSided)
+            .is_none());
+    }
 }
@@ -12,6 +12,11 @@ pub struct Capsule<N: PhysicsScalar> {
 }
 
 impl<N: FloatingPhysicsScalar> Capsule<N> {
+    pub fn volume(&self) -> N {
+        let r2 = self.radius * self.radius;
+        let length = (self.end - self.start).magnitude();
+        N::pi() * r2 * (length + N::from_f64(4.0 / 3.0).unwrap() * self.radius)
+    }
     pub fn distance(&self) -> N {
         (self.start - self.end).magnitude() + self.radius * N::from_f64(2f64).unwrap()
     }
@@ -21,84 +26,189 @@ impl<N: FloatingPhysicsScalar> Capsule<N> {
     pub fn closest_point(&self, point: &Vector3<N>) -> Vector3<N> {
         closest_to_segment(&self.start, &self.end, point)
     }
-    pub fn closest_points(&self, capsule: &Capsule<N>) -> (Vector3<N>, Vector3<N>) {
-        let other_closest = capsule.closest_point(&self.start);
-        let this_closest = self.closest_point(&other_closest);
-        (this_closest, other_closest)
-    }
-    pub fn get_capsule_collision(&self, capsule: &Capsule<N>) -> Option<CollisionResolution<Vector3<N>, N>> {
-        if self
-            .bounding_aabb()
-            .get_aabb_collision(&capsule.bounding_aabb())
-            .is_none()
-        {
-            return None;
+    pub fn contains_point(&self, point: &Vector3<N>) -> bool {
+        (point - self.closest_point(point)).magnitude_squared() <= self.radius * self.radius
+    }
+    pub fn project_point(&self, point: &Vector3<N>) -> PointProjection<Vector3<N>> {
+        let closest = self.closest_point(point);
+        let n = point - closest;
+        let direction = if !is_zero(n.magnitude_squared()) {
+            n.normalize()
+        } else {
+            // The point lies on the segment so any direction perpendicular to it will do.
+            perpendicular(&(self.end - self.start))
+        };
+        PointProjection {
+            point: closest + direction * self.radius,
+            is_inside: n.magnitude_squared() <= self.radius * self.radius,
         }
+    }
+    /// Returns the closest points between the two segments, the first on `self`.
+    pub fn closest_points(&self, capsule: &Capsule<N>) -> (Vector3<N>, Vector3<N>) {
+        closest_between_segments(&self.start, &self.end, &capsule.start, &capsule.end)
+    }
+    pub fn get_capsule_collision(
+        &self,
+        capsule: &Capsule<N>,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        self.bounding_aabb()
+            .get_aabb_collision(&capsule.bounding_aabb())?;
 
         let (point1, point2) = self.closest_points(capsule);
-
-        Sphere {
-            center: point1,
-            radius: self.radius,
+        let radius = self.radius + capsule.radius;
+        let n = point1 - point2;
+        let len = n.magnitude_squared();
+        if len > radius * radius {
+            return None;
         }
-        .get_sphere_collision(&Sphere {
-            center: point2,
-            radius: self.radius,
+
+        let (normal, penetration) = if is_zero(len) {
+            // The segments cross so push out perpendicular to both of them.
+            let axis = self.end - self.start;
+            let cross = axis.cross(&(capsule.end - capsule.start));
+            let normal = if is_zero(cross.magnitude_squared()) {
+                perpendicular(&axis)
+            } else {
+                cross.normalize()
+            };
+            if normal.dot(&(self.center() - capsule.center())) < N::zero() {
+                (-normal, radius)
+            } else {
+                (normal, radius)
+            }
+        } else {
+            let d = Float::sqrt(len);
+            (n / d, radius - d)
+        };
+        Some(CollisionResolution {
+            normal,
+            penetration,
         })
     }
+    pub fn get_aabb_collision(
+        &self,
+        aabb: &AxisAlignedBoundingBox<N>,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        aabb.get_capsule_collision(self).map(|r| r.flip())
+    }
+    pub fn get_cylinder_collision(
+        &self,
+        cylinder: &Cylinder<N>,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        cylinder.get_capsule_collision(self).map(|r| r.flip())
+    }
+    /// How far the capsule reaches along the unit vector `direction`.
+    pub(crate) fn extent(&self, direction: &Vector3<N>) -> N {
+        n_max(self.start.dot(direction), self.end.dot(direction)) + self.radius
+    }
+    /// Returns a resolution that pushes the capsule out of the triangle. When
+    /// double-sided the capsule is pushed away from the point of the triangle
+    /// closest to its segment, or out along the shortest of the separating
+    /// axes when the segment passes through the triangle.
     pub fn get_triangle_collision(
         &self,
         triangle: &Triangle<N>,
-        double_sided: bool,
+        culling: FaceCulling,
     ) -> Option<CollisionResolution<Vector3<N>,N>> {
-        if self
-            .bounding_aabb()
-            .get_aabb_collision(&triangle.bounding_aabb())
-            .is_none()
-        {
-            return None;
+        if culling == FaceCulling::HalfSpace {
+            return prism_collision(triangle, self, &self.center(), |d| self.extent(d));
         }
+        self.bounding_aabb()
+            .get_aabb_collision(&triangle.bounding_aabb())?;
 
-        let mut points = [self.start, self.end, unsafe { std::mem::zeroed() }];
-
-        let plane = Plane::from(triangle);
-        let ray = Ray::from(self);
-
-        if let Some(t) = ray.intersects_plane(&plane) {
-            if N::zero() < t && t < self.distance() {
-                unsafe {
-                    *points.get_unchecked_mut(2) = ray.get_point(t);
-                }
+        let segment = Capsule {
+            radius: N::zero(),
+            ..*self
+        };
+        let closest = closest_points(&segment, triangle);
+        if let Some((on_segment, on_triangle)) = closest {
+            if (on_segment - on_triangle).magnitude_squared() > self.radius * self.radius {
+                return None;
             }
         }
+        if culling != FaceCulling::DoubleSided {
+            let plane = Plane::from(triangle);
+            return culling.plane_collision(&plane, &self.center(), |d| self.extent(d));
+        }
 
-        points.sort_by(|a, b| {
-            let (_, a) = triangle.closest_point(a);
-            let (_, b) = triangle.closest_point(b);
-            a.partial_cmp(&b).unwrap_or(Ordering::Equal)
-        });
-
-        points.iter().find_map(|point| {
-            Sphere {
-                center: *point,
-                radius: self.radius,
+        if let Some((on_segment, on_triangle)) = closest {
+            let n = on_segment - on_triangle;
+            let d = n.magnitude();
+            if !is_zero(d) {
+                return Some(CollisionResolution {
+                    normal: n / d,
+                    penetration: self.radius - d,
+                });
             }
-            .get_triangle_collision(triangle, double_sided)
-        })
+        }
+        // The segment passes through the triangle. Try the triangle's normal,
+        // the directions across the segment and each edge, and the directions
+        // in the triangle's plane square to each edge and to the segment.
+        let normal = triangle.normal();
+        let direction = self.end - self.start;
+        let edges = [
+            triangle.point2 - triangle.point1,
+            triangle.point3 - triangle.point2,
+            triangle.point1 - triangle.point3,
+        ];
+        let mut axes = [Vector3::zeros(); 8];
+        axes[0] = normal;
+        axes[1] = normal.cross(&direction);
+        for (i, edge) in edges.iter().enumerate() {
+            axes[2 + i] = direction.cross(edge);
+            axes[5 + i] = normal.cross(edge);
+        }
+        Some(min_push(self, triangle, &axes))
     }
-    pub fn get_plane_collision(&self, plane: &Plane<N>) -> Option<CollisionResolution<Vector3<N>, N>> {
-        let ray = Ray::from(self);
-        match ray.intersects_plane(plane) {
-            Some(t) if N::zero() < t && t < self.distance() => {
-                let center = ray.get_point(t);
-                Sphere {
-                    center,
-                    radius: self.radius,
-                }
-                .get_plane_collision(plane)
+    pub fn get_plane_collision(
+        &self,
+        plane: &Plane<N>,
+        culling: FaceCulling,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        culling.plane_collision(plane, &self.center(), |d| self.extent(d))
+    }
+    /// Collides the sphere at the point of the segment deepest in the field.
+    pub fn get_sdf_collision<S: Sdf3D<N> + ?Sized>(
+        &self,
+        sdf: &S,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        let direction = self.end - self.start;
+        let distance = |t: N| sdf.signed_distance(&(self.start + direction * t));
+
+        // The field need not be convex, so sample the segment first and then
+        // narrow down around the deepest sample.
+        let samples = 8;
+        let step = N::one() / N::from_usize(samples).unwrap();
+        let (mut best, mut best_distance) = (N::zero(), distance(N::zero()));
+        for i in 1..=samples {
+            let t = step * N::from_usize(i).unwrap();
+            let d = distance(t);
+            if d < best_distance {
+                best = t;
+                best_distance = d;
             }
-            _ => None,
         }
+        let ratio = N::from_f64(0.618_033_988_749_895).unwrap();
+        let (mut low, mut high) = (n_max(best - step, N::zero()), n_min(best + step, N::one()));
+        for _ in 0..32 {
+            let width = (high - low) * ratio;
+            if distance(high - width) <= distance(low + width) {
+                high = low + width;
+            } else {
+                low = high - width;
+            }
+        }
+        let middle = (low + high) * N::from_f64(0.5).unwrap();
+        let t = if distance(middle) < best_distance {
+            middle
+        } else {
+            best
+        };
+        Sphere {
+            center: self.start + direction * t,
+            radius: self.radius,
+        }
+        .get_sdf_collision(sdf)
     }
     pub fn to_spheres(&self) -> (Sphere<N>, Sphere<N>) {
         let radius = self.radius;
@@ -115,13 +225,49 @@ impl<N: FloatingPhysicsScalar> Capsule<N> {
     }
 }
 
+impl<N: FloatingPhysicsScalar> MassProperties<N> for Capsule<N> {
+    type Vector = Vector3<N>;
+    type Inertia = Matrix3<N>;
+
+    fn mass_properties(&self, density: N) -> MassData<Vector3<N>, Matrix3<N>, N> {
+        let f = |v: f64| N::from_f64(v).unwrap();
+        let r = self.radius;
+        let r2 = r * r;
+        let axis = self.end - self.start;
+        let length = axis.magnitude();
+        let cylinder = N::pi() * r2 * length * density;
+        let caps = f(4.0 / 3.0) * N::pi() * r2 * r * density;
+
+        // Each hemisphere's inertia is shifted from its own center of mass
+        // (3r/8 from the flat face) to the capsule's center.
+        let along = cylinder * r2 * f(0.5) + caps * r2 * f(0.4);
+        let across = cylinder * (length * length / f(12.0) + r2 * f(0.25))
+            + caps * (r2 * f(0.4) + length * length * f(0.25) + length * r * f(3.0 / 8.0));
+        let inertia = if is_zero(length) {
+            Matrix3::from_diagonal_element(across)
+        } else {
+            let axis = axis / length;
+            Matrix3::from_diagonal_element(across) + axis * axis.transpose() * (along - across)
+        };
+        MassData {
+            mass: cylinder + caps,
+            center_of_mass: self.center(),
+            inertia,
+        }
+    }
+}
+
 impl<N: FloatingPhysicsScalar> Shape3D<N> for Capsule<N> {
     fn bounding_aabb(&self) -> AxisAlignedBoundingBox<N> {
         let (start, end) = self.to_spheres();
         start.bounding_aabb() + end.bounding_aabb()
     }
     fn bounding_sphere(&self) -> Sphere<N> {
-        self.bounding_aabb().bounding_sphere()
+        Sphere {
+            center: self.center(),
+            radius: (self.end - self.start).magnitude() * N::from_f64(0.5f64).unwrap()
+                + self.radius,
+        }
     }
     fn center(&self) -> Vector3<N> {
         self.start * N::from_f64(0.5f64).unwrap() + self.end * N::from_f64(0.5f64).unwrap()
@@ -150,3 +296,187 @@ impl<N: FloatingPhysicsScalar> Shape3D<N> for Capsule<N> {
         self.end = new_center + end_dist;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mass_matches_sampling_test() {
+        let capsule = Capsule {
+            start: Vector3::new(0f64, -1f64, 0f64),
+            end: Vector3::new(0f64, 1f64, 0f64),
+            radius: 0.5f64,
+        };
+        let mass = capsule.mass_properties(1f64);
+
+        let steps = 60;
+        let aabb = capsule.bounding_aabb();
+        let (min_vec, max_vec) = aabb.min_max();
+        let cell = (max_vec - min_vec) / steps as f64;
+        let cell_volume = cell.x * cell.y * cell.z;
+        let (mut sampled_mass, mut sampled_x, mut sampled_y) = (0f64, 0f64, 0f64);
+        for i in 0..steps {
+            for j in 0..steps {
+                for k in 0..steps {
+                    let p = min_vec
+                        + Vector3::new(i as f64 + 0.5, j as f64 + 0.5, k as f64 + 0.5)
+                            .component_mul(&cell);
+                    if (capsule.closest_point(&p) - p).magnitude() <= capsule.radius {
+                        sampled_mass += cell_volume;
+                        sampled_x += cell_volume * (p.y * p.y + p.z * p.z);
+                        sampled_y += cell_volume * (p.x * p.x + p.z * p.z);
+                    }
+                }
+            }
+        }
+
+        assert!((mass.mass - sampled_mass).abs() / sampled_mass < 0.01);
+        assert!((mass.inertia[(0, 0)] - sampled_x).abs() / sampled_x < 0.01);
+        assert!((mass.inertia[(1, 1)] - sampled_y).abs() / sampled_y < 0.01);
+        assert!(mass.inertia[(0, 1)].abs() < 1e-9);
+    }
+    #[test]
+    fn parallel_capsule_test() {
+        let a = Capsule {
+            start: Vector3::new(0f64, 0f64, 0f64),
+            end: Vector3::new(4f64, 0f64, 0f64),
+            radius: 0.75f64,
+        };
+        let b = Capsule {
+            start: Vector3::new(3f64, -1f64, 0f64),
+            end: Vector3::new(6f64, -1f64, 0f64),
+            radius: 0.5f64,
+        };
+        let res = a.get_capsule_collision(&b).unwrap();
+        assert!((res.normal - Vector3::y()).magnitude() < 1e-9);
+        assert!((res.penetration - 0.25).abs() < 1e-9);
+        assert_eq!(b.get_capsule_collision(&a).unwrap().normal, -res.normal);
+    }
+    #[test]
+    fn crossing_capsule_test() {
+        let a = Capsule {
+            start: Vector3::new(-2f64, 0f64, 0f64),
+            end: Vector3::new(2f64, 0f64, 0f64),
+            radius: 0.5f64,
+        };
+        let b = Capsule {
+            start: Vector3::new(1f64, -2f64, 0.5f64),
+            end: Vector3::new(1f64, 2f64, 0.5f64),
+            radius: 0.25f64,
+        };
+        assert_eq!(
+            a.closest_points(&b),
+            (
+                Vector3::new(1f64, 0f64, 0f64),
+                Vector3::new(1f64, 0f64, 0.5f64)
+            )
+        );
+        let res = a.get_capsule_collision(&b).unwrap();
+        assert_eq!(res.normal, -Vector3::z());
+        assert!((res.penetration - 0.25).abs() < 1e-9);
+
+        // Segments that intersect are pushed apart across both of them.
+        let b = b.translate(&Vector3::new(0f64, 0.5f64, -0.5f64));
+        let res = a.get_capsule_collision(&b).unwrap();
+        assert_eq!(res.normal.z.abs(), 1f64);
+        assert!((res.penetration - 0.75).abs() < 1e-9);
+    }
+    #[test]
+    fn end_cap_capsule_test() {
+        let a = Capsule {
+            start: Vector3::new(0f64, 0f64, 0f64),
+            end: Vector3::new(0f64, 1f64, 0f64),
+            radius: 0.5f64,
+        };
+        let b = Capsule {
+            start: Vector3::new(0.5f64, 1.5f64, 0f64),
+            end: Vector3::new(3f64, 4f64, 0f64),
+            radius: 0.5f64,
+        };
+        assert_eq!(
+            a.closest_points(&b),
+            (
+                Vector3::new(0f64, 1f64, 0f64),
+                Vector3::new(0.5f64, 1.5f64, 0f64)
+            )
+        );
+        let res = a.get_capsule_collision(&b).unwrap();
+        let expected = Vector3::new(-1f64, -1f64, 0f64).normalize();
+        assert!((res.normal - expected).magnitude() < 1e-9);
+        assert!((res.penetration - (1.0 - 0.5f64.sqrt())).abs() < 1e-9);
+
+        let b = b.translate(&Vector3::new(0.5f64, 0f64, 0f64));
+        assert!(a.get_capsule_collision(&b).is_none());
+    }
+    #[test]
+    fn plane_collision_test() {
+        let plane = Plane::from_point(&Vector3::y(), &Vector3::zeros());
+        // The segment stays in front and only the cap reaches through.
+        let capsule = Capsule {
+            start: Vector3::new(0f64, 0.25f64, 0f64),
+            end: Vector3::new(1f64, 1f64, 0f64),
+            radius: 0.5f64,
+        };
+        let res = capsule
+            .get_plane_collision(&plane, FaceCulling::OneSided)
+            .unwrap();
+        assert_eq!(res.normal, Vector3::y());
+        assert!((res.penetration - 0.25f64).abs() < 1e-9);
+
+        let sunk = capsule.translate(&Vector3::new(0f64, -1.25f64, 0f64));
+        assert!(sunk
+            .get_plane_collision(&plane, FaceCulling::OneSided)
+            .is_none());
+        let res = sunk
+            .get_plane_collision(&plane, FaceCulling::HalfSpace)
+            .unwrap();
+        assert_eq!(res.normal, Vector3::y());
+        assert!((res.penetration - 1.5f64).abs() < 1e-9);
+        let res = sunk
+            .get_plane_collision(&plane, FaceCulling::DoubleSided)
+            .unwrap();
+        assert_eq!(res.normal, -Vector3::y());
+        assert!((res.penetration - 0.25f64).abs() < 1e-9);
+    }
+    #[test]
+    fn triangle_collision_test() {
+        let floor = Triangle {
+            point1: Vector3::new(-4f64, 0f64, -4f64),
+            point2: Vector3::new(-4f64, 0f64, 4f64),
+            point3: Vector3::new(4f64, 0f64, 0f64),
+        };
+        // Standing through the floor with its centre below it.
+        let capsule = Capsule {
+            start: Vector3::new(0f64, -1f64, 0f64),
+            end: Vector3::new(0f64, 0.5f64, 0f64),
+            radius: 0.25f64,
+        };
+        assert!(capsule
+            .get_triangle_collision(&floor, FaceCulling::OneSided)
+            .is_none());
+        let res = capsule
+            .get_triangle_collision(&floor, FaceCulling::HalfSpace)
+            .unwrap();
+        assert_eq!(res.normal, Vector3::y());
+        assert!((res.penetration - 1.25f64).abs() < 1e-9);
+        let res = capsule
+            .get_triangle_collision(&floor, FaceCulling::DoubleSided)
+            .unwrap();
+        assert_eq!(res.normal, -Vector3::y());
+        assert!((res.penetration - 0.75f64).abs() < 1e-9);
+
+        // Lying beside the floor's edge, pushed away from it.
+        let capsule = Capsule {
+            start: Vector3::new(-5f64, 0.25f64, -1f64),
+            end: Vector3::new(-5f64, 0.25f64, 1f64),
+            radius: 1.25f64,
+        };
+        let res = capsule
+            .get_triangle_collision(&floor, FaceCulling::DoubleSided)
+            .unwrap();
+        let expected = Vector3::new(-1f64, 0.25f64, 0f64).normalize();
+        assert!((res.normal - expected).magnitude() < 1e-9);
+        assert!((res.penetration - (1.25f64 - 1.0625f64.sqrt())).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,478 @@
+use super::*;
+use nalgebra::*;
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+/// A solid right circular cone. `axis` is a unit vector pointing from the
+/// apex towards the centre of the base.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct Cone<N: PhysicsScalar> {
+    pub apex: Vector3<N>,
+    pub axis: Vector3<N>,
+    pub height: N,
+    pub radius: N,
+}
+
+/// The closest point on the cone's surface along with the outward normal there.
+struct SurfacePoint<N: PhysicsScalar> {
+    point: Vector3<N>,
+    normal: Vector3<N>,
+    is_inside: bool,
+}
+
+impl<N: FloatingPhysicsScalar> Cone<N> {
+    pub fn new(apex: &Vector3<N>, axis: &Vector3<N>, height: N, radius: N) -> Self {
+        Self {
+            apex: *apex,
+            axis: axis.normalize(),
+            height,
+            radius,
+        }
+    }
+    /// Builds a cone from the angle between its axis and its side, in radians.
+    pub fn from_angle(apex: &Vector3<N>, axis: &Vector3<N>, height: N, angle: N) -> Self {
+        Self::new(apex, axis, height, height * Float::tan(angle))
+    }
+    /// The angle between the axis and the side, in radians.
+    pub fn angle(&self) -> N {
+        Float::atan2(self.radius, self.height)
+    }
+    pub fn volume(&self) -> N {
+        N::pi() * self.radius * self.radius * self.height / N::from_f64(3.0).unwrap()
+    }
+    pub fn base_center(&self) -> Vector3<N> {
+        self.apex + self.axis * self.height
+    }
+    /// Splits `point` into its distance along the axis from the apex, its
+    /// distance from the axis and the unit direction away from the axis.
+    fn cylindrical(&self, point: &Vector3<N>) -> (N, N, Vector3<N>) {
+        let v = point - self.apex;
+        let along = v.dot(&self.axis);
+        let radial = v - self.axis * along;
+        let distance = radial.magnitude();
+        let direction = if is_zero(distance) {
+            perpendicular(&self.axis)
+        } else {
+            radial / distance
+        };
+        (along, distance, direction)
+    }
+    pub fn contains_point(&self, point: &Vector3<N>) -> bool {
+        let (along, distance, _) = self.cylindrical(point);
+        along >= N::zero() && along <= self.height && distance * self.height <= self.radius * along
+    }
+    fn surface_point(&self, point: &Vector3<N>) -> SurfacePoint<N> {
+        let (along, distance, direction) = self.cylindrical(point);
+        let is_inside = along >= N::zero()
+            && along <= self.height
+            && distance * self.height <= self.radius * along;
+
+        // Work in the half plane through the axis and the point, where the
+        // side is the segment from the apex to (height, radius) and the base
+        // is the segment from (height, 0) to (height, radius).
+        let slant = self.height * self.height + self.radius * self.radius;
+        let t = num_traits::clamp(
+            (along * self.height + distance * self.radius) / slant,
+            N::zero(),
+            N::one(),
+        );
+        let side = (t * self.height, t * self.radius);
+        let base = (self.height, n_min(distance, self.radius));
+        let distance_to =
+            |(x, y): (N, N)| (x - along) * (x - along) + (y - distance) * (y - distance);
+
+        let ((x, y), normal) = if distance_to(side) < distance_to(base) {
+            let normal = (direction * self.height - self.axis * self.radius) / Float::sqrt(slant);
+            (side, normal)
+        } else {
+            (base, self.axis)
+        };
+        SurfacePoint {
+            point: self.apex + self.axis * x + direction * y,
+            normal,
+            is_inside,
+        }
+    }
+    pub fn project_point(&self, point: &Vector3<N>) -> PointProjection<Vector3<N>> {
+        let surface = self.surface_point(point);
+        PointProjection {
+            point: surface.point,
+            is_inside: surface.is_inside,
+        }
+    }
+    pub fn get_sphere_collision(
+        &self,
+        sphere: &Sphere<N>,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        let surface = self.surface_point(&sphere.center);
+        let n = surface.point - sphere.center;
+        let len = n.magnitude_squared();
+        if surface.is_inside {
+            return Some(CollisionResolution {
+                normal: -surface.normal,
+                penetration: sphere.radius + Float::sqrt(len),
+            });
+        }
+        if len > sphere.radius * sphere.radius {
+            return None;
+        }
+        let (normal, distance) = if is_zero(len) {
+            (-surface.normal, N::zero())
+        } else {
+            let d = Float::sqrt(len);
+            (n / d, d)
+        };
+        Some(CollisionResolution {
+            normal,
+            penetration: sphere.radius - distance,
+        })
+    }
+    pub fn get_aabb_collision(
+        &self,
+        aabb: &AxisAlignedBoundingBox<N>,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        self.bounding_aabb().get_aabb_collision(aabb)?;
+        if closest_points(self, aabb).is_some() {
+            return None;
+        }
+
+        // Try the box's faces, the base, the directions across the axis and
+        // each box edge, and the side of the cone facing the box.
+        let mut axes = [Vector3::zeros(); 16];
+        for i in 0..3 {
+            axes[i] = Vector3::ith(i, N::one());
+            axes[i + 3] = self.axis.cross(&Vector3::ith(i, N::one()));
+        }
+        axes[6] = self.axis;
+        axes[7] = self.surface_point(&aabb.center()).normal;
+        for (axis, corner) in axes[8..].iter_mut().zip(aabb.corners().iter()) {
+            *axis = self.surface_point(corner).normal;
+        }
+        Some(min_push(self, aabb, &axes))
+    }
+    /// Returns the closest points between the cone and the segment from
+    /// `start` to `end`, the first on the cone. They are the same point when
+    /// the segment passes through the cone.
+    pub fn closest_points_to_segment(
+        &self,
+        start: &Vector3<N>,
+        end: &Vector3<N>,
+    ) -> (Vector3<N>, Vector3<N>) {
+        let direction = end - start;
+        let closest = |t: N| {
+            let point = start + direction * t;
+            let surface = self.surface_point(&point);
+            if surface.is_inside {
+                (point, point)
+            } else {
+                (surface.point, point)
+            }
+        };
+        let distance = |t: N| {
+            let (on_cone, on_segment) = closest(t);
+            (on_cone - on_segment).magnitude_squared()
+        };
+
+        let ratio = N::from_f64(0.618_033_988_749_895).unwrap();
+        let (mut low, mut high) = (N::zero(), N::one());
+        for _ in 0..64 {
+            let step = (high - low) * ratio;
+            if distance(high - step) <= distance(low + step) {
+                high = low + step;
+            } else {
+                low = high - step;
+            }
+        }
+        closest((low + high) * N::from_f64(0.5).unwrap())
+    }
+    pub fn get_capsule_collision(
+        &self,
+        capsule: &Capsule<N>,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        self.bounding_aabb()
+            .get_aabb_collision(&capsule.bounding_aabb())?;
+
+        let (on_cone, on_segment) = self.closest_points_to_segment(&capsule.start, &capsule.end);
+        let n = on_cone - on_segment;
+        let len = n.magnitude_squared();
+        if len > capsule.radius * capsule.radius {
+            return None;
+        }
+        if !is_zero(len) {
+            let d = Float::sqrt(len);
+            return Some(CollisionResolution {
+                normal: n / d,
+                penetration: capsule.radius - d,
+            });
+        }
+
+        // The segment passes through the cone.
+        let direction = capsule.end - capsule.start;
+        let axes = [
+            self.axis,
+            direction.cross(&self.axis),
+            direction.cross(&self.surface_point(&on_segment).normal),
+            self.surface_point(&capsule.start).normal,
+            self.surface_point(&capsule.end).normal,
+            self.surface_point(&on_segment).normal,
+        ];
+        Some(min_push(self, capsule, &axes))
+    }
+}
+
+impl<N: FloatingPhysicsScalar> SupportMap<N> for Cone<N> {
+    fn support_point(&self, direction: &Vector3<N>) -> Vector3<N> {
+        let radial = direction - self.axis * direction.dot(&self.axis);
+        // Along the axis the radial part is only rounding error, which says
+        // nothing about which way to go.
+        let threshold = direction.magnitude() * Float::sqrt(N::epsilon());
+        let rim = self.base_center()
+            + radial
+                .try_normalize(threshold)
+                .unwrap_or_else(Vector3::zeros)
+                * self.radius;
+        if self.apex.dot(direction) > rim.dot(direction) {
+            self.apex
+        } else {
+            rim
+        }
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Sdf3D<N> for Cone<N> {
+    fn signed_distance(&self, point: &Vector3<N>) -> N {
+        let surface = self.surface_point(point);
+        let distance = (point - surface.point).magnitude();
+        if surface.is_inside {
+            -distance
+        } else {
+            distance
+        }
+    }
+    fn gradient(&self, point: &Vector3<N>) -> Vector3<N> {
+        let surface = self.surface_point(point);
+        if surface.is_inside {
+            surface.normal
+        } else {
+            (point - surface.point)
+                .try_normalize(N::zero())
+                .unwrap_or(surface.normal)
+        }
+    }
+}
+
+impl<N: FloatingPhysicsScalar> MassProperties<N> for Cone<N> {
+    type Vector = Vector3<N>;
+    type Inertia = Matrix3<N>;
+
+    fn mass_properties(&self, density: N) -> MassData<Vector3<N>, Matrix3<N>, N> {
+        let mass = self.volume() * density;
+        let r2 = self.radius * self.radius;
+        let h2 = self.height * self.height;
+        let along = mass * r2 * N::from_f64(0.3).unwrap();
+        let across = mass * (r2 * N::from_f64(0.15).unwrap() + h2 * N::from_f64(0.0375).unwrap());
+        let outer = self.axis * self.axis.transpose();
+        MassData {
+            mass,
+            center_of_mass: self.center(),
+            inertia: outer * along + (Matrix3::identity() - outer) * across,
+        }
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Shape3D<N> for Cone<N> {
+    fn bounding_aabb(&self) -> AxisAlignedBoundingBox<N> {
+        let base = self.base_center();
+        let rim = Vector3::from_fn(|i, _| {
+            self.radius * Float::sqrt(n_max(N::one() - self.axis[i] * self.axis[i], N::zero()))
+        });
+        AxisAlignedBoundingBox {
+            start: (base - rim).inf(&self.apex),
+            end: (base + rim).sup(&self.apex),
+        }
+    }
+    /// The smallest sphere holding the cone, which passes through the rim of
+    /// the base and, for cones taller than they are wide, the apex.
+    fn bounding_sphere(&self) -> Sphere<N> {
+        if self.height <= self.radius {
+            Sphere {
+                center: self.base_center(),
+                radius: self.radius,
+            }
+        } else {
+            let radius = (self.height * self.height + self.radius * self.radius)
+                / (self.height * N::from_f64(2.0).unwrap());
+            Sphere {
+                center: self.apex + self.axis * radius,
+                radius,
+            }
+        }
+    }
+    /// The centroid, a quarter of the way up from the base.
+    fn center(&self) -> Vector3<N> {
+        self.apex + self.axis * (self.height * N::from_f64(0.75).unwrap())
+    }
+    fn translate(&self, point: &Vector3<N>) -> Self {
+        Self {
+            apex: self.apex + point,
+            ..*self
+        }
+    }
+    fn translate_mut(&mut self, point: &Vector3<N>) {
+        self.apex += point;
+    }
+    fn set_center(&self, point: &Vector3<N>) -> Self {
+        let mut c = *self;
+        c.set_center_mut(point);
+        c
+    }
+    fn set_center_mut(&mut self, point: &Vector3<N>) {
+        let offset = point - self.center();
+        self.translate_mut(&offset);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cone() -> Cone<f64> {
+        Cone::new(
+            &Vector3::new(0f64, 2f64, 0f64),
+            &Vector3::new(0f64, -1f64, 0f64),
+            2f64,
+            1f64,
+        )
+    }
+
+    #[test]
+    fn bounding_test() {
+        let cone = cone();
+        let aabb = cone.bounding_aabb();
+        assert!((aabb.start - Vector3::new(-1f64, 0f64, -1f64)).magnitude() < 1e-9);
+        assert!((aabb.end - Vector3::new(1f64, 2f64, 1f64)).magnitude() < 1e-9);
+
+        let sphere = cone.bounding_sphere();
+        assert!((sphere.radius - 1.25f64).abs() < 1e-9);
+        assert!((sphere.center - Vector3::new(0f64, 0.75f64, 0f64)).magnitude() < 1e-9);
+        assert!((cone.angle() - 0.5f64.atan()).abs() < 1e-9);
+    }
+    #[test]
+    fn project_point_test() {
+        let cone = cone();
+        assert!(cone.contains_point(&Vector3::new(0.4f64, 1f64, 0f64)));
+        assert!(!cone.contains_point(&Vector3::new(0.6f64, 1f64, 0f64)));
+        assert!(!cone.contains_point(&Vector3::new(0f64, -0.1f64, 0f64)));
+
+        let projection = cone.project_point(&Vector3::new(0f64, 0.1f64, 0.2f64));
+        assert!(projection.is_inside);
+        assert!((projection.point - Vector3::new(0f64, 0f64, 0.2f64)).magnitude() < 1e-9);
+
+        let projection = cone.project_point(&Vector3::new(2f64, 1f64, 0f64));
+        assert!(!projection.is_inside);
+        assert!((projection.point - Vector3::new(0.8f64, 0.4f64, 0f64)).magnitude() < 1e-9);
+    }
+    #[test]
+    fn sphere_collision_test() {
+        let cone = cone();
+        let sphere = Sphere {
+            center: Vector3::new(0f64, -0.5f64, 0f64),
+            radius: 0.75f64,
+        };
+        let res = cone.get_sphere_collision(&sphere).unwrap();
+        assert!((res.normal - Vector3::y()).magnitude() < 1e-9);
+        assert!((res.penetration - 0.25f64).abs() < 1e-9);
+        assert!(cone
+            .get_sphere_collision(&sphere.translate(&Vector3::new(0f64, -0.5f64, 0f64)))
+            .is_none());
+    }
+    #[test]
+    fn aabb_collision_test() {
+        let cone = cone();
+        let aabb = AxisAlignedBoundingBox {
+            start: Vector3::new(-2f64, -1f64, -2f64),
+            end: Vector3::new(2f64, 0.25f64, 2f64),
+        };
+        let res = cone.get_aabb_collision(&aabb).unwrap();
+        assert!((res.normal - Vector3::y()).magnitude() < 1e-9);
+        assert!((res.penetration - 0.25f64).abs() < 1e-9);
+
+        // Inside the bounding box but clear of the side.
+        let aabb = AxisAlignedBoundingBox {
+            start: Vector3::new(0.8f64, 1.6f64, -0.1f64),
+            end: Vector3::new(1f64, 2f64, 0.1f64),
+        };
+        assert!(cone.get_aabb_collision(&aabb).is_none());
+    }
+    #[test]
+    fn capsule_collision_test() {
+        let cone = cone();
+        let capsule = Capsule {
+            start: Vector3::new(1f64, 1f64, -1f64),
+            end: Vector3::new(1f64, 1f64, 1f64),
+            radius: 0.5f64,
+        };
+        let res = cone.get_capsule_collision(&capsule).unwrap();
+        let normal = Vector3::new(-2f64, -1f64, 0f64).normalize();
+        assert!((res.normal - normal).magnitude() < 1e-6);
+        assert!((res.penetration - (0.5f64 - 0.2f64.sqrt())).abs() < 1e-6);
+        assert!(cone
+            .get_capsule_collision(&capsule.translate(&Vector3::new(1f64, 0f64, 0f64)))
+            .is_none());
+    }
+    #[test]
+    fn ray_test() {
+        let cone = cone();
+        let ray = Ray::new(&Vector3::new(-3f64, 1f64, 0f64), &Vector3::x());
+        assert!((ray.intersects_cone(&cone).unwrap() - 2.5f64).abs() < 1e-9);
+        let ray = Ray::new(&Vector3::new(0f64, -3f64, 0.5f64), &Vector3::y());
+        assert!((ray.intersects_cone(&cone).unwrap() - 3f64).abs() < 1e-9);
+        let ray = Ray::new(&Vector3::new(0f64, 1f64, 0f64), &Vector3::x());
+        assert_eq!(ray.intersects_cone(&cone), Some(0f64));
+        let ray = Ray::new(&Vector3::new(-3f64, 1f64, 0f64), &-Vector3::x());
+        assert_eq!(ray.intersects_cone(&cone), None);
+        let ray = Ray::new(&Vector3::new(-3f64, 2.5f64, 0f64), &Vector3::x());
+        assert_eq!(ray.intersects_cone(&cone), None);
+    }
+    #[test]
+    fn mass_matches_sampling_test() {
+        let shape = Cone::new(
+            &Vector3::new(1f64, 2f64, 3f64),
+            &Vector3::new(1f64, -2f64, 1f64),
+            2f64,
+            1f64,
+        );
+        let mass = shape.mass_properties(1f64);
+
+        let steps = 60;
+        let (min_vec, max_vec) = shape.bounding_aabb().min_max();
+        let cell = (max_vec - min_vec) / steps as f64;
+        let cell_volume = cell.x * cell.y * cell.z;
+        let outer = |r: Vector3<f64>| Matrix3::identity() * r.dot(&r) - r * r.transpose();
+        let (mut sampled_mass, mut moment, mut inertia) =
+            (0f64, Vector3::zeros(), Matrix3::zeros());
+        for i in 0..steps {
+            for j in 0..steps {
+                for k in 0..steps {
+                    let p = min_vec
+                        + Vector3::new(i as f64 + 0.5, j as f64 + 0.5, k as f64 + 0.5)
+                            .component_mul(&cell);
+                    if shape.contains_point(&p) {
+                        sampled_mass += cell_volume;
+                        moment += p * cell_volume;
+                        inertia += outer(p) * cell_volume;
+                    }
+                }
+            }
+        }
+        // Move the inertia about the origin to the center of mass.
+        let center = moment / sampled_mass;
+        let sampled_inertia = inertia - outer(center) * sampled_mass;
+
+        assert!((mass.mass - sampled_mass).abs() / sampled_mass < 0.01);
+        assert!((mass.center_of_mass - center).magnitude() < 0.01);
+        assert!((mass.inertia - sampled_inertia).norm() / sampled_inertia.norm() < 0.02);
+    }
+}
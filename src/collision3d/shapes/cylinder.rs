@@ -16,6 +16,52 @@ pub struct Cylinder<N: PhysicsScalar> {
 }
 
 impl<N: FloatingPhysicsScalar> Cylinder<N> {
+    pub fn volume(&self) -> N {
+        N::pi() * self.radius * self.radius * self.height()
+    }
+    pub fn contains_point(&self, point: &Vector3<N>) -> bool {
+        let axis = self.axis.to_num();
+        let mut radial = point - self.center;
+        let along = radial[axis];
+        radial[axis] = N::zero();
+        Float::abs(along) <= self.half_height
+            && radial.magnitude_squared() <= self.radius * self.radius
+    }
+    pub fn project_point(&self, point: &Vector3<N>) -> PointProjection<Vector3<N>> {
+        let axis = self.axis.to_num();
+        let mut radial = point - self.center;
+        let along = radial[axis];
+        radial[axis] = N::zero();
+        let distance = radial.magnitude();
+        let is_inside = Float::abs(along) <= self.half_height && distance <= self.radius;
+
+        let cap = if along < N::zero() {
+            -self.half_height
+        } else {
+            self.half_height
+        };
+        let side = if is_zero(distance) {
+            Vector3::ith((axis + 1) % 3, self.radius)
+        } else {
+            radial * (self.radius / distance)
+        };
+
+        let (along, radial) = if !is_inside {
+            let along = clamp(along, -self.half_height, self.half_height);
+            let radial = if distance > self.radius { side } else { radial };
+            (along, radial)
+        } else if self.half_height - Float::abs(along) < self.radius - distance {
+            (cap, radial)
+        } else {
+            (along, side)
+        };
+        let mut projected = self.center + radial;
+        projected[axis] += along;
+        PointProjection {
+            point: projected,
+            is_inside,
+        }
+    }
     pub fn from_aabb(aabb: &AABB3D<N>, axis: Axis) -> Self {
         let (half_height, radius) = match axis {
             Axis::X => (
@@ -148,6 +194,160 @@ impl<N: FloatingPhysicsScalar> Cylinder<N> {
 
         Self::get_collision(&collisions)
     }
+    /// Returns the closest points between the cylinder and the segment from
+    /// `start` to `end`, the first on the cylinder. They are the same point when
+    /// the segment passes through the cylinder.
+    pub fn closest_points_to_segment(
+        &self,
+        start: &Vector3<N>,
+        end: &Vector3<N>,
+    ) -> (Vector3<N>, Vector3<N>) {
+        let direction = end - start;
+        let closest = |t: N| {
+            let point = start + direction * t;
+            let projection = self.project_point(&point);
+            if projection.is_inside {
+                (point, point)
+            } else {
+                (projection.point, point)
+            }
+        };
+        let distance = |t: N| {
+            let (on_cylinder, on_segment) = closest(t);
+            (on_cylinder - on_segment).magnitude_squared()
+        };
+
+        // The distance to a convex shape is convex along the segment, so a
+        // golden-section search converges on its minimum.
+        let ratio = N::from_f64(0.618_033_988_749_895).unwrap();
+        let (mut low, mut high) = (N::zero(), N::one());
+        for _ in 0..64 {
+            let step = (high - low) * ratio;
+            if distance(high - step) <= distance(low + step) {
+                high = low + step;
+            } else {
+                low = high - step;
+            }
+        }
+        closest((low + high) * N::from_f64(0.5).unwrap())
+    }
+    /// How far the cylinder reaches along the unit vector `direction`.
+    pub(crate) fn extent(&self, direction: &Vector3<N>) -> N {
+        let along = direction[self.axis.to_num()];
+        let radial = Float::sqrt(n_max(N::one() - along * along, N::zero()));
+        self.center.dot(direction) + self.half_height * Float::abs(along) + self.radius * radial
+    }
+    pub fn get_capsule_collision(
+        &self,
+        capsule: &Capsule<N>,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        self.bounding_aabb()
+            .get_aabb_collision(&capsule.bounding_aabb())?;
+
+        let (on_cylinder, on_segment) =
+            self.closest_points_to_segment(&capsule.start, &capsule.end);
+        let n = on_cylinder - on_segment;
+        let len = n.magnitude_squared();
+        if len > capsule.radius * capsule.radius {
+            return None;
+        }
+        if !is_zero(len) {
+            let d = Float::sqrt(len);
+            return Some(CollisionResolution {
+                normal: n / d,
+                penetration: capsule.radius - d,
+            });
+        }
+
+        // The segment passes through the cylinder. Try the cylinder's axis,
+        // the direction across both axes and the radial directions towards
+        // the segment.
+        let axis = Vector3::ith(self.axis.to_num(), N::one());
+        let radial = |point: &Vector3<N>| {
+            let mut v = point - self.center;
+            v[self.axis.to_num()] = N::zero();
+            v
+        };
+        let axes = [
+            axis,
+            (capsule.end - capsule.start).cross(&axis),
+            radial(&capsule.start),
+            radial(&capsule.end),
+            radial(&on_segment),
+        ];
+        Some(min_push(self, capsule, &axes))
+    }
+    /// Returns a resolution that pushes the cylinder out of the triangle.
+    pub fn get_triangle_collision(
+        &self,
+        triangle: &Triangle<N>,
+        culling: FaceCulling,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        if culling == FaceCulling::HalfSpace {
+            return prism_collision(triangle, self, &self.center, |d| self.extent(d));
+        }
+        self.bounding_aabb()
+            .get_aabb_collision(&triangle.bounding_aabb())?;
+        if closest_points(self, triangle).is_some() {
+            return None;
+        }
+        if culling != FaceCulling::DoubleSided {
+            return culling
+                .plane_collision(&Plane::from(triangle), &self.center, |d| self.extent(d));
+        }
+
+        // Try the triangle's normal, the cylinder's axis, the directions across
+        // the axis and each edge, the radial directions towards each vertex
+        // and edge, and where a vertex or edge meets the rim, the directions
+        // between the axis and the radial one that are square to an edge.
+        let axis = Vector3::ith(self.axis.to_num(), N::one());
+        let radial = |point: &Vector3<N>| {
+            let mut v = point - self.center;
+            v[self.axis.to_num()] = N::zero();
+            v
+        };
+        let points = [triangle.point1, triangle.point2, triangle.point3];
+        let edges = [
+            points[1] - points[0],
+            points[2] - points[1],
+            points[0] - points[2],
+        ];
+        let mut axes = [Vector3::zeros(); 21];
+        axes[0] = triangle.normal();
+        axes[1] = axis;
+        axes[2] = radial(&triangle.closest_point(&self.center).0);
+        for i in 0..3 {
+            let (_, on_edge) = self.closest_points_to_segment(&points[i], &(points[i] + edges[i]));
+            let tangent = axis.cross(&radial(&points[i]));
+            axes[3 + i] = edges[i].cross(&axis);
+            axes[6 + i] = radial(&on_edge);
+            axes[9 + i] = axis.cross(&radial(&on_edge)).cross(&edges[i]);
+            axes[12 + i] = radial(&points[i]);
+            axes[15 + i] = tangent.cross(&edges[i]);
+            axes[18 + i] = tangent.cross(&edges[(i + 2) % 3]);
+        }
+        Some(min_push(self, triangle, &axes)).filter(|r| r.penetration >= N::zero())
+    }
+}
+
+impl<N: FloatingPhysicsScalar> MassProperties<N> for Cylinder<N> {
+    type Vector = Vector3<N>;
+    type Inertia = Matrix3<N>;
+
+    fn mass_properties(&self, density: N) -> MassData<Vector3<N>, Matrix3<N>, N> {
+        let mass = self.volume() * density;
+        let r2 = self.radius * self.radius;
+        let h = self.height();
+        let along = mass * r2 * N::from_f64(0.5).unwrap();
+        let across = mass * (N::from_f64(3.0).unwrap() * r2 + h * h) / N::from_f64(12.0).unwrap();
+        let mut inertia = Vector3::from_element(across);
+        inertia[self.axis.to_num()] = along;
+        MassData {
+            mass,
+            center_of_mass: self.center,
+            inertia: Matrix3::from_diagonal(&inertia),
+        }
+    }
 }
 
 impl<N: FloatingPhysicsScalar> Shape3D<N> for Cylinder<N> {
@@ -164,14 +364,8 @@ impl<N: FloatingPhysicsScalar> Shape3D<N> for Cylinder<N> {
     fn bounding_sphere(&self) -> Sphere<N> {
         Sphere {
             center: self.center,
-            radius: [self.half_height, self.radius]
-                .iter()
-                .max_by(|a, b| match a.partial_cmp(b) {
-                    Some(o) => o,
-                    None => Ordering::Equal,
-                })
-                .cloned()
-                .unwrap(),
+            // The rims are furthest from the centre.
+            radius: Float::sqrt(self.half_height * self.half_height + self.radius * self.radius),
         }
     }
     fn center(&self) -> Vector3<N> {
@@ -198,6 +392,7 @@ impl<N: FloatingPhysicsScalar> Shape3D<N> for Cylinder<N> {
     }
 }
 
+#[allow(clippy::upper_case_acronyms)]
 enum CylinderShape<N: PhysicsScalar> {
     AABB(AABB2D<N>),
     Circle(Circle<N>),
@@ -217,3 +412,166 @@ impl<N: FloatingPhysicsScalar> CylinderShape<N> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_point_test() {
+        let cylinder = Cylinder {
+            center: Vector3::new(0f32, 1f32, 0f32),
+            half_height: 1f32,
+            radius: 0.5f32,
+            axis: Axis::Y,
+        };
+
+        assert!(cylinder.contains_point(&Vector3::new(0.3f32, 1.9f32, 0.3f32)));
+        assert!(!cylinder.contains_point(&Vector3::new(0.4f32, 1f32, 0.4f32)));
+        assert_eq!(
+            cylinder.project_point(&Vector3::new(0f32, 1.9f32, 0.1f32)),
+            PointProjection {
+                point: Vector3::new(0f32, 2f32, 0.1f32),
+                is_inside: true,
+            }
+        );
+        assert_eq!(
+            cylinder.project_point(&Vector3::new(0.4f32, 1f32, 0f32)),
+            PointProjection {
+                point: Vector3::new(0.5f32, 1f32, 0f32),
+                is_inside: true,
+            }
+        );
+        assert_eq!(
+            cylinder.project_point(&Vector3::new(2f32, -1f32, 0f32)),
+            PointProjection {
+                point: Vector3::new(0.5f32, 0f32, 0f32),
+                is_inside: false,
+            }
+        );
+    }
+    #[test]
+    fn capsule_collision_test() {
+        let cylinder = Cylinder {
+            center: Vector3::new(0f64, 0f64, 0f64),
+            half_height: 1f64,
+            radius: 1f64,
+            axis: Axis::Y,
+        };
+
+        let capsule = Capsule {
+            start: Vector3::new(-2f64, 1.25f64, 0f64),
+            end: Vector3::new(2f64, 1.25f64, 0f64),
+            radius: 0.5f64,
+        };
+        let res = cylinder.get_capsule_collision(&capsule).unwrap();
+        assert!((res.normal + Vector3::y()).magnitude() < 1e-6);
+        assert!((res.penetration - 0.25).abs() < 1e-6);
+
+        let capsule = Capsule {
+            start: Vector3::new(1.4f64, -3f64, 0f64),
+            end: Vector3::new(1.4f64, 3f64, 0f64),
+            radius: 0.5f64,
+        };
+        let res = capsule.get_cylinder_collision(&cylinder).unwrap();
+        assert!((res.normal - Vector3::x()).magnitude() < 1e-6);
+        assert!((res.penetration - 0.1).abs() < 1e-6);
+
+        assert!(cylinder
+            .get_capsule_collision(&capsule.translate(&Vector3::new(0.2f64, 0f64, 0f64)))
+            .is_none());
+    }
+    #[test]
+    fn triangle_collision_test() {
+        let cylinder = Cylinder {
+            center: Vector3::new(0f64, 0.75f64, 0f64),
+            half_height: 1f64,
+            radius: 0.5f64,
+            axis: Axis::Y,
+        };
+        let floor = Triangle {
+            point1: Vector3::new(-2f64, 0f64, -2f64),
+            point2: Vector3::new(-2f64, 0f64, 2f64),
+            point3: Vector3::new(2f64, 0f64, 0f64),
+        };
+        let res = cylinder
+            .get_triangle_collision(&floor, FaceCulling::OneSided)
+            .unwrap();
+        assert_eq!(res.normal, Vector3::y());
+        assert!((res.penetration - 0.25f64).abs() < 1e-9);
+        let res = cylinder
+            .get_triangle_collision(&floor, FaceCulling::DoubleSided)
+            .unwrap();
+        assert!((res.normal - Vector3::y()).magnitude() < 1e-9);
+        assert!((res.penetration - 0.25f64).abs() < 1e-9);
+
+        // Below a one-sided floor.
+        let below = cylinder.translate(&Vector3::new(0f64, -1f64, 0f64));
+        assert!(below
+            .get_triangle_collision(&floor, FaceCulling::OneSided)
+            .is_none());
+        assert!(below
+            .get_triangle_collision(&floor, FaceCulling::DoubleSided)
+            .is_some());
+        let res = below
+            .get_triangle_collision(&floor, FaceCulling::HalfSpace)
+            .unwrap();
+        assert_eq!(res.normal, Vector3::y());
+        assert!((res.penetration - 1.25f64).abs() < 1e-9);
+
+        // A wall touching the side.
+        let wall = Triangle {
+            point1: Vector3::new(0.4f64, -1f64, -1f64),
+            point2: Vector3::new(0.4f64, 2f64, -1f64),
+            point3: Vector3::new(0.4f64, 0f64, 2f64),
+        };
+        let res = cylinder
+            .get_triangle_collision(&wall, FaceCulling::DoubleSided)
+            .unwrap();
+        assert!((res.normal + Vector3::x()).magnitude() < 1e-9);
+        assert!((res.penetration - 0.1f64).abs() < 1e-9);
+        assert!(cylinder
+            .translate(&Vector3::new(-0.2f64, 0f64, 0f64))
+            .get_triangle_collision(&wall, FaceCulling::DoubleSided)
+            .is_none());
+    }
+    #[test]
+    fn mass_matches_sampling_test() {
+        let shape = Cylinder {
+            center: Vector3::new(1f64, 2f64, 3f64),
+            half_height: 1.5f64,
+            radius: 0.5f64,
+            axis: Axis::Z,
+        };
+        let mass = shape.mass_properties(1f64);
+
+        let steps = 60;
+        let (min_vec, max_vec) = shape.bounding_aabb().min_max();
+        let cell = (max_vec - min_vec) / steps as f64;
+        let cell_volume = cell.x * cell.y * cell.z;
+        let outer = |r: Vector3<f64>| Matrix3::identity() * r.dot(&r) - r * r.transpose();
+        let (mut sampled_mass, mut moment, mut inertia) =
+            (0f64, Vector3::zeros(), Matrix3::zeros());
+        for i in 0..steps {
+            for j in 0..steps {
+                for k in 0..steps {
+                    let p = min_vec
+                        + Vector3::new(i as f64 + 0.5, j as f64 + 0.5, k as f64 + 0.5)
+                            .component_mul(&cell);
+                    if shape.contains_point(&p) {
+                        sampled_mass += cell_volume;
+                        moment += p * cell_volume;
+                        inertia += outer(p) * cell_volume;
+                    }
+                }
+            }
+        }
+        // Move the inertia about the origin to the center of mass.
+        let center = moment / sampled_mass;
+        let sampled_inertia = inertia - outer(center) * sampled_mass;
+
+        assert!((mass.mass - sampled_mass).abs() / sampled_mass < 0.01);
+        assert!((mass.center_of_mass - center).magnitude() < 0.01);
+        assert!((mass.inertia - sampled_inertia).norm() / sampled_inertia.norm() < 0.02);
+    }
+}
@@ -0,0 +1,104 @@
+use super::*;
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+/// The volume a camera sees, bounded by six planes whose normals point out of
+/// it: left, right, bottom, top, near and far.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct Frustum<N: PhysicsScalar> {
+    pub planes: [Plane<N>; 6],
+}
+
+impl<N: FloatingPhysicsScalar> Frustum<N> {
+    /// Extracts the planes of a combined view and projection matrix that maps
+    /// the visible volume to clip coordinates between -1 and 1, as
+    /// `Perspective3` and `Orthographic3` do.
+    pub fn from_view_projection(matrix: &Matrix4<N>) -> Self {
+        let row = |i: usize| matrix.row(i).transpose();
+        let plane = |v: Vector4<N>| {
+            // `v` holds the inside of the plane as `v · (x, 1) >= 0`.
+            let length = v.xyz().magnitude();
+            Plane {
+                normal: -v.xyz() / length,
+                d: -v.w / length,
+            }
+        };
+        let w = row(3);
+        Frustum {
+            planes: [
+                plane(w + row(0)),
+                plane(w - row(0)),
+                plane(w + row(1)),
+                plane(w - row(1)),
+                plane(w + row(2)),
+                plane(w - row(2)),
+            ],
+        }
+    }
+    pub fn contains_point(&self, point: &Vector3<N>) -> bool {
+        self.planes.iter().all(|p| p.contains_point(point))
+    }
+    /// Whether the sphere may be visible. Spheres near the corners of the
+    /// frustum can be reported when they are just outside.
+    pub fn intersects_sphere(&self, sphere: &Sphere<N>) -> bool {
+        self.planes
+            .iter()
+            .all(|p| p.distance(&sphere.center) <= sphere.radius)
+    }
+    /// Whether the box may be visible. Like `intersects_sphere` this only
+    /// rejects boxes entirely outside one of the planes.
+    pub fn intersects_aabb(&self, aabb: &AxisAlignedBoundingBox<N>) -> bool {
+        let (min, max) = aabb.min_max();
+        self.planes.iter().all(|p| {
+            // The corner furthest behind the plane.
+            let corner = Vector3::from_fn(|i, _| {
+                if p.normal[i] > N::zero() {
+                    min[i]
+                } else {
+                    max[i]
+                }
+            });
+            p.contains_point(&corner)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn view_projection_test() {
+        let projection = Perspective3::new(1f64, core::f64::consts::FRAC_PI_2, 1f64, 10f64);
+        let view = Isometry3::look_at_rh(
+            &Point3::new(0f64, 0f64, 5f64),
+            &Point3::origin(),
+            &Vector3::y(),
+        );
+        let frustum =
+            Frustum::from_view_projection(&(projection.as_matrix() * view.to_homogeneous()));
+
+        assert!(frustum.contains_point(&Vector3::zeros()));
+        assert!(frustum.contains_point(&Vector3::new(4.9f64, 0f64, 0f64)));
+        assert!(!frustum.contains_point(&Vector3::new(5.1f64, 0f64, 0f64)));
+        assert!(!frustum.contains_point(&Vector3::new(0f64, 0f64, 4.5f64)));
+        assert!(!frustum.contains_point(&Vector3::new(0f64, 0f64, -5.5f64)));
+        assert!((frustum.planes[4].distance(&Vector3::new(0f64, 0f64, 3f64)) + 1f64).abs() < 1e-9);
+
+        let sphere = Sphere {
+            center: Vector3::new(0f64, 0f64, -5.5f64),
+            radius: 1f64,
+        };
+        assert!(frustum.intersects_sphere(&sphere));
+        assert!(!frustum.intersects_sphere(&sphere.translate(&Vector3::new(0f64, 0f64, -1.5f64))));
+
+        let aabb = AxisAlignedBoundingBox {
+            start: Vector3::new(4f64, -1f64, -1f64),
+            end: Vector3::new(6f64, 1f64, 1f64),
+        };
+        assert!(frustum.intersects_aabb(&aabb));
+        assert!(!frustum.intersects_aabb(&aabb.translate(&Vector3::new(2.5f64, 0f64, 0f64))));
+    }
+}
@@ -19,6 +19,22 @@ pub use ray::*;
 pub mod cylinder;
 pub use cylinder::*;
 
+pub mod cone;
+pub use cone::*;
+
+pub mod frustum;
+pub use frustum::*;
+
+#[cfg(feature = "alloc")]
+pub mod triangle_mesh;
+#[cfg(feature = "alloc")]
+pub use triangle_mesh::*;
+
+#[cfg(feature = "alloc")]
+pub mod voxel_grid;
+#[cfg(feature = "alloc")]
+pub use voxel_grid::*;
+
 use super::*;
 
 pub trait Shape3D<N : PhysicsScalar> {
@@ -45,6 +45,16 @@ impl<N: FloatingPhysicsScalar> Plane<N> {
     pub fn closest_point(&self, point: &Vector3<N>) -> Vector3<N> {
         point - self.normal * self.distance(point)
     }
+    /// Treats everything behind the plane as solid.
+    pub fn contains_point(&self, point: &Vector3<N>) -> bool {
+        self.distance(point) <= N::zero()
+    }
+    pub fn project_point(&self, point: &Vector3<N>) -> PointProjection<Vector3<N>> {
+        PointProjection {
+            point: self.closest_point(point),
+            is_inside: self.contains_point(point),
+        }
+    }
 }
 
 impl<N: FloatingPhysicsScalar> Shape3D<N> for Plane<N> {
@@ -60,15 +70,26 @@ impl<N: FloatingPhysicsScalar> Shape3D<N> for Plane<N> {
             radius: Bounded::max_value(),
         }
     }
+    /// The point of the plane closest to the origin.
     fn center(&self) -> Vector3<N> {
-        Vector3::from_element(N::zero())
+        self.closest_point(&Vector3::zeros())
+    }
+    /// Moves the plane along with everything on it, keeping its normal.
+    fn translate_mut(&mut self, point: &Vector3<N>) {
+        self.d -= self.normal.dot(point);
+    }
+    /// Moves the plane so that it passes through `point`.
+    fn set_center_mut(&mut self, point: &Vector3<N>) {
+        self.d = -self.normal.dot(point);
     }
-    fn translate_mut(&mut self, _: &Vector3<N>) {}
-    fn set_center_mut(&mut self, _: &Vector3<N>) {}
-    fn translate(&self, _: &Vector3<N>) -> Self {
-        *self
+    fn translate(&self, point: &Vector3<N>) -> Self {
+        let mut plane = *self;
+        plane.translate_mut(point);
+        plane
     }
-    fn set_center(&self, _: &Vector3<N>) -> Self {
-        *self
+    fn set_center(&self, point: &Vector3<N>) -> Self {
+        let mut plane = *self;
+        plane.set_center_mut(point);
+        plane
     }
 }
@@ -1,11 +1,19 @@
 use super::*;
+use arrayvec::ArrayVec;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Ray<N: PhysicsScalar> {
-    point: Vector3<N>,
-    direction: Vector3<N>,
+    pub point: Vector3<N>,
+    pub direction: Vector3<N>,
 }
 
 impl<N: FloatingPhysicsScalar> Ray<N> {
+    pub fn new(point: &Vector3<N>, direction: &Vector3<N>) -> Self {
+        Ray {
+            point: *point,
+            direction: *direction,
+        }
+    }
     pub fn get_point(&self, distance: N) -> Vector3<N> {
         self.point + self.direction * distance
     }
@@ -22,11 +30,69 @@ impl<N: FloatingPhysicsScalar> Ray<N> {
             Some(p.dot(&plane.normal) / denom)
         }
     }
+    /// Returns the distance along the ray to where it enters the box, or zero
+    /// when it starts inside.
+    pub fn intersects_aabb(&self, aabb: &AxisAlignedBoundingBox<N>) -> Option<N> {
+        let (min, max) = aabb.min_max();
+        slab_entry(
+            min.as_slice(),
+            max.as_slice(),
+            self.point.as_slice(),
+            self.direction.as_slice(),
+            Bounded::max_value(),
+        )
+    }
+    /// Returns the distance along the ray to where it enters the cone, or zero
+    /// when it starts inside.
+    pub fn intersects_cone(&self, cone: &Cone<N>) -> Option<N> {
+        if cone.contains_point(&self.point) {
+            return Some(N::zero());
+        }
+        let two = N::from_f64(2.0).unwrap();
+        let v = self.point - cone.apex;
+        let va = v.dot(&cone.axis);
+        let da = self.direction.dot(&cone.axis);
+        let h2 = cone.height * cone.height;
+        let cos2 = h2 / (h2 + cone.radius * cone.radius);
+
+        // Where the ray meets the infinite double cone around the axis.
+        let a = da * da - cos2 * self.direction.dot(&self.direction);
+        let b = (da * va - cos2 * self.direction.dot(&v)) * two;
+        let c = va * va - cos2 * v.dot(&v);
+        let mut hits = ArrayVec::<[N; 3]>::new();
+        if is_zero(a) {
+            if !is_zero(b) {
+                hits.push(-c / b);
+            }
+        } else {
+            let discriminant = b * b - a * c * two * two;
+            if discriminant >= N::zero() {
+                let root = Float::sqrt(discriminant);
+                hits.push((-b - root) / (a * two));
+                hits.push((-b + root) / (a * two));
+            }
+        }
+        hits.retain(|t| {
+            let along = va + da * *t;
+            along >= N::zero() && along <= cone.height
+        });
+
+        if !is_zero(da) {
+            let t = (cone.height - va) / da;
+            let offset = self.get_point(t) - cone.base_center();
+            if offset.magnitude_squared() <= cone.radius * cone.radius {
+                hits.push(t);
+            }
+        }
+        hits.into_iter()
+            .filter(|t| *t >= N::zero())
+            .min_by(|a, b| n_ordering(*a, *b))
+    }
 }
 
 impl<N: FloatingPhysicsScalar> From<&Capsule<N>> for Ray<N> {
     fn from(capsule: &Capsule<N>) -> Self {
-        let direction = capsule.direction();
+        let direction = -capsule.direction();
         Ray {
             direction,
             point: capsule.start,
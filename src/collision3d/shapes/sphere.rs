@@ -14,6 +14,24 @@ pub struct Sphere<N: PhysicsScalar> {
 }
 
 impl<N: FloatingPhysicsScalar> Sphere<N> {
+    pub fn volume(&self) -> N {
+        N::from_f64(4.0 / 3.0).unwrap() * N::pi() * self.radius.pow(N::from_usize(3).unwrap())
+    }
+    pub fn contains_point(&self, point: &Vector3<N>) -> bool {
+        (point - self.center).magnitude_squared() <= self.radius * self.radius
+    }
+    pub fn project_point(&self, point: &Vector3<N>) -> PointProjection<Vector3<N>> {
+        let n = point - self.center;
+        let direction = if is_zero(n.magnitude_squared()) {
+            Vector3::x()
+        } else {
+            n.normalize()
+        };
+        PointProjection {
+            point: self.center + direction * self.radius,
+            is_inside: self.contains_point(point),
+        }
+    }
     pub fn get_sphere_collision(
         &self,
         sphere: &Sphere<N>,
@@ -38,54 +56,46 @@ impl<N: FloatingPhysicsScalar> Sphere<N> {
             penetration,
         })
     }
+    /// How far the sphere reaches along the unit vector `direction`.
+    pub(crate) fn extent(&self, direction: &Vector3<N>) -> N {
+        self.center.dot(direction) + self.radius
+    }
     pub fn get_plane_collision(
         &self,
         plane: &Plane<N>,
+        culling: FaceCulling,
     ) -> Option<CollisionResolution<Vector3<N>, N>> {
-        let dist = plane.distance(&self.center);
-        if dist < N::zero() || dist > self.radius {
-            None
-        } else {
-            Some(CollisionResolution {
-                normal: plane.normal,
-                penetration: self.radius - dist,
-            })
-        }
+        culling.plane_collision(plane, &self.center, |d| self.extent(d))
     }
+    /// Returns a resolution that pushes the sphere out of the triangle. When
+    /// double-sided the sphere is pushed away from the closest point of the
+    /// triangle.
     pub fn get_triangle_collision(
         &self,
         triangle: &Triangle<N>,
-        double_sided: bool,
+        culling: FaceCulling,
     ) -> Option<CollisionResolution<Vector3<N>, N>> {
-        let normal = triangle.normal();
-        let dist = (self.center - triangle.point1).dot(&normal);
-        if double_sided {
-            if Float::abs(dist) > self.radius {
-                return None;
-            }
-        } else {
-            if dist < N::zero() || dist > self.radius {
-                return None;
-            }
+        if culling == FaceCulling::HalfSpace {
+            return prism_collision(triangle, self, &self.center, |d| self.extent(d));
         }
-
-        let point = self.center - normal * dist;
-        if triangle.contains(&point) {
+        let (point, distance) = triangle.closest_point(&self.center);
+        if distance > self.radius {
+            return None;
+        }
+        if culling != FaceCulling::DoubleSided {
+            let plane = Plane::from(triangle);
+            return culling.plane_collision(&plane, &self.center, |d| self.extent(d));
+        }
+        if is_zero(distance) {
             return Some(CollisionResolution {
-                normal,
-                penetration: self.radius - dist,
+                normal: triangle.normal(),
+                penetration: self.radius,
             });
         }
-
-        let (_, distance) = triangle.closest_point(&self.center);
-        if distance < self.radius {
-            Some(CollisionResolution {
-                normal,
-                penetration: self.radius - dist,
-            })
-        } else {
-            None
-        }
+        Some(CollisionResolution {
+            normal: (self.center - point) / distance,
+            penetration: self.radius - distance,
+        })
     }
     pub fn get_capsule_collision(
         &self,
@@ -97,6 +107,107 @@ impl<N: FloatingPhysicsScalar> Sphere<N> {
             radius: capsule.radius,
         })
     }
+    /// Pushes the sphere out along the field's gradient at its centre.
+    pub fn get_sdf_collision<S: Sdf3D<N> + ?Sized>(
+        &self,
+        sdf: &S,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        let distance = sdf.signed_distance(&self.center);
+        if distance > self.radius {
+            return None;
+        }
+        Some(CollisionResolution {
+            normal: sdf.gradient(&self.center),
+            penetration: self.radius - distance,
+        })
+    }
+    /// The smallest sphere holding every point, by Welzl's algorithm. The
+    /// points are shuffled first so sorted input, such as the vertices of a
+    /// mesh, still takes expected linear time. No points give a sphere of
+    /// radius zero at the origin.
+    #[cfg(feature = "alloc")]
+    pub fn from_points<I: IntoIterator<Item = Vector3<N>>>(points: I) -> Self {
+        let mut points: Vec<_> = points.into_iter().collect();
+        shuffle(&mut points);
+        Self::enclosing(&points)
+    }
+    /// `from_points` without the shuffle, for a few points.
+    pub(crate) fn enclosing(points: &[Vector3<N>]) -> Self {
+        smallest_ball(points)
+    }
+    fn through_two(a: &Vector3<N>, b: &Vector3<N>) -> Self {
+        Sphere {
+            center: (a + b) * N::from_f64(0.5).unwrap(),
+            radius: (a - b).magnitude() * N::from_f64(0.5).unwrap(),
+        }
+    }
+    /// The smallest sphere with all three points on its surface, which is
+    /// centred on their circumcircle. Collinear points give the sphere through
+    /// the two furthest apart.
+    fn through_three(a: &Vector3<N>, b: &Vector3<N>, c: &Vector3<N>) -> Self {
+        let (ab, ac) = (b - a, c - a);
+        let n = ab.cross(&ac);
+        let n2 = n.magnitude_squared();
+        if n2 <= ab.magnitude_squared() * ac.magnitude_squared() * N::epsilon() {
+            return [
+                Self::through_two(a, b),
+                Self::through_two(a, c),
+                Self::through_two(b, c),
+            ]
+            .iter()
+            .copied()
+            .max_by(|x, y| n_ordering(x.radius, y.radius))
+            .unwrap();
+        }
+        let offset = (n.cross(&ab) * ac.magnitude_squared()
+            + ac.cross(&n) * ab.magnitude_squared())
+            / (n2 * N::from_f64(2.0).unwrap());
+        Sphere {
+            center: a + offset,
+            radius: offset.magnitude(),
+        }
+    }
+    /// The sphere with all four points on its surface. Coplanar points have
+    /// none, so the smallest sphere through three of them that holds the
+    /// fourth is used instead.
+    fn through_four(a: &Vector3<N>, b: &Vector3<N>, c: &Vector3<N>, d: &Vector3<N>) -> Self {
+        let (ab, ac, ad) = (b - a, c - a, d - a);
+        let m = Matrix3::from_rows(&[ab.transpose(), ac.transpose(), ad.transpose()]);
+        let scale = ab.magnitude() * ac.magnitude() * ad.magnitude();
+        if Float::abs(m.determinant()) > scale * N::epsilon() * N::from_f64(1024.0).unwrap() {
+            if let Some(inverse) = m.try_inverse() {
+                let half = N::from_f64(0.5).unwrap();
+                let offset = inverse
+                    * Vector3::new(
+                        ab.magnitude_squared() * half,
+                        ac.magnitude_squared() * half,
+                        ad.magnitude_squared() * half,
+                    );
+                return Sphere {
+                    center: a + offset,
+                    radius: offset.magnitude(),
+                };
+            }
+        }
+        let candidates = [
+            (Self::through_three(a, b, c), d),
+            (Self::through_three(a, b, d), c),
+            (Self::through_three(a, c, d), b),
+            (Self::through_three(b, c, d), a),
+        ];
+        candidates
+            .iter()
+            .filter(|(sphere, rest)| sphere.holds(rest))
+            .map(|(sphere, _)| *sphere)
+            .min_by(|x, y| n_ordering(x.radius, y.radius))
+            .unwrap_or_else(|| {
+                candidates
+                    .iter()
+                    .map(|(sphere, _)| *sphere)
+                    .max_by(|x, y| n_ordering(x.radius, y.radius))
+                    .unwrap()
+            })
+    }
     pub fn to_circle(&self, horizontal: Axis, vertical: Axis) -> Circle<N> {
         debug_assert_ne!(horizontal, vertical);
         let x = match horizontal {
@@ -116,6 +227,52 @@ impl<N: FloatingPhysicsScalar> Sphere<N> {
     }
 }
 
+impl<N: FloatingPhysicsScalar> Ball<N> for Sphere<N> {
+    type Point = Vector3<N>;
+    const MAX_BOUNDARY: usize = 4;
+
+    fn radius(&self) -> N {
+        self.radius
+    }
+    fn with_radius(&self, radius: N) -> Self {
+        Sphere { radius, ..*self }
+    }
+    fn distance(&self, point: &Vector3<N>) -> N {
+        (point - self.center).magnitude()
+    }
+    fn through(boundary: &[Vector3<N>]) -> Self {
+        match *boundary {
+            [] => Sphere {
+                center: Vector3::zeros(),
+                radius: N::zero(),
+            },
+            [a] => Sphere {
+                center: a,
+                radius: N::zero(),
+            },
+            [a, b] => Self::through_two(&a, &b),
+            [a, b, c] => Self::through_three(&a, &b, &c),
+            [a, b, c, d, ..] => Self::through_four(&a, &b, &c, &d),
+        }
+    }
+}
+
+impl<N: FloatingPhysicsScalar> MassProperties<N> for Sphere<N> {
+    type Vector = Vector3<N>;
+    type Inertia = Matrix3<N>;
+
+    fn mass_properties(&self, density: N) -> MassData<Vector3<N>, Matrix3<N>, N> {
+        let mass = self.volume() * density;
+        MassData {
+            mass,
+            center_of_mass: self.center,
+            inertia: Matrix3::from_diagonal_element(
+                N::from_f64(0.4).unwrap() * mass * self.radius * self.radius,
+            ),
+        }
+    }
+}
+
 impl<N: FloatingPhysicsScalar> Shape3D<N> for Sphere<N> {
     fn bounding_aabb(&self) -> AxisAlignedBoundingBox<N> {
         AxisAlignedBoundingBox {
@@ -148,3 +305,49 @@ impl<N: FloatingPhysicsScalar> Shape3D<N> for Sphere<N> {
         self.center = *point;
     }
 }
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_points_test() {
+        // The corners of a cube, with its centre and a point on a face.
+        let mut points: Vec<_> = (0..8)
+            .map(|i| Vector3::from_fn(|k, _| if i & (1 << k) != 0 { 1f64 } else { -1f64 }))
+            .collect();
+        points.push(Vector3::zeros());
+        points.push(Vector3::new(1f64, 0.5f64, 0f64));
+        let sphere = Sphere::from_points(points);
+        assert!(sphere.center.magnitude() < 1e-9);
+        assert!((sphere.radius - 3f64.sqrt()).abs() < 1e-9);
+
+        // A regular tetrahedron needs all four points.
+        let sphere = Sphere::from_points(vec![
+            Vector3::new(1f64, 1f64, 1f64),
+            Vector3::new(1f64, -1f64, -1f64),
+            Vector3::new(-1f64, 1f64, -1f64),
+            Vector3::new(-1f64, -1f64, 1f64),
+        ]);
+        assert!(sphere.center.magnitude() < 1e-9);
+        assert!((sphere.radius - 3f64.sqrt()).abs() < 1e-9);
+
+        let single = Sphere::from_points(Some(Vector3::new(1f64, 2f64, 3f64)));
+        assert_eq!(single.center, Vector3::new(1f64, 2f64, 3f64));
+        assert_eq!(single.radius, 0f64);
+        assert_eq!(Sphere::<f64>::from_points(None).radius, 0f64);
+    }
+    #[test]
+    fn mass_test() {
+        let sphere = Sphere {
+            center: Vector3::new(1f64, 2f64, 3f64),
+            radius: 2f64,
+        };
+        let mass = sphere.mass_properties(3f64);
+
+        let expected = 32f64 * std::f64::consts::PI;
+        assert!((mass.mass - expected).abs() < 1e-9);
+        assert_eq!(mass.center_of_mass, sphere.center);
+        assert!((mass.inertia - Matrix3::from_diagonal_element(1.6f64 * expected)).norm() < 1e-9);
+    }
+}
@@ -1,4 +1,5 @@
 use super::*;
+use arrayvec::ArrayVec;
 use nalgebra::*;
 
 #[cfg(feature = "serde-serialize")]
@@ -14,6 +15,15 @@ pub struct Triangle<N: PhysicsScalar> {
 
 
 impl<N: FloatingPhysicsScalar> Triangle<N> {
+    pub fn area(&self) -> N {
+        (self.point2 - self.point1)
+            .cross(&(self.point3 - self.point1))
+            .magnitude()
+            * N::from_f64(0.5).unwrap()
+    }
+    pub fn centroid(&self) -> Vector3<N> {
+        self.center()
+    }
     pub fn x_width(&self) -> N {
         let arr = [self.point1.x, self.point2.x, self.point3.x];
         let min_value = arr.iter().min_by(|&a, &b| n_ordering(*a, *b)).unwrap();
@@ -37,9 +47,12 @@ impl<N: FloatingPhysicsScalar> Triangle<N> {
             .cross(&(self.point3 - self.point1))
             .normalize()
     }
+    /// Returns the point on the triangle closest to `point` and the distance between them.
     pub fn closest_point(&self, point: &Vector3<N>) -> (Vector3<N>, N) {
-        if self.contains(&point) {
-            return (*point, N::zero());
+        let distance = self.distance(point);
+        let projected = point - self.normal() * distance;
+        if self.contains(&projected) {
+            return (projected, Float::abs(distance));
         }
 
         let points = [
@@ -50,9 +63,9 @@ impl<N: FloatingPhysicsScalar> Triangle<N> {
 
         points
             .iter()
-            .map(|p| (point, (point - p).norm_squared()))
+            .map(|p| (*p, (point - p).norm_squared()))
             .min_by(|(_, dist1), (_, dist2)| n_ordering(*dist1, *dist2))
-            .map(|(&point, p)| (point, p))
+            .map(|(p, dist)| (p, Float::sqrt(dist)))
             .unwrap()
     }
     pub fn contains(&self, point: &Vector3<N>) -> bool {
@@ -85,6 +98,124 @@ impl<N: FloatingPhysicsScalar> Triangle<N> {
     pub fn distance(&self, point: &Vector3<N>) -> N {
         (point - self.point1).dot(&self.normal())
     }
+    fn points(&self) -> [Vector3<N>; 3] {
+        [self.point1, self.point2, self.point3]
+    }
+    fn edges(&self) -> [Vector3<N>; 3] {
+        [
+            self.point2 - self.point1,
+            self.point3 - self.point2,
+            self.point1 - self.point3,
+        ]
+    }
+    /// The part of the triangle on the plane through `point` with `normal`, as
+    /// the two ends of a segment. `None` when the triangle is entirely on one
+    /// side of it.
+    fn plane_section(
+        &self,
+        normal: &Vector3<N>,
+        point: &Vector3<N>,
+    ) -> Option<(Vector3<N>, Vector3<N>)> {
+        let points = self.points();
+        let distances = [
+            (points[0] - point).dot(normal),
+            (points[1] - point).dot(normal),
+            (points[2] - point).dot(normal),
+        ];
+        let mut section: ArrayVec<[Vector3<N>; 3]> = ArrayVec::new();
+        for i in 0..3 {
+            let j = (i + 1) % 3;
+            let (di, dj) = (distances[i], distances[j]);
+            if is_zero(di) {
+                section.push(points[i]);
+            } else if !is_zero(dj) && (di < N::zero()) != (dj < N::zero()) {
+                section.push(points[i] + (points[j] - points[i]) * (di / (di - dj)));
+            }
+        }
+        // Three points only when the whole triangle is on the plane, so keep
+        // the two furthest apart.
+        let pairs: &[(usize, usize)] = match section.len() {
+            0 => return None,
+            1 => &[(0, 0)],
+            2 => &[(0, 1)],
+            _ => &[(0, 1), (0, 2), (1, 2)],
+        };
+        pairs
+            .iter()
+            .map(|&(i, j)| (section[i], section[j]))
+            .max_by(|(a, b), (c, d)| {
+                n_ordering((a - b).magnitude_squared(), (c - d).magnitude_squared())
+            })
+    }
+    /// Returns the segment where the two triangles cross, running along
+    /// `self.normal().cross(&other.normal())`. `None` when they are apart or
+    /// coplanar, as coplanar triangles overlap in an area rather than a
+    /// segment; `get_triangle_collision` reports those as touching. Also
+    /// `None` when either triangle is squashed flat and has no normal.
+    pub fn intersection_segment(&self, other: &Triangle<N>) -> Option<(Vector3<N>, Vector3<N>)> {
+        // Relative to the edges, so a squashed triangle has none.
+        let plane_normal = |t: &Triangle<N>| {
+            let (u, v) = (t.point2 - t.point1, t.point3 - t.point1);
+            let scale = n_max(u.magnitude_squared(), v.magnitude_squared());
+            u.cross(&v).try_normalize(Float::sqrt(N::epsilon()) * scale)
+        };
+        let (normal, other_normal) = (plane_normal(self)?, plane_normal(other)?);
+        // Planes closer to parallel than this meet along a line rounding
+        // cannot place.
+        let direction = normal
+            .cross(&other_normal)
+            .try_normalize(Float::sqrt(N::epsilon()))?;
+        let (a_start, a_end) = self.plane_section(&other_normal, &other.point1)?;
+        let (b_start, b_end) = other.plane_section(&normal, &self.point1)?;
+
+        let t = |p: &Vector3<N>| p.dot(&direction);
+        let (a_min, a_max) = (n_min(t(&a_start), t(&a_end)), n_max(t(&a_start), t(&a_end)));
+        let (b_min, b_max) = (n_min(t(&b_start), t(&b_end)), n_max(t(&b_start), t(&b_end)));
+        let (start, end) = (n_max(a_min, b_min), n_min(a_max, b_max));
+        if start > end {
+            return None;
+        }
+        // Both sections lie on the line where the planes meet.
+        let at = |s: N| a_start + direction * (s - t(&a_start));
+        Some((at(start), at(end)))
+    }
+    /// Returns a resolution that pushes `self` out of `other`, found by testing
+    /// each separating axis of two triangles: both normals, the directions
+    /// across each pair of edges and, for coplanar triangles, the in-plane
+    /// normals of every edge. Coplanar and degenerate triangles that meet
+    /// only touch, with zero penetration.
+    pub fn get_triangle_collision(
+        &self,
+        other: &Triangle<N>,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        // Flat boxes, as coplanar triangles have, only ever touch.
+        self.bounding_aabb().intersection(&other.bounding_aabb())?;
+
+        let (normal, other_normal) = (self.normal(), other.normal());
+        let (edges, other_edges) = (self.edges(), other.edges());
+        let mut axes = [Vector3::zeros(); 17];
+        axes[0] = normal;
+        axes[1] = other_normal;
+        for i in 0..3 {
+            for j in 0..3 {
+                axes[2 + i * 3 + j] = edges[i].cross(&other_edges[j]);
+            }
+            axes[11 + i] = normal.cross(&edges[i]);
+            axes[14 + i] = other_normal.cross(&other_edges[i]);
+        }
+        // The axes miss gaps between flat and squashed triangles, whose normals
+        // vanish, so GJK decides whether they touch.
+        if closest_points(self, other).is_some() {
+            return None;
+        }
+        let mut resolution = min_push(self, other, &axes);
+        // Rounding can leave coplanar triangles a hair apart, and two that
+        // have squashed to points have no axis at all.
+        if resolution.penetration < N::zero() || resolution.penetration == Bounded::max_value() {
+            resolution.penetration = N::zero();
+        }
+        Some(resolution)
+    }
     fn get_x<F: FnMut(N, &N) -> N>(&self, f: F) -> N {
         [self.point1.x, self.point2.x].iter().fold(self.point3.x, f)
     }
@@ -121,6 +252,30 @@ impl<N: FloatingPhysicsScalar> Triangle<N> {
     }
 }
 
+/// Treats the triangle as a thin plate, so `density` is mass per unit area.
+impl<N: FloatingPhysicsScalar> MassProperties<N> for Triangle<N> {
+    type Vector = Vector3<N>;
+    type Inertia = Matrix3<N>;
+
+    fn mass_properties(&self, density: N) -> MassData<Vector3<N>, Matrix3<N>, N> {
+        let mass = self.area() * density;
+        let centroid = self.centroid();
+        let covariance = [self.point1, self.point2, self.point3]
+            .iter()
+            .map(|p| {
+                let v = p - centroid;
+                v * v.transpose()
+            })
+            .fold(Matrix3::zeros(), |acc, m| acc + m)
+            * (mass / N::from_f64(12.0).unwrap());
+        MassData {
+            mass,
+            center_of_mass: centroid,
+            inertia: Matrix3::from_diagonal_element(covariance.trace()) - covariance,
+        }
+    }
+}
+
 impl<N: FloatingPhysicsScalar> Shape3D<N> for Triangle<N> {
     fn bounding_aabb(&self) -> AxisAlignedBoundingBox<N> {
         AxisAlignedBoundingBox {
@@ -129,7 +284,7 @@ impl<N: FloatingPhysicsScalar> Shape3D<N> for Triangle<N> {
         }
     }
     fn bounding_sphere(&self) -> Sphere<N> {
-        self.bounding_aabb().bounding_sphere()
+        Sphere::enclosing(&[self.point1, self.point2, self.point3])
     }
     fn center(&self) -> Vector3<N> {
         (self.point1 + self.point2 + self.point3) / N::from_usize(3).unwrap()
@@ -161,3 +316,106 @@ impl<N: FloatingPhysicsScalar> Shape3D<N> for Triangle<N> {
         self.point3 = new_center + dist3;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mass_test() {
+        let triangle = Triangle {
+            point1: Vector3::new(0f32, 0f32, 0f32),
+            point2: Vector3::new(1f32, 0f32, 0f32),
+            point3: Vector3::new(0f32, 1f32, 0f32),
+        };
+        let mass = triangle.mass_properties(1f32);
+
+        assert_eq!(triangle.area(), 0.5f32);
+        assert_eq!(mass.mass, 0.5f32);
+        assert!((mass.inertia[(0, 0)] - 1f32 / 36f32).abs() < 1e-6);
+        assert!((mass.inertia[(1, 1)] - 1f32 / 36f32).abs() < 1e-6);
+        assert!((mass.inertia[(2, 2)] - 1f32 / 18f32).abs() < 1e-6);
+    }
+    #[test]
+    fn bounding_sphere_test() {
+        // Obtuse triangles are bounded by their longest edge.
+        let obtuse = Triangle {
+            point1: Vector3::new(-2f64, 0f64, 0f64),
+            point2: Vector3::new(2f64, 0f64, 0f64),
+            point3: Vector3::new(0f64, 1f64, 1f64),
+        };
+        let sphere = obtuse.bounding_sphere();
+        assert!((sphere.center - Vector3::zeros()).magnitude() < 1e-9);
+        assert!((sphere.radius - 2f64).abs() < 1e-9);
+
+        // Acute ones by their circumcircle.
+        let equilateral = Triangle {
+            point1: Vector3::new(1f64, 0f64, 0f64),
+            point2: Vector3::new(0f64, 1f64, 0f64),
+            point3: Vector3::new(0f64, 0f64, 1f64),
+        };
+        let sphere = equilateral.bounding_sphere();
+        assert!((sphere.center - Vector3::from_element(1f64 / 3f64)).magnitude() < 1e-9);
+        assert!((sphere.radius - (2f64 / 3f64).sqrt()).abs() < 1e-9);
+    }
+    #[test]
+    fn triangle_collision_test() {
+        let floor = Triangle {
+            point1: Vector3::new(-2f64, 0f64, -2f64),
+            point2: Vector3::new(-2f64, 0f64, 2f64),
+            point3: Vector3::new(2f64, 0f64, 0f64),
+        };
+        // Upright, dipping a quarter below the floor.
+        let wall = Triangle {
+            point1: Vector3::new(0f64, -0.25f64, 0f64),
+            point2: Vector3::new(-1f64, 1f64, 0f64),
+            point3: Vector3::new(1f64, 1f64, 0f64),
+        };
+
+        let (start, end) = wall.intersection_segment(&floor).unwrap();
+        assert!((start - Vector3::new(-0.2f64, 0f64, 0f64)).magnitude() < 1e-9);
+        assert!((end - Vector3::new(0.2f64, 0f64, 0f64)).magnitude() < 1e-9);
+        let (start, end) = floor.intersection_segment(&wall).unwrap();
+        assert!((start - Vector3::new(0.2f64, 0f64, 0f64)).magnitude() < 1e-9);
+        assert!((end - Vector3::new(-0.2f64, 0f64, 0f64)).magnitude() < 1e-9);
+
+        let res = wall.get_triangle_collision(&floor).unwrap();
+        assert!((res.normal - Vector3::y()).magnitude() < 1e-9);
+        assert!((res.penetration - 0.25f64).abs() < 1e-9);
+
+        let lifted = wall.translate(&Vector3::new(0f64, 0.5f64, 0f64));
+        assert!(lifted.intersection_segment(&floor).is_none());
+        assert!(lifted.get_triangle_collision(&floor).is_none());
+
+        // Coplanar triangles overlap without crossing.
+        let coplanar = floor.translate(&Vector3::new(1f64, 0f64, 0f64));
+        assert!(coplanar.intersection_segment(&floor).is_none());
+        let res = coplanar.get_triangle_collision(&floor).unwrap();
+        assert_eq!(res.normal.y.abs(), 1f64);
+        assert_eq!(res.penetration, 0f64);
+        // Beside the floor's long edge, inside its bounding box.
+        let beside = Triangle {
+            point1: Vector3::new(1f64, 0f64, 2f64),
+            point2: Vector3::new(2f64, 0f64, 2f64),
+            point3: Vector3::new(2f64, 0f64, 1f64),
+        };
+        assert!(beside.get_triangle_collision(&floor).is_none());
+
+        // Squashed to a segment through the floor, and to points.
+        let segment = Triangle {
+            point1: Vector3::new(0f64, -1f64, 0.2f64),
+            point2: Vector3::new(0f64, 1f64, 0.2f64),
+            point3: Vector3::new(0f64, 0f64, 0.2f64),
+        };
+        assert!(segment.intersection_segment(&floor).is_none());
+        assert!(segment.get_triangle_collision(&floor).is_some());
+        let point = |x: f64| Triangle {
+            point1: Vector3::new(x, 5f64, 0f64),
+            point2: Vector3::new(x, 5f64, 0f64),
+            point3: Vector3::new(x, 5f64, 0f64),
+        };
+        assert!(point(0f64).get_triangle_collision(&point(1f64)).is_none());
+        let res = point(0f64).get_triangle_collision(&point(0f64)).unwrap();
+        assert_eq!(res.penetration, 0f64);
+    }
+}
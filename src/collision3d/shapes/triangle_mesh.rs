@@ -0,0 +1,151 @@
+use super::*;
+use arrayvec::ArrayVec;
+use core::iter::FromIterator;
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+/// A static collider made of many triangles, such as level geometry.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct TriangleMesh<N: PhysicsScalar> {
+    pub triangles: Vec<Triangle<N>>,
+}
+
+impl<N: FloatingPhysicsScalar> From<Vec<Triangle<N>>> for TriangleMesh<N> {
+    fn from(triangles: Vec<Triangle<N>>) -> Self {
+        TriangleMesh { triangles }
+    }
+}
+
+impl<N: FloatingPhysicsScalar> FromIterator<Triangle<N>> for TriangleMesh<N> {
+    fn from_iter<T: IntoIterator<Item = Triangle<N>>>(iter: T) -> Self {
+        TriangleMesh {
+            triangles: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<N: FloatingPhysicsScalar> TriangleMesh<N> {
+    pub fn iter(&self) -> impl Iterator<Item = &Triangle<N>> + '_ {
+        self.triangles.iter()
+    }
+    /// Returns the deepest of the per-triangle collisions.
+    fn deepest<F>(&self, f: F) -> Option<CollisionResolution<Vector3<N>, N>>
+    where
+        F: FnMut(&Triangle<N>) -> Option<CollisionResolution<Vector3<N>, N>>,
+    {
+        self.triangles
+            .iter()
+            .filter_map(f)
+            .max_by(|a, b| n_ordering(a.penetration, b.penetration))
+            .map(|r| r.flip())
+    }
+    pub fn get_sphere_collision(
+        &self,
+        sphere: &Sphere<N>,
+        culling: FaceCulling,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        self.deepest(|t| sphere.get_triangle_collision(t, culling))
+    }
+    pub fn get_capsule_collision(
+        &self,
+        capsule: &Capsule<N>,
+        culling: FaceCulling,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        self.deepest(|t| capsule.get_triangle_collision(t, culling))
+    }
+    pub fn get_aabb_collision(
+        &self,
+        aabb: &AxisAlignedBoundingBox<N>,
+        culling: FaceCulling,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        self.deepest(|t| aabb.get_triangle_collision(t, culling))
+    }
+    pub fn get_cylinder_collision(
+        &self,
+        cylinder: &Cylinder<N>,
+        culling: FaceCulling,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        self.deepest(|t| cylinder.get_triangle_collision(t, culling))
+    }
+    /// Returns a resolution that pushes the mesh out of `triangle`.
+    pub fn get_triangle_collision(
+        &self,
+        triangle: &Triangle<N>,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        self.deepest(|t| triangle.get_triangle_collision(t))
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Shape3D<N> for TriangleMesh<N> {
+    fn bounding_aabb(&self) -> AxisAlignedBoundingBox<N> {
+        self.triangles.iter().map(|t| t.bounding_aabb()).sum()
+    }
+    fn bounding_sphere(&self) -> Sphere<N> {
+        Sphere::from_points(
+            self.triangles
+                .iter()
+                .flat_map(|t| ArrayVec::from([t.point1, t.point2, t.point3])),
+        )
+    }
+    fn center(&self) -> Vector3<N> {
+        self.bounding_aabb().center()
+    }
+    fn translate(&self, point: &Vector3<N>) -> Self {
+        self.triangles.iter().map(|t| t.translate(point)).collect()
+    }
+    fn set_center(&self, point: &Vector3<N>) -> Self {
+        let mut m = self.clone();
+        m.set_center_mut(point);
+        m
+    }
+    fn translate_mut(&mut self, point: &Vector3<N>) {
+        for t in self.triangles.iter_mut() {
+            t.translate_mut(point);
+        }
+    }
+    fn set_center_mut(&mut self, point: &Vector3<N>) {
+        let offset = point - self.center();
+        self.translate_mut(&offset);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sphere_collision_test() {
+        let mesh: TriangleMesh<f32> = vec![
+            Triangle {
+                point1: Vector3::new(-1f32, 0f32, -1f32),
+                point2: Vector3::new(-1f32, 0f32, 1f32),
+                point3: Vector3::new(1f32, 0f32, 1f32),
+            },
+            Triangle {
+                point1: Vector3::new(-1f32, 0f32, -1f32),
+                point2: Vector3::new(1f32, 0f32, 1f32),
+                point3: Vector3::new(1f32, 0f32, -1f32),
+            },
+        ]
+        .into();
+        let sphere = Sphere {
+            center: Vector3::new(0.5f32, 0.25f32, -0.5f32),
+            radius: 0.5f32,
+        };
+
+        let res = mesh
+            .get_sphere_collision(&sphere, FaceCulling::OneSided)
+            .unwrap();
+        assert_eq!(res.normal, -Vector3::y());
+        assert_eq!(res.penetration, 0.25f32);
+        assert_eq!(
+            mesh.bounding_aabb().min_max(),
+            (
+                Vector3::new(-1f32, 0f32, -1f32),
+                Vector3::new(1f32, 0f32, 1f32)
+            )
+        );
+    }
+}
@@ -0,0 +1,379 @@
+use super::*;
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+/// The index of a voxel along each axis.
+pub type Cell = [usize; 3];
+
+/// A static collider made of solid and empty cubes on a regular grid, such as
+/// block terrain. Cell `[0, 0, 0]` starts at `origin`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct VoxelGrid<N: PhysicsScalar> {
+    pub origin: Vector3<N>,
+    pub cell_size: N,
+    dimensions: Cell,
+    /// One bit per cell, x first, then y, then z.
+    occupancy: Vec<u64>,
+}
+
+/// Where a ray first enters a solid voxel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VoxelHit<N: PhysicsScalar> {
+    pub cell: Cell,
+    /// The distance along the ray in multiples of its direction.
+    pub distance: N,
+    /// The normal of the face the ray entered through, averaged over the
+    /// faces when it enters through an edge or corner, or zero when the ray
+    /// starts inside the voxel or on its boundary.
+    pub normal: Vector3<N>,
+}
+
+impl<N: FloatingPhysicsScalar> VoxelGrid<N> {
+    /// An empty grid with `dimensions` cells along each axis.
+    pub fn new(origin: &Vector3<N>, cell_size: N, dimensions: Cell) -> Self {
+        let cells = dimensions[0] * dimensions[1] * dimensions[2];
+        VoxelGrid {
+            origin: *origin,
+            cell_size,
+            dimensions,
+            occupancy: vec![0; cells.div_ceil(64)],
+        }
+    }
+    /// A grid where `solid` decides which cells are filled.
+    pub fn from_fn<F: FnMut(Cell) -> bool>(
+        origin: &Vector3<N>,
+        cell_size: N,
+        dimensions: Cell,
+        mut solid: F,
+    ) -> Self {
+        let mut grid = Self::new(origin, cell_size, dimensions);
+        for z in 0..dimensions[2] {
+            for y in 0..dimensions[1] {
+                for x in 0..dimensions[0] {
+                    if solid([x, y, z]) {
+                        grid.set_solid([x, y, z], true);
+                    }
+                }
+            }
+        }
+        grid
+    }
+    pub fn dimensions(&self) -> Cell {
+        self.dimensions
+    }
+    fn index(&self, cell: Cell) -> Option<usize> {
+        if cell.iter().zip(self.dimensions.iter()).all(|(c, d)| c < d) {
+            Some(cell[0] + self.dimensions[0] * (cell[1] + self.dimensions[1] * cell[2]))
+        } else {
+            None
+        }
+    }
+    /// Whether the cell is filled. Cells outside the grid are empty.
+    pub fn is_solid(&self, cell: Cell) -> bool {
+        self.index(cell)
+            .is_some_and(|i| self.occupancy[i / 64] & (1 << (i % 64)) != 0)
+    }
+    /// Fills or empties a cell.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell is outside the grid.
+    pub fn set_solid(&mut self, cell: Cell, solid: bool) {
+        let i = self.index(cell).expect("cell outside the voxel grid");
+        if solid {
+            self.occupancy[i / 64] |= 1 << (i % 64);
+        } else {
+            self.occupancy[i / 64] &= !(1 << (i % 64));
+        }
+    }
+    /// Whether the neighbour of `cell` one step along `axis` in the direction
+    /// of `sign` is solid.
+    fn is_neighbour_solid(&self, cell: Cell, axis: usize, sign: N) -> bool {
+        let mut neighbour = cell;
+        if sign > N::zero() {
+            neighbour[axis] += 1;
+        } else if cell[axis] > 0 {
+            neighbour[axis] -= 1;
+        } else {
+            return false;
+        }
+        self.is_solid(neighbour)
+    }
+    pub fn cell_aabb(&self, cell: Cell) -> AxisAlignedBoundingBox<N> {
+        let start =
+            self.origin + Vector3::from_fn(|i, _| N::from_usize(cell[i]).unwrap() * self.cell_size);
+        AxisAlignedBoundingBox {
+            start,
+            end: start + Vector3::from_element(self.cell_size),
+        }
+    }
+    /// The cell holding `point`, if it is inside the grid.
+    pub fn cell_at(&self, point: &Vector3<N>) -> Option<Cell> {
+        let mut cell = [0; 3];
+        for (i, c) in cell.iter_mut().enumerate() {
+            let index = Float::floor((point[i] - self.origin[i]) / self.cell_size);
+            *c = index.to_usize().filter(|c| *c < self.dimensions[i])?;
+        }
+        Some(cell)
+    }
+    /// The solid cells that overlap `aabb`.
+    pub fn solid_cells_in(
+        &self,
+        aabb: &AxisAlignedBoundingBox<N>,
+    ) -> impl Iterator<Item = Cell> + '_ {
+        let (min, max) = aabb.min_max();
+        let range = |i: usize| {
+            let low = Float::floor((min[i] - self.origin[i]) / self.cell_size);
+            let high = Float::floor((max[i] - self.origin[i]) / self.cell_size);
+            let last = N::from_usize(self.dimensions[i]).unwrap() - N::one();
+            if high < N::zero() || low > last {
+                0..0
+            } else {
+                let low = n_max(low, N::zero()).to_usize().unwrap();
+                let high = n_min(high, last).to_usize().unwrap();
+                low..high + 1
+            }
+        };
+        let (xs, ys, zs) = (range(0), range(1), range(2));
+        zs.flat_map(move |z| {
+            let xs = xs.clone();
+            ys.clone()
+                .flat_map(move |y| xs.clone().map(move |x| [x, y, z]))
+        })
+        .filter(move |cell| self.is_solid(*cell))
+    }
+    /// Collides `shape` with every solid cell it touches and keeps the deepest
+    /// result, flipped to push the grid.
+    ///
+    /// Faces shared by two solid cells are inside the terrain, so any part of a
+    /// normal pointing through one is dropped and the depth is measured along
+    /// what is left. This keeps shapes sliding along a flat run of voxels from
+    /// catching on the seams between them.
+    fn deepest<S, F>(&self, shape: &S, mut collide: F) -> Option<CollisionResolution<Vector3<N>, N>>
+    where
+        S: Shape3D<N> + SupportMap<N>,
+        F: FnMut(&AxisAlignedBoundingBox<N>) -> Option<CollisionResolution<Vector3<N>, N>>,
+    {
+        self.solid_cells_in(&shape.bounding_aabb())
+            .filter_map(|cell| {
+                let aabb = self.cell_aabb(cell);
+                let res = collide(&aabb)?;
+                let mut normal = res.normal;
+                for i in 0..3 {
+                    if !is_zero(normal[i]) && self.is_neighbour_solid(cell, i, normal[i]) {
+                        normal[i] = N::zero();
+                    }
+                }
+                if normal == res.normal {
+                    return Some(res);
+                }
+                let normal = normal.try_normalize(N::zero())?;
+                let penetration = aabb.support_point(&normal).dot(&normal)
+                    - shape.support_point(&-normal).dot(&normal);
+                if penetration > N::zero() {
+                    Some(CollisionResolution {
+                        normal,
+                        penetration,
+                    })
+                } else {
+                    None
+                }
+            })
+            .max_by(|a, b| n_ordering(a.penetration, b.penetration))
+            .map(|r| r.flip())
+    }
+    pub fn get_aabb_collision(
+        &self,
+        aabb: &AxisAlignedBoundingBox<N>,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        self.deepest(aabb, |cell| aabb.get_aabb_collision(cell))
+    }
+    pub fn get_sphere_collision(
+        &self,
+        sphere: &Sphere<N>,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        self.deepest(sphere, |cell| {
+            cell.get_sphere_collision(sphere).map(|r| r.flip())
+        })
+    }
+    pub fn get_capsule_collision(
+        &self,
+        capsule: &Capsule<N>,
+    ) -> Option<CollisionResolution<Vector3<N>, N>> {
+        self.deepest(capsule, |cell| capsule.get_aabb_collision(cell))
+    }
+    /// Walks the cells along `ray` and returns the first solid one within
+    /// `max_distance`, using the traversal of Amanatides and Woo. A ray that
+    /// only grazes a voxel along a face, edge or corner hits it.
+    pub fn cast_ray(&self, ray: &Ray<N>, max_distance: N) -> Option<VoxelHit<N>> {
+        let step = grid_walk(
+            self.origin.as_slice(),
+            self.cell_size,
+            self.dimensions,
+            ray.point.as_slice(),
+            ray.direction.as_slice(),
+            max_distance,
+            |step| self.is_solid(step.cell),
+        )?;
+        let normal = Vector3::from_fn(|i, _| {
+            if step.entered[i] {
+                -Float::signum(ray.direction[i])
+            } else {
+                N::zero()
+            }
+        });
+        Some(VoxelHit {
+            cell: step.cell,
+            distance: step.distance,
+            normal: normal.try_normalize(N::zero()).unwrap_or(normal),
+        })
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Shape3D<N> for VoxelGrid<N> {
+    fn bounding_aabb(&self) -> AxisAlignedBoundingBox<N> {
+        let size = Vector3::from_fn(|i, _| N::from_usize(self.dimensions[i]).unwrap());
+        AxisAlignedBoundingBox {
+            start: self.origin,
+            end: self.origin + size * self.cell_size,
+        }
+    }
+    fn bounding_sphere(&self) -> Sphere<N> {
+        self.bounding_aabb().bounding_sphere()
+    }
+    fn center(&self) -> Vector3<N> {
+        self.bounding_aabb().center()
+    }
+    fn translate(&self, point: &Vector3<N>) -> Self {
+        let mut grid = self.clone();
+        grid.translate_mut(point);
+        grid
+    }
+    fn set_center(&self, point: &Vector3<N>) -> Self {
+        let mut grid = self.clone();
+        grid.set_center_mut(point);
+        grid
+    }
+    fn translate_mut(&mut self, point: &Vector3<N>) {
+        self.origin += point;
+    }
+    fn set_center_mut(&mut self, point: &Vector3<N>) {
+        let offset = point - self.center();
+        self.translate_mut(&offset);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 4x4 floor one voxel thick with a single block on top at `[3, 1, 0]`.
+    fn grid() -> VoxelGrid<f64> {
+        VoxelGrid::from_fn(&Vector3::zeros(), 1f64, [4, 3, 4], |[x, y, z]| {
+            y == 0 || (x == 3 && y == 1 && z == 0)
+        })
+    }
+
+    #[test]
+    fn occupancy_test() {
+        let mut grid = grid();
+        assert!(grid.is_solid([2, 0, 2]));
+        assert!(!grid.is_solid([2, 1, 2]));
+        assert!(!grid.is_solid([4, 0, 0]));
+        grid.set_solid([2, 1, 2], true);
+        assert!(grid.is_solid([2, 1, 2]));
+        grid.set_solid([2, 1, 2], false);
+        assert!(!grid.is_solid([2, 1, 2]));
+        assert_eq!(
+            grid.cell_at(&Vector3::new(3.5f64, 1.5f64, 0.5f64)),
+            Some([3, 1, 0])
+        );
+        assert_eq!(grid.cell_at(&Vector3::new(-0.5f64, 1.5f64, 0.5f64)), None);
+        let aabb = AxisAlignedBoundingBox {
+            start: Vector3::new(2.5f64, -1f64, -1f64),
+            end: Vector3::new(3.5f64, 1.5f64, 0.5f64),
+        };
+        let cells: Vec<_> = grid.solid_cells_in(&aabb).collect();
+        assert_eq!(cells, vec![[2, 0, 0], [3, 0, 0], [3, 1, 0]]);
+    }
+    #[test]
+    fn seam_test() {
+        let grid = grid();
+        // A box resting on the floor across the seam between two voxels,
+        // sunk slightly deeper into the next one along x.
+        let aabb = AxisAlignedBoundingBox {
+            start: Vector3::new(0.5f64, 0.9f64, 1.2f64),
+            end: Vector3::new(1.05f64, 1.5f64, 1.8f64),
+        };
+        let res = grid.get_aabb_collision(&aabb).unwrap().flip();
+        assert!((res.normal - Vector3::y()).magnitude() < 1e-9);
+        assert!((res.penetration - 0.1f64).abs() < 1e-9);
+
+        let sphere = Sphere {
+            center: Vector3::new(1f64, 1.4f64, 1f64),
+            radius: 0.5f64,
+        };
+        let res = grid.get_sphere_collision(&sphere).unwrap().flip();
+        assert!((res.normal - Vector3::y()).magnitude() < 1e-9);
+        assert!((res.penetration - 0.1f64).abs() < 1e-9);
+
+        // Against the side of the block the wall is a real face.
+        let capsule = Capsule {
+            start: Vector3::new(2.75f64, 1.5f64, 0.5f64),
+            end: Vector3::new(2.75f64, 2f64, 0.5f64),
+            radius: 0.5f64,
+        };
+        let res = grid.get_capsule_collision(&capsule).unwrap().flip();
+        assert!((res.normal + Vector3::x()).magnitude() < 1e-9);
+        assert!((res.penetration - 0.25f64).abs() < 1e-9);
+
+        assert!(grid
+            .get_sphere_collision(&sphere.translate(&Vector3::new(0f64, 1f64, 0f64)))
+            .is_none());
+    }
+    #[test]
+    fn ray_test() {
+        let grid = grid();
+        let ray = Ray::new(&Vector3::new(-1f64, 1.5f64, 0.5f64), &Vector3::x());
+        let hit = grid.cast_ray(&ray, 10f64).unwrap();
+        assert_eq!(hit.cell, [3, 1, 0]);
+        assert_eq!(hit.distance, 4f64);
+        assert_eq!(hit.normal, -Vector3::x());
+        assert_eq!(grid.cast_ray(&ray, 3.5f64), None);
+
+        let ray = Ray::new(
+            &Vector3::new(1.5f64, 5f64, 1.5f64),
+            &Vector3::new(0.5f64, -1f64, 0f64),
+        );
+        let hit = grid.cast_ray(&ray, 10f64).unwrap();
+        assert_eq!(hit.cell, [3, 0, 1]);
+        assert_eq!(hit.distance, 4f64);
+        assert_eq!(hit.normal, Vector3::y());
+
+        let ray = Ray::new(&Vector3::new(0.5f64, 0.5f64, 0.5f64), &Vector3::y());
+        let hit = grid.cast_ray(&ray, 10f64).unwrap();
+        assert_eq!(
+            (hit.cell, hit.distance, hit.normal),
+            ([0, 0, 0], 0f64, Vector3::zeros())
+        );
+
+        let ray = Ray::new(&Vector3::new(0.5f64, 1.5f64, 0.5f64), &Vector3::y());
+        assert_eq!(grid.cast_ray(&ray, 10f64), None);
+
+        // Grazing the top of the floor touches it.
+        let ray = Ray::new(&Vector3::new(-1f64, 1f64, 0.5f64), &Vector3::x());
+        let hit = grid.cast_ray(&ray, 10f64).unwrap();
+        assert_eq!(
+            (hit.cell, hit.distance, hit.normal),
+            ([0, 0, 0], 1f64, -Vector3::x())
+        );
+        let ray = Ray::new(&Vector3::new(0.5f64, 1f64, 0.5f64), &Vector3::y());
+        let hit = grid.cast_ray(&ray, 10f64).unwrap();
+        assert_eq!(
+            (hit.cell, hit.distance, hit.normal),
+            ([0, 0, 0], 0f64, Vector3::zeros())
+        );
+    }
+}
@@ -0,0 +1,860 @@
+use super::*;
+use arrayvec::ArrayVec;
+
+/// A convex shape described by its furthest point in any direction, which is
+/// all the distance queries in this module need to know about it.
+pub trait SupportMap<N: FloatingPhysicsScalar> {
+    /// Returns the point of the shape furthest along `direction`, which does
+    /// not need to be normalised.
+    fn support_point(&self, direction: &Vector3<N>) -> Vector3<N>;
+}
+
+const MAX_ITERATIONS: usize = 64;
+
+/// A point of the Minkowski difference `a - b` along with the points of each
+/// shape it came from and its weight in the current closest point.
+#[derive(Clone, Copy)]
+struct Vertex<N: FloatingPhysicsScalar> {
+    point: Vector3<N>,
+    on_a: Vector3<N>,
+    on_b: Vector3<N>,
+    weight: N,
+}
+
+impl<N: FloatingPhysicsScalar> Vertex<N> {
+    fn new<A, B>(a: &A, b: &B, direction: &Vector3<N>) -> Self
+    where
+        A: SupportMap<N> + ?Sized,
+        B: SupportMap<N> + ?Sized,
+    {
+        let on_a = a.support_point(direction);
+        let on_b = b.support_point(&-direction);
+        Vertex {
+            point: on_a - on_b,
+            on_a,
+            on_b,
+            weight: N::one(),
+        }
+    }
+}
+
+type Simplex<N> = ArrayVec<[Vertex<N>; 4]>;
+
+/// Returns the closest points between two convex shapes, the first on `a`,
+/// or `None` when they overlap or touch. Uses the GJK distance algorithm.
+pub fn closest_points<N, A, B>(a: &A, b: &B) -> Option<(Vector3<N>, Vector3<N>)>
+where
+    N: FloatingPhysicsScalar,
+    A: SupportMap<N> + ?Sized,
+    B: SupportMap<N> + ?Sized,
+{
+    let tolerance = N::epsilon() * N::from_f64(128.0).unwrap();
+    let mut simplex = Simplex::new();
+    simplex.push(Vertex::new(a, b, &Vector3::x()));
+    let mut closest = simplex[0].point;
+
+    for _ in 0..MAX_ITERATIONS {
+        // The origin is on the simplex, up to rounding relative to its size.
+        let length = closest.magnitude_squared();
+        let size = simplex
+            .iter()
+            .map(|v| v.point.magnitude_squared())
+            .fold(N::one(), n_max);
+        if length <= tolerance * tolerance * size {
+            return None;
+        }
+        let vertex = Vertex::new(a, b, &-closest);
+        // Stop once the new support point gets no closer to the origin, or
+        // is one already in the simplex, as happens on curved shapes once the
+        // search has converged.
+        if length - closest.dot(&vertex.point) <= tolerance * length
+            || simplex.iter().any(|v| {
+                (v.point - vertex.point).magnitude_squared()
+                    <= tolerance * tolerance * vertex.point.magnitude_squared()
+            })
+        {
+            break;
+        }
+        let previous = simplex.clone();
+        simplex.push(vertex);
+        let next = reduce(&mut simplex)?;
+        // Each step gets closer to the origin, except through rounding on a
+        // sliver of a simplex, which can jump back out. Keep the last one.
+        if next.magnitude_squared() > length * (N::one() + tolerance) {
+            simplex = previous;
+            break;
+        }
+        closest = next;
+    }
+
+    let on_a = simplex.iter().map(|v| v.on_a * v.weight).sum();
+    let on_b = simplex.iter().map(|v| v.on_b * v.weight).sum();
+    Some((on_a, on_b))
+}
+
+/// The distance between two convex shapes, zero when they overlap.
+pub fn distance_between<N, A, B>(a: &A, b: &B) -> N
+where
+    N: FloatingPhysicsScalar,
+    A: SupportMap<N> + ?Sized,
+    B: SupportMap<N> + ?Sized,
+{
+    closest_points(a, b).map_or(N::zero(), |(on_a, on_b)| (on_a - on_b).magnitude())
+}
+
+/// Where a moving shape first comes within the skin distance or tolerance of
+/// another.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShapeCastHit<N: PhysicsScalar> {
+    /// The fraction of the motion before the hit, from zero to one.
+    pub time: N,
+    /// The closest point of the moving shape at `time`.
+    pub point1: Vector3<N>,
+    /// The closest point of the target at `time`.
+    pub point2: Vector3<N>,
+    /// The unit normal pointing from the target towards the moving shape, or
+    /// zero when the shapes already overlap.
+    pub normal: Vector3<N>,
+}
+
+/// A shape moved by `offset`.
+struct Translated<'a, N: FloatingPhysicsScalar, S: ?Sized> {
+    shape: &'a S,
+    offset: Vector3<N>,
+}
+
+impl<N: FloatingPhysicsScalar, S: SupportMap<N> + ?Sized> SupportMap<N> for Translated<'_, N, S> {
+    fn support_point(&self, direction: &Vector3<N>) -> Vector3<N> {
+        self.shape.support_point(direction) + self.offset
+    }
+}
+
+/// Sweeps `shape` along `displacement` and returns where it first comes within
+/// `skin` of `target`, so a caller can move it by `time * displacement` and
+/// stop just short of contact. Shapes that start within `skin` hit at time
+/// zero. When they already overlap both points are the moving shape's
+/// furthest point along the displacement.
+///
+/// Uses conservative advancement: each step moves the shape as far as the
+/// closest points allow without closing the gap faster than the motion can.
+/// Returns `None` if no contact is reached within a fixed number of steps.
+pub fn shape_cast<N, A, B>(
+    shape: &A,
+    displacement: &Vector3<N>,
+    target: &B,
+    skin: N,
+) -> Option<ShapeCastHit<N>>
+where
+    N: FloatingPhysicsScalar,
+    A: SupportMap<N> + ?Sized,
+    B: SupportMap<N> + ?Sized,
+{
+    capped_shape_cast(shape, displacement, target, skin, MAX_ITERATIONS)
+}
+
+/// `shape_cast` giving up with `None` after `max_iterations` advances.
+fn capped_shape_cast<N, A, B>(
+    shape: &A,
+    displacement: &Vector3<N>,
+    target: &B,
+    skin: N,
+    max_iterations: usize,
+) -> Option<ShapeCastHit<N>>
+where
+    N: FloatingPhysicsScalar,
+    A: SupportMap<N> + ?Sized,
+    B: SupportMap<N> + ?Sized,
+{
+    let tolerance = Float::sqrt(N::epsilon()) * n_max(displacement.magnitude(), N::one());
+    // Aiming a little short of the skin keeps a gap to take the normal from.
+    let aim = skin + tolerance * N::from_f64(0.5).unwrap();
+    let mut time = N::zero();
+    let mut last = None;
+    for _ in 0..max_iterations {
+        let moved = Translated {
+            shape,
+            offset: displacement * time,
+        };
+        let (on_a, on_b) = match (closest_points(&moved, target), last) {
+            (Some(points), _) => points,
+            // Touching after an advance that landed right on the surface.
+            (None, Some(ShapeCastHit { normal, .. })) => {
+                let point = moved.support_point(&-normal);
+                return Some(ShapeCastHit {
+                    time,
+                    point1: point,
+                    point2: point,
+                    normal,
+                });
+            }
+            (None, None) => {
+                let point = shape.support_point(displacement);
+                return Some(ShapeCastHit {
+                    time,
+                    point1: point,
+                    point2: point,
+                    normal: Vector3::zeros(),
+                });
+            }
+        };
+        let gap = on_a - on_b;
+        let distance = gap.magnitude();
+        let normal = gap / distance;
+        last = Some(ShapeCastHit {
+            time,
+            point1: on_a,
+            point2: on_b,
+            normal,
+        });
+        if distance <= skin + tolerance {
+            return last;
+        }
+        // Along `normal` the shapes are `distance` apart and close at this
+        // rate, and they can get no closer anywhere else.
+        let approach = -normal.dot(displacement);
+        if approach <= N::zero() {
+            return None;
+        }
+        time += (distance - aim) / approach;
+        if time > N::one() {
+            return None;
+        }
+    }
+    None
+}
+
+/// `shape_cast` against everything behind `plane`.
+pub fn plane_shape_cast<N, A>(
+    shape: &A,
+    displacement: &Vector3<N>,
+    plane: &Plane<N>,
+    skin: N,
+) -> Option<ShapeCastHit<N>>
+where
+    N: FloatingPhysicsScalar,
+    A: SupportMap<N> + ?Sized,
+{
+    let point = shape.support_point(&-plane.normal);
+    let distance = plane.distance(&point);
+    if distance < N::zero() {
+        let point = shape.support_point(displacement);
+        return Some(ShapeCastHit {
+            time: N::zero(),
+            point1: point,
+            point2: point,
+            normal: Vector3::zeros(),
+        });
+    }
+    let time = if distance <= skin {
+        N::zero()
+    } else {
+        let approach = -plane.normal.dot(displacement);
+        if approach <= N::zero() {
+            return None;
+        }
+        (distance - skin) / approach
+    };
+    if time > N::one() {
+        return None;
+    }
+    let point1 = point + displacement * time;
+    Some(ShapeCastHit {
+        time,
+        point1,
+        point2: plane.closest_point(&point1),
+        normal: plane.normal,
+    })
+}
+
+/// A shape's pose at the start and end of a step. In between it moves in a
+/// straight line and turns about a fixed axis at a steady rate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Motion<N: FloatingPhysicsScalar> {
+    pub start: Isometry3<N>,
+    pub end: Isometry3<N>,
+}
+
+impl<N: FloatingPhysicsScalar> Motion<N> {
+    pub fn new(start: &Isometry3<N>, end: &Isometry3<N>) -> Self {
+        Motion {
+            start: *start,
+            end: *end,
+        }
+    }
+    /// A shape standing still at `pose`.
+    pub fn stationary(pose: &Isometry3<N>) -> Self {
+        Motion::new(pose, pose)
+    }
+    /// The pose at `time`, from zero at the start to one at the end.
+    pub fn at(&self, time: N) -> Isometry3<N> {
+        self.start.lerp_slerp(&self.end, time)
+    }
+    /// How far the pose's origin moves over the step.
+    pub fn translation(&self) -> Vector3<N> {
+        self.end.translation.vector - self.start.translation.vector
+    }
+    /// The angle the pose turns through over the step, at most half a turn.
+    pub fn angle(&self) -> N {
+        self.start.rotation.angle_to(&self.end.rotation)
+    }
+}
+
+/// A shape placed by `pose`.
+struct Transformed<'a, N: FloatingPhysicsScalar, S: ?Sized> {
+    shape: &'a S,
+    pose: Isometry3<N>,
+}
+
+impl<N: FloatingPhysicsScalar, S: SupportMap<N> + ?Sized> SupportMap<N> for Transformed<'_, N, S> {
+    fn support_point(&self, direction: &Vector3<N>) -> Vector3<N> {
+        let local = self.pose.rotation.inverse_transform_vector(direction);
+        self.pose.rotation * self.shape.support_point(&local) + self.pose.translation.vector
+    }
+}
+
+/// The furthest any point of `shape` can be from the origin, taken from the
+/// corners of the box around its support points on each axis.
+fn reach<N, S>(shape: &S) -> N
+where
+    N: FloatingPhysicsScalar,
+    S: SupportMap<N> + ?Sized,
+{
+    let squared = (0..3).fold(N::zero(), |sum, i| {
+        let axis = Vector3::ith(i, N::one());
+        let upper = Float::abs(shape.support_point(&axis)[i]);
+        let lower = Float::abs(shape.support_point(&-axis)[i]);
+        let furthest = n_max(upper, lower);
+        sum + furthest * furthest
+    });
+    Float::sqrt(squared)
+}
+
+/// The first time two shapes following `motion_a` and `motion_b` come within
+/// `tolerance` of each other, turning as well as moving, so fast spinning
+/// shapes cannot pass through each other between steps. The poses place each
+/// shape's own coordinates, so a shape turns about its origin. The hit points
+/// and normal are in world coordinates at the time of the hit, with the
+/// normal pointing from `b` towards `a`. Shapes that already overlap hit at
+/// time zero with a zero normal and both points on `a`.
+///
+/// Uses conservative advancement: each step advances time as far as the
+/// closest points allow given the fastest that any point of either shape can
+/// close the gap. `tolerance` must be positive, as the shapes only approach
+/// contact in ever smaller steps. When `max_iterations` runs out first the
+/// result is `None`, the same as for shapes that never meet, so the cap should
+/// leave room for the steps to shrink.
+pub fn time_of_impact<N, A, B>(
+    a: &A,
+    motion_a: &Motion<N>,
+    b: &B,
+    motion_b: &Motion<N>,
+    tolerance: N,
+    max_iterations: usize,
+) -> Option<ShapeCastHit<N>>
+where
+    N: FloatingPhysicsScalar,
+    A: SupportMap<N> + ?Sized,
+    B: SupportMap<N> + ?Sized,
+{
+    let velocity = motion_a.translation() - motion_b.translation();
+    // No point of either shape turns faster than its furthest one.
+    let spin = motion_a.angle() * reach(a) + motion_b.angle() * reach(b);
+    let aim = tolerance * N::from_f64(0.5).unwrap();
+    let mut time = N::zero();
+    let mut last_normal: Option<Vector3<N>> = None;
+    for _ in 0..max_iterations {
+        let placed_a = Transformed {
+            shape: a,
+            pose: motion_a.at(time),
+        };
+        let placed_b = Transformed {
+            shape: b,
+            pose: motion_b.at(time),
+        };
+        let (on_a, on_b) = match (closest_points(&placed_a, &placed_b), last_normal) {
+            (Some(points), _) => points,
+            // Touching after an advance that landed right on the surface.
+            (None, Some(normal)) => {
+                let point = placed_a.support_point(&-normal);
+                return Some(ShapeCastHit {
+                    time,
+                    point1: point,
+                    point2: point,
+                    normal,
+                });
+            }
+            (None, None) => {
+                let point = placed_a.support_point(&velocity);
+                return Some(ShapeCastHit {
+                    time,
+                    point1: point,
+                    point2: point,
+                    normal: Vector3::zeros(),
+                });
+            }
+        };
+        let gap = on_a - on_b;
+        let distance = gap.magnitude();
+        let normal = gap / distance;
+        if distance <= tolerance {
+            return Some(ShapeCastHit {
+                time,
+                point1: on_a,
+                point2: on_b,
+                normal,
+            });
+        }
+        last_normal = Some(normal);
+        let approach = spin - normal.dot(&velocity);
+        if approach <= N::zero() {
+            return None;
+        }
+        time += (distance - aim) / approach;
+        if time > N::one() {
+            return None;
+        }
+    }
+    None
+}
+
+/// Resolves two overlapping shapes by trying to push `a` both ways along each
+/// of `axes` and keeping the shortest push.
+pub(crate) fn min_push<N, A, B>(
+    a: &A,
+    b: &B,
+    axes: &[Vector3<N>],
+) -> CollisionResolution<Vector3<N>, N>
+where
+    N: FloatingPhysicsScalar,
+    A: SupportMap<N> + ?Sized,
+    B: SupportMap<N> + ?Sized,
+{
+    let mut best = CollisionResolution {
+        normal: Vector3::x(),
+        penetration: Bounded::max_value(),
+    };
+    for axis in axes.iter().filter(|a| !is_zero(a.magnitude_squared())) {
+        let axis = axis.normalize();
+        for normal in [axis, -axis].iter() {
+            let penetration =
+                b.support_point(normal).dot(normal) - a.support_point(&-normal).dot(normal);
+            if penetration < best.penetration {
+                best = CollisionResolution {
+                    normal: *normal,
+                    penetration,
+                };
+            }
+        }
+    }
+    best
+}
+
+/// Reduces the simplex to the smallest one holding its closest point to the
+/// origin, updating the weights, and returns that point. `None` means the
+/// simplex contains the origin.
+fn reduce<N: FloatingPhysicsScalar>(simplex: &mut Simplex<N>) -> Option<Vector3<N>> {
+    match simplex.len() {
+        1 => simplex[0].weight = N::one(),
+        2 => {
+            let weights = segment_weights(&simplex[0].point, &simplex[1].point);
+            keep_weighted(simplex, &weights);
+        }
+        3 => {
+            let weights = triangle_weights(&simplex[0].point, &simplex[1].point, &simplex[2].point);
+            keep_weighted(simplex, &weights);
+        }
+        _ => {
+            if tetrahedron_contains_origin(simplex) {
+                return None;
+            }
+            let faces = [[0, 1, 2], [0, 1, 3], [0, 2, 3], [1, 2, 3]];
+            let (face, weights, _) = faces
+                .iter()
+                .map(|face| {
+                    let weights = triangle_weights(
+                        &simplex[face[0]].point,
+                        &simplex[face[1]].point,
+                        &simplex[face[2]].point,
+                    );
+                    let point: Vector3<N> = face
+                        .iter()
+                        .zip(weights.iter())
+                        .map(|(&i, &w)| simplex[i].point * w)
+                        .sum();
+                    (face, weights, point.magnitude_squared())
+                })
+                .min_by(|a, b| n_ordering(a.2, b.2))
+                .unwrap();
+            let mut reduced = Simplex::new();
+            for &i in face.iter() {
+                reduced.push(simplex[i]);
+            }
+            *simplex = reduced;
+            keep_weighted(simplex, &weights);
+        }
+    }
+    Some(simplex.iter().map(|v| v.point * v.weight).sum())
+}
+
+/// Assigns `weights` to the vertices and drops those that don't contribute.
+fn keep_weighted<N: FloatingPhysicsScalar>(simplex: &mut Simplex<N>, weights: &[N]) {
+    for (vertex, weight) in simplex.iter_mut().zip(weights.iter()) {
+        vertex.weight = *weight;
+    }
+    simplex.retain(|v| v.weight > N::zero());
+}
+
+/// Barycentric weights of the closest point to the origin on a segment.
+fn segment_weights<N: FloatingPhysicsScalar>(a: &Vector3<N>, b: &Vector3<N>) -> [N; 2] {
+    let ab = b - a;
+    let length = ab.magnitude_squared();
+    let t = if is_zero(length) {
+        N::zero()
+    } else {
+        num_traits::clamp(-a.dot(&ab) / length, N::zero(), N::one())
+    };
+    [N::one() - t, t]
+}
+
+/// Barycentric weights of the closest point to the origin on a triangle,
+/// following the region tests in Ericson's Real-Time Collision Detection.
+fn triangle_weights<N: FloatingPhysicsScalar>(
+    a: &Vector3<N>,
+    b: &Vector3<N>,
+    c: &Vector3<N>,
+) -> [N; 3] {
+    let ab = b - a;
+    let ac = c - a;
+    if is_zero(ab.cross(&ac).magnitude_squared()) {
+        // A degenerate triangle is as good as its best edge.
+        let edges = [
+            segment_weights(a, b),
+            segment_weights(a, c),
+            segment_weights(b, c),
+        ];
+        let weights = [
+            [edges[0][0], edges[0][1], N::zero()],
+            [edges[1][0], N::zero(), edges[1][1]],
+            [N::zero(), edges[2][0], edges[2][1]],
+        ];
+        return *weights
+            .iter()
+            .min_by(|x, y| {
+                let distance = |w: &[N; 3]| (a * w[0] + b * w[1] + c * w[2]).magnitude_squared();
+                n_ordering(distance(x), distance(y))
+            })
+            .unwrap();
+    }
+
+    let d1 = -ab.dot(a);
+    let d2 = -ac.dot(a);
+    if d1 <= N::zero() && d2 <= N::zero() {
+        return [N::one(), N::zero(), N::zero()];
+    }
+    let d3 = -ab.dot(b);
+    let d4 = -ac.dot(b);
+    if d3 >= N::zero() && d4 <= d3 {
+        return [N::zero(), N::one(), N::zero()];
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= N::zero() && d1 >= N::zero() && d3 <= N::zero() {
+        let v = d1 / (d1 - d3);
+        return [N::one() - v, v, N::zero()];
+    }
+    let d5 = -ab.dot(c);
+    let d6 = -ac.dot(c);
+    if d6 >= N::zero() && d5 <= d6 {
+        return [N::zero(), N::zero(), N::one()];
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= N::zero() && d2 >= N::zero() && d6 <= N::zero() {
+        let w = d2 / (d2 - d6);
+        return [N::one() - w, N::zero(), w];
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= N::zero() && d4 - d3 >= N::zero() && d5 - d6 >= N::zero() {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return [N::zero(), N::one() - w, w];
+    }
+    let denom = N::one() / (va + vb + vc);
+    let v = vb * denom;
+    let w = vc * denom;
+    [N::one() - v - w, v, w]
+}
+
+/// Whether the origin lies inside the tetrahedron, which is the case when it is
+/// on the same side of each face as the opposite vertex. A nearly flat
+/// tetrahedron, as left by a vertex that almost repeats one already in the
+/// simplex, holds nothing, since the sides of its faces are mostly rounding.
+fn tetrahedron_contains_origin<N: FloatingPhysicsScalar>(simplex: &Simplex<N>) -> bool {
+    let flat = Float::sqrt(N::epsilon());
+    let faces = [[0, 1, 2, 3], [0, 1, 3, 2], [0, 2, 3, 1], [1, 2, 3, 0]];
+    faces.iter().all(|&[i, j, k, l]| {
+        let (a, b, c) = (simplex[i].point, simplex[j].point, simplex[k].point);
+        let normal = (b - a).cross(&(c - a));
+        let offset = simplex[l].point - a;
+        let opposite = normal.dot(&offset);
+        Float::abs(opposite) > flat * normal.magnitude() * offset.magnitude()
+            && normal.dot(&-a) * opposite >= N::zero()
+    })
+}
+
+impl<N: FloatingPhysicsScalar> SupportMap<N> for Sphere<N> {
+    fn support_point(&self, direction: &Vector3<N>) -> Vector3<N> {
+        self.center
+            + direction
+                .try_normalize(N::zero())
+                .unwrap_or_else(Vector3::zeros)
+                * self.radius
+    }
+}
+
+// Ties pick the middle of a face or segment, which makes a good contact
+// point for a box or capsule lying flat.
+impl<N: FloatingPhysicsScalar> SupportMap<N> for AxisAlignedBoundingBox<N> {
+    fn support_point(&self, direction: &Vector3<N>) -> Vector3<N> {
+        let (min, max) = self.min_max();
+        let center = self.center();
+        Vector3::from_fn(|i, _| {
+            if is_zero(direction[i]) {
+                center[i]
+            } else if direction[i] < N::zero() {
+                min[i]
+            } else {
+                max[i]
+            }
+        })
+    }
+}
+
+impl<N: FloatingPhysicsScalar> SupportMap<N> for Capsule<N> {
+    fn support_point(&self, direction: &Vector3<N>) -> Vector3<N> {
+        let start = self.start.dot(direction);
+        let end = self.end.dot(direction);
+        let point = if is_zero(start - end) {
+            self.center()
+        } else if start > end {
+            self.start
+        } else {
+            self.end
+        };
+        point
+            + direction
+                .try_normalize(N::zero())
+                .unwrap_or_else(Vector3::zeros)
+                * self.radius
+    }
+}
+
+impl<N: FloatingPhysicsScalar> SupportMap<N> for Cylinder<N> {
+    fn support_point(&self, direction: &Vector3<N>) -> Vector3<N> {
+        let axis = self.axis.to_num();
+        let mut radial = *direction;
+        radial[axis] = N::zero();
+        let mut point = self.center
+            + radial
+                .try_normalize(N::zero())
+                .unwrap_or_else(Vector3::zeros)
+                * self.radius;
+        if direction[axis] < N::zero() {
+            point[axis] -= self.half_height;
+        } else {
+            point[axis] += self.half_height;
+        }
+        point
+    }
+}
+
+impl<N: FloatingPhysicsScalar> SupportMap<N> for Triangle<N> {
+    fn support_point(&self, direction: &Vector3<N>) -> Vector3<N> {
+        *[self.point1, self.point2, self.point3]
+            .iter()
+            .max_by(|a, b| n_ordering(a.dot(direction), b.dot(direction)))
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    #[test]
+    fn closest_points_test() {
+        let sphere = Sphere {
+            center: Vector3::new(0f64, 3f64, 0f64),
+            radius: 1f64,
+        };
+        let aabb = AxisAlignedBoundingBox {
+            start: Vector3::new(-1f64, -1f64, -1f64),
+            end: Vector3::new(1f64, 1f64, 1f64),
+        };
+        let (on_sphere, on_aabb) = closest_points(&sphere, &aabb).unwrap();
+        assert!((on_sphere - Vector3::new(0f64, 2f64, 0f64)).magnitude() < 1e-6);
+        assert!((on_aabb.y - 1f64).abs() < 1e-6);
+        assert!((distance_between(&sphere, &aabb) - 1f64).abs() < 1e-6);
+
+        let triangle = Triangle {
+            point1: Vector3::new(2f64, 2f64, -5f64),
+            point2: Vector3::new(2f64, 2f64, 5f64),
+            point3: Vector3::new(5f64, 2f64, 0f64),
+        };
+        let (on_aabb, on_triangle) = closest_points(&aabb, &triangle).unwrap();
+        assert!((on_aabb - Vector3::new(1f64, 1f64, on_aabb.z)).magnitude() < 1e-6);
+        assert!((on_triangle - on_aabb - Vector3::new(1f64, 1f64, 0f64)).magnitude() < 1e-6);
+
+        assert!(
+            closest_points(&sphere.translate(&Vector3::new(0f64, -1.5f64, 0f64)), &aabb).is_none()
+        );
+        assert_eq!(distance_between(&aabb, &aabb), 0f64);
+    }
+
+    #[test]
+    fn closest_points_regression_test() {
+        // A sliver of a simplex used to round its way onto the origin here
+        // and report the gap as an overlap.
+        let cone = Cone {
+            apex: Vector3::new(
+                0.3284472783981432f64,
+                -0.29244293653015946,
+                2.384266654009453,
+            ),
+            axis: Vector3::new(
+                -0.6457683996479435f64,
+                0.38323285010369973,
+                -0.6603906091227595,
+            ),
+            height: 3.950783169158868,
+            radius: 0.5476086940635481,
+        };
+        let aabb = AxisAlignedBoundingBox {
+            start: Vector3::new(
+                -0.7145473830644948f64,
+                0.946702895770287,
+                -2.0536721300712135,
+            ),
+            end: Vector3::new(3.1344214377404045f64, 4.364411341850962, 0.7288951188982077),
+        };
+        let (on_cone, on_aabb) = closest_points(&cone, &aabb).unwrap();
+        assert!(((on_cone - on_aabb).magnitude() - 0.2687f64).abs() < 1e-3);
+
+        // These touch at a corner of the box, which used to come out as a
+        // gap of a few ulps rather than a contact.
+        let cylinder = Cylinder {
+            center: Vector3::new(
+                -1.5982829947957156f64,
+                1.6460262673562664,
+                2.3840814784116957,
+            ),
+            half_height: 1.3416805357328203,
+            radius: 1.4696464882968778,
+            axis: Axis::X,
+        };
+        let aabb = AxisAlignedBoundingBox {
+            start: Vector3::new(
+                -2.4233406960829793f64,
+                0.05114943569251007,
+                -1.4853620394867353,
+            ),
+            end: Vector3::new(-2.223340696082979f64, 0.752632271662053, 1.562024354616564),
+        };
+        assert!(closest_points(&cylinder, &aabb).is_none());
+    }
+    #[test]
+    fn shape_cast_test() {
+        let sphere = Sphere {
+            center: Vector3::new(0f64, 5f64, 0f64),
+            radius: 1f64,
+        };
+        let aabb = AxisAlignedBoundingBox {
+            start: Vector3::new(-1f64, -1f64, -1f64),
+            end: Vector3::new(1f64, 1f64, 1f64),
+        };
+        let down = Vector3::new(0f64, -6f64, 0f64);
+        let hit = shape_cast(&sphere, &down, &aabb, 0f64).unwrap();
+        assert!((hit.time - 0.5f64).abs() < 1e-6);
+        assert!((hit.point1 - Vector3::new(0f64, 1f64, 0f64)).magnitude() < 1e-6);
+        assert!((hit.point2 - Vector3::new(0f64, 1f64, 0f64)).magnitude() < 1e-6);
+        assert!((hit.normal - Vector3::y()).magnitude() < 1e-6);
+
+        // Stopping short leaves the skin between them.
+        let hit = shape_cast(&sphere, &down, &aabb, 0.3f64).unwrap();
+        assert!((hit.time - 0.45f64).abs() < 1e-6);
+        assert!(((hit.point1 - hit.point2).magnitude() - 0.3f64).abs() < 1e-6);
+
+        // Too short, moving away and passing by.
+        assert!(shape_cast(&sphere, &(down * 0.4f64), &aabb, 0f64).is_none());
+        assert!(shape_cast(&sphere, &-down, &aabb, 0f64).is_none());
+        let across = Vector3::new(6f64, -3f64, 0f64);
+        assert!(shape_cast(&sphere, &across, &aabb, 0f64).is_none());
+
+        // Running out of steps before contact is not a hit.
+        assert!(capped_shape_cast(&sphere, &down, &aabb, 0f64, 1).is_none());
+        assert!(capped_shape_cast(&sphere, &down, &aabb, 0f64, 2).is_some());
+
+        let inside = sphere.translate(&Vector3::new(0f64, -4f64, 0f64));
+        let hit = shape_cast(&inside, &down, &aabb, 0f64).unwrap();
+        assert_eq!((hit.time, hit.normal), (0f64, Vector3::zeros()));
+
+        let floor = Plane::from_point(&Vector3::y(), &Vector3::new(0f64, -1f64, 0f64));
+        let hit = plane_shape_cast(&sphere, &down, &floor, 0f64).unwrap();
+        assert!((hit.time - 5f64 / 6f64).abs() < 1e-9);
+        assert_eq!(hit.point2, Vector3::new(0f64, -1f64, 0f64));
+        assert_eq!(hit.normal, Vector3::y());
+        assert!(plane_shape_cast(&sphere, &-down, &floor, 0f64).is_none());
+    }
+    #[test]
+    fn time_of_impact_test() {
+        // A blade sweeping a quarter turn about z, which starts and ends clear
+        // of the ball in its path.
+        let blade = AxisAlignedBoundingBox {
+            start: Vector3::new(-2f64, -0.1f64, -0.1f64),
+            end: Vector3::new(2f64, 0.1f64, 0.1f64),
+        };
+        let turn = Motion::new(
+            &Isometry3::identity(),
+            &Isometry3::new(Vector3::zeros(), Vector3::z() * FRAC_PI_2),
+        );
+        let ball = Sphere {
+            center: Vector3::new(1f64, 1f64, 0f64),
+            radius: 0.25f64,
+        };
+        let still = Motion::stationary(&Isometry3::identity());
+        assert!(closest_points(
+            &Transformed {
+                shape: &blade,
+                pose: turn.end
+            },
+            &ball
+        )
+        .is_some());
+
+        let hit = time_of_impact(&blade, &turn, &ball, &still, 1e-6f64, 64).unwrap();
+        let angle = FRAC_PI_4 - (0.35f64 / 2f64.sqrt()).asin();
+        assert!((hit.time - angle / FRAC_PI_2).abs() < 1e-6);
+        assert!(((hit.point2 - ball.center).magnitude() - 0.25f64).abs() < 1e-6);
+        assert!((hit.normal - Vector3::new(angle.sin(), -angle.cos(), 0f64)).magnitude() < 1e-3);
+
+        let far = ball.translate(&Vector3::new(2f64, 2f64, 0f64));
+        assert!(time_of_impact(&blade, &turn, &far, &still, 1e-6f64, 64).is_none());
+
+        // Running out of steps before contact is not a hit.
+        assert!(time_of_impact(&blade, &turn, &ball, &still, 1e-6f64, 1).is_none());
+        assert!(time_of_impact(&blade, &turn, &ball, &still, 1e-6f64, 2).is_none());
+
+        // Without turning it matches a straight cast.
+        let sphere = ball.translate(&Vector3::new(0f64, 4f64, 0f64));
+        let down = Vector3::new(0f64, -6f64, 0f64);
+        let drop = Motion::new(
+            &Isometry3::identity(),
+            &Isometry3::translation(0f64, -6f64, 0f64),
+        );
+        let hit = time_of_impact(&sphere, &drop, &blade, &still, 1e-6f64, 64).unwrap();
+        let cast = shape_cast(&sphere, &down, &blade, 0f64).unwrap();
+        assert!((hit.time - cast.time).abs() < 1e-6);
+        assert!((hit.normal - cast.normal).magnitude() < 1e-6);
+
+        let hit = time_of_impact(&blade, &turn, &blade, &still, 1e-6f64, 64).unwrap();
+        assert_eq!((hit.time, hit.normal), (0f64, Vector3::zeros()));
+    }
+}
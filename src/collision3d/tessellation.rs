@@ -0,0 +1,437 @@
+use super::*;
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+/// Indexed triangles wound counter-clockwise when seen from outside the shape,
+/// with one unit normal per vertex.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TriangleBuffers<N: PhysicsScalar> {
+    pub vertices: Vec<Vector3<N>>,
+    pub normals: Vec<Vector3<N>>,
+    pub indices: Vec<[usize; 3]>,
+}
+
+/// Controls how finely curved shapes are approximated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct TessellationDetail<N: PhysicsScalar> {
+    /// Edges used to approximate a full circle around a shape's axis. At least 3.
+    pub segments: usize,
+    /// Bands from pole to pole of a sphere. Capsules split them between their caps.
+    pub rings: usize,
+    /// Half the side length of the square patch used for planes.
+    pub plane_half_extent: N,
+}
+
+impl<N: FloatingPhysicsScalar> Default for TessellationDetail<N> {
+    fn default() -> Self {
+        TessellationDetail {
+            segments: 16,
+            rings: 8,
+            plane_half_extent: N::from_f64(10.0).unwrap(),
+        }
+    }
+}
+
+pub trait Tessellate<N: PhysicsScalar> {
+    fn tessellate(&self, detail: &TessellationDetail<N>) -> TriangleBuffers<N>;
+}
+
+/// A point of a revolve profile with its normal in the (axis, radial) plane.
+#[derive(Clone, Copy)]
+struct ProfilePoint<N: PhysicsScalar> {
+    height: N,
+    radius: N,
+    axial: N,
+    radial: N,
+}
+
+impl<N: FloatingPhysicsScalar> TriangleBuffers<N> {
+    pub fn new() -> Self {
+        TriangleBuffers {
+            vertices: Vec::new(),
+            normals: Vec::new(),
+            indices: Vec::new(),
+        }
+    }
+    pub fn append(&mut self, other: &TriangleBuffers<N>) {
+        let offset = self.vertices.len();
+        self.vertices.extend_from_slice(&other.vertices);
+        self.normals.extend_from_slice(&other.normals);
+        self.indices.extend(
+            other
+                .indices
+                .iter()
+                .map(|[a, b, c]| [a + offset, b + offset, c + offset]),
+        );
+    }
+    pub fn triangles(&self) -> impl Iterator<Item = Triangle<N>> + '_ {
+        self.indices.iter().map(move |[a, b, c]| Triangle {
+            point1: self.vertices[*a],
+            point2: self.vertices[*b],
+            point3: self.vertices[*c],
+        })
+    }
+    /// Applies an affine transform such as the one built from an
+    /// `AxisAlignedBoundingBox`. Normals are kept when it cannot be inverted.
+    pub fn transform_mut(&mut self, transform: &Matrix4<N>) {
+        for v in self.vertices.iter_mut() {
+            *v = transform.transform_point(&Point3::from(*v)).coords;
+        }
+        let linear = transform.fixed_slice::<U3, U3>(0, 0).into_owned();
+        if let Some(inverse) = linear.try_inverse() {
+            let inverse_transpose = inverse.transpose();
+            for n in self.normals.iter_mut() {
+                *n = (inverse_transpose * *n).normalize();
+            }
+        }
+    }
+    fn push(&mut self, vertex: Vector3<N>, normal: Vector3<N>) {
+        self.vertices.push(vertex);
+        self.normals.push(normal);
+    }
+    /// Sweeps a profile around `axis`, starting at `base`. Profile points with
+    /// no radius become a single pole vertex.
+    fn revolve(
+        &mut self,
+        base: &Vector3<N>,
+        axis: &Vector3<N>,
+        profile: &[ProfilePoint<N>],
+        segments: usize,
+    ) {
+        let (u, v) = perpendicular_basis(axis);
+        let step = N::two_pi() / N::from_usize(segments).unwrap();
+        let mut previous: Option<(usize, bool)> = None;
+        for p in profile {
+            let center = base + axis * p.height;
+            let start = self.vertices.len();
+            let is_ring = !is_zero(p.radius);
+            if is_ring {
+                for k in 0..segments {
+                    let angle = step * N::from_usize(k).unwrap();
+                    let radial = u * Float::cos(angle) + v * Float::sin(angle);
+                    self.push(
+                        center + radial * p.radius,
+                        (axis * p.axial + radial * p.radial).normalize(),
+                    );
+                }
+            } else {
+                self.push(center, axis * p.axial);
+            }
+            if let Some((prev, prev_is_ring)) = previous {
+                for k in 0..segments {
+                    let next = (k + 1) % segments;
+                    match (prev_is_ring, is_ring) {
+                        (true, true) => {
+                            self.indices.push([prev + k, prev + next, start + k]);
+                            self.indices.push([prev + next, start + next, start + k]);
+                        }
+                        (false, true) => self.indices.push([prev, start + next, start + k]),
+                        (true, false) => self.indices.push([prev + k, prev + next, start]),
+                        (false, false) => (),
+                    }
+                }
+            }
+            previous = Some((start, is_ring));
+        }
+    }
+}
+
+/// Returns two unit vectors that form a right handed basis with `axis`.
+fn perpendicular_basis<N: FloatingPhysicsScalar>(axis: &Vector3<N>) -> (Vector3<N>, Vector3<N>) {
+    let u = perpendicular(axis);
+    (u, axis.cross(&u))
+}
+
+/// Appends a circular arc from `from` to `to` radians, measured from the
+/// negative axis, to a revolve profile.
+fn arc<N: FloatingPhysicsScalar>(
+    profile: &mut Vec<ProfilePoint<N>>,
+    offset: N,
+    radius: N,
+    from: N,
+    to: N,
+    steps: usize,
+) {
+    for i in 0..=steps {
+        let angle = from + (to - from) * N::from_usize(i).unwrap() / N::from_usize(steps).unwrap();
+        let (sin, cos) = (Float::sin(angle), Float::cos(angle));
+        // Snap the poles so they become a single vertex.
+        let sin = if is_zero(sin) { N::zero() } else { sin };
+        profile.push(ProfilePoint {
+            height: offset - radius * cos,
+            radius: radius * sin,
+            axial: -cos,
+            radial: sin,
+        });
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Tessellate<N> for Sphere<N> {
+    fn tessellate(&self, detail: &TessellationDetail<N>) -> TriangleBuffers<N> {
+        let mut profile = Vec::new();
+        arc(
+            &mut profile,
+            N::zero(),
+            self.radius,
+            N::zero(),
+            N::pi(),
+            detail.rings.max(2),
+        );
+        let mut buffers = TriangleBuffers::new();
+        buffers.revolve(
+            &self.center,
+            &Vector3::y(),
+            &profile,
+            detail.segments.max(3),
+        );
+        buffers
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Tessellate<N> for Capsule<N> {
+    fn tessellate(&self, detail: &TessellationDetail<N>) -> TriangleBuffers<N> {
+        let length = (self.end - self.start).magnitude();
+        let axis = if is_zero(length) {
+            Vector3::y()
+        } else {
+            (self.end - self.start) / length
+        };
+        let steps = (detail.rings / 2).max(1);
+        let mut profile = Vec::new();
+        arc(
+            &mut profile,
+            N::zero(),
+            self.radius,
+            N::zero(),
+            N::frac_pi_2(),
+            steps,
+        );
+        arc(
+            &mut profile,
+            length,
+            self.radius,
+            N::frac_pi_2(),
+            N::pi(),
+            steps,
+        );
+        let mut buffers = TriangleBuffers::new();
+        buffers.revolve(&self.start, &axis, &profile, detail.segments.max(3));
+        buffers
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Tessellate<N> for Cylinder<N> {
+    fn tessellate(&self, detail: &TessellationDetail<N>) -> TriangleBuffers<N> {
+        let point = |height, radius, axial, radial| ProfilePoint {
+            height,
+            radius,
+            axial,
+            radial,
+        };
+        let (h, r) = (self.half_height, self.radius);
+        let axis = Vector3::ith(self.axis.to_num(), N::one());
+        let segments = detail.segments.max(3);
+        let (flat, up, down) = (N::zero(), N::one(), -N::one());
+
+        // The caps and the side are separate strips so their edges stay sharp.
+        let mut buffers = TriangleBuffers::new();
+        for strip in [
+            [point(-h, flat, down, flat), point(-h, r, down, flat)],
+            [point(-h, r, flat, up), point(h, r, flat, up)],
+            [point(h, r, up, flat), point(h, flat, up, flat)],
+        ]
+        .iter()
+        {
+            buffers.revolve(&self.center, &axis, strip, segments);
+        }
+        buffers
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Tessellate<N> for AxisAlignedBoundingBox<N> {
+    fn tessellate(&self, _: &TessellationDetail<N>) -> TriangleBuffers<N> {
+        let (start, end) = self.min_max();
+        let corners = AxisAlignedBoundingBox { start, end }.corners();
+        // Corners are indexed by their x, y and z bits, in that order. Faces
+        // are listed as -x, +x, -y, +y, -z, +z.
+        const FACES: [[usize; 4]; 6] = [
+            [0, 1, 3, 2],
+            [4, 6, 7, 5],
+            [0, 4, 5, 1],
+            [2, 3, 7, 6],
+            [0, 2, 6, 4],
+            [1, 5, 7, 3],
+        ];
+        let mut buffers = TriangleBuffers::new();
+        for (i, face) in FACES.iter().enumerate() {
+            let normal = Vector3::ith(i / 2, N::one());
+            let normal = if i % 2 == 0 { -normal } else { normal };
+            let first = buffers.vertices.len();
+            for corner in face.iter() {
+                buffers.push(corners[*corner], normal);
+            }
+            buffers.indices.push([first, first + 1, first + 2]);
+            buffers.indices.push([first, first + 2, first + 3]);
+        }
+        buffers
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Tessellate<N> for Triangle<N> {
+    fn tessellate(&self, _: &TessellationDetail<N>) -> TriangleBuffers<N> {
+        TriangleBuffers {
+            vertices: vec![self.point1, self.point2, self.point3],
+            normals: vec![self.normal(); 3],
+            indices: vec![[0, 1, 2]],
+        }
+    }
+}
+
+/// Produces a square grid facing along the normal, centered on the point of
+/// the plane closest to the origin.
+impl<N: FloatingPhysicsScalar> Tessellate<N> for Plane<N> {
+    fn tessellate(&self, detail: &TessellationDetail<N>) -> TriangleBuffers<N> {
+        let normal = self.normal.normalize();
+        let (u, v) = perpendicular_basis(&normal);
+        let center = self.closest_point(&Vector3::zeros());
+        let divisions = detail.segments.max(1);
+        let extent = detail.plane_half_extent;
+        let step = extent * N::from_f64(2.0).unwrap() / N::from_usize(divisions).unwrap();
+
+        let mut buffers = TriangleBuffers::new();
+        for j in 0..=divisions {
+            for i in 0..=divisions {
+                let s = step * N::from_usize(i).unwrap() - extent;
+                let t = step * N::from_usize(j).unwrap() - extent;
+                buffers.push(center + u * s + v * t, normal);
+            }
+        }
+        let row = divisions + 1;
+        for j in 0..divisions {
+            for i in 0..divisions {
+                let a = j * row + i;
+                buffers.indices.push([a, a + 1, a + row + 1]);
+                buffers.indices.push([a, a + row + 1, a + row]);
+            }
+        }
+        buffers
+    }
+}
+
+impl<N: FloatingPhysicsScalar> Tessellate<N> for Collider3D<N> {
+    fn tessellate(&self, detail: &TessellationDetail<N>) -> TriangleBuffers<N> {
+        match self {
+            Collider3D::AABB(a) => a.tessellate(detail),
+            Collider3D::Sphere(a) => a.tessellate(detail),
+            Collider3D::Capsule(a) => a.tessellate(detail),
+            Collider3D::Triangle(a) => a.tessellate(detail),
+            Collider3D::Plane(a) => a.tessellate(detail),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Signed volume of a closed mesh, positive when wound outwards.
+    fn volume(buffers: &TriangleBuffers<f64>) -> f64 {
+        buffers
+            .triangles()
+            .map(|t| t.point1.dot(&t.point2.cross(&t.point3)) / 6.0)
+            .sum()
+    }
+
+    /// Every face normal agrees with the normals of its vertices.
+    fn normals_face_outwards(buffers: &TriangleBuffers<f64>) -> bool {
+        buffers.indices.iter().all(|[a, b, c]| {
+            let face = (buffers.vertices[*b] - buffers.vertices[*a])
+                .cross(&(buffers.vertices[*c] - buffers.vertices[*a]));
+            [a, b, c]
+                .iter()
+                .all(|i| face.dot(&buffers.normals[**i]) > 0.0)
+        })
+    }
+
+    #[test]
+    fn closed_mesh_volume_test() {
+        let detail = TessellationDetail {
+            segments: 64,
+            rings: 32,
+            ..Default::default()
+        };
+        let aabb = AxisAlignedBoundingBox {
+            start: Vector3::new(1.0, 2.0, 3.0),
+            end: Vector3::new(0.0, 0.0, 0.0),
+        };
+        let buffers = aabb.tessellate(&detail);
+        assert!((volume(&buffers) - 6.0).abs() < 1e-9);
+        assert!(normals_face_outwards(&buffers));
+
+        let sphere = Sphere {
+            center: Vector3::new(1.0, -2.0, 0.5),
+            radius: 2f64,
+        };
+        let buffers = sphere.tessellate(&detail);
+        for (v, n) in buffers.vertices.iter().zip(buffers.normals.iter()) {
+            assert!(((v - sphere.center).magnitude() - 2.0).abs() < 1e-9);
+            assert!((v - sphere.center - n * 2.0).magnitude() < 1e-9);
+        }
+        assert!((volume(&buffers) / sphere.volume() - 1.0).abs() < 0.01);
+
+        let capsule = Capsule {
+            start: Vector3::new(0.0, 0.0, 0.0),
+            end: Vector3::new(1.0, 2.0, -1.0),
+            radius: 0.5,
+        };
+        let buffers = capsule.tessellate(&detail);
+        assert!((volume(&buffers) / capsule.volume() - 1.0).abs() < 0.01);
+        assert!(normals_face_outwards(&buffers));
+
+        let cylinder = Cylinder {
+            center: Vector3::new(0.0, 1.0, 0.0),
+            half_height: 1.5,
+            radius: 0.5,
+            axis: Axis::Z,
+        };
+        let buffers = cylinder.tessellate(&detail);
+        assert!((volume(&buffers) / cylinder.volume() - 1.0).abs() < 0.01);
+        assert!(normals_face_outwards(&buffers));
+    }
+
+    #[test]
+    fn plane_and_transform_test() {
+        let detail = TessellationDetail {
+            segments: 4,
+            rings: 4,
+            plane_half_extent: 2.0,
+        };
+        let plane = Plane::from_point(&Vector3::z(), &Vector3::new(0.0, 0.0, 3f64));
+        let buffers = plane.tessellate(&detail);
+        assert_eq!(buffers.vertices.len(), 25);
+        assert_eq!(buffers.indices.len(), 32);
+        assert!(normals_face_outwards(&buffers));
+        assert!(buffers.vertices.iter().all(|v| v.z == 3.0));
+        let area: f64 = buffers.triangles().map(|t| t.area()).sum();
+        assert!((area - 16.0).abs() < 1e-9);
+
+        // A unit cube placed by the box's matrix matches the box itself.
+        let aabb = AxisAlignedBoundingBox {
+            start: Vector3::new(-1.0, 2.0, 0.0),
+            end: Vector3::new(3.0, 3.0, 0.5f64),
+        };
+        let mut unit = AxisAlignedBoundingBox {
+            start: Vector3::from_element(-0.5),
+            end: Vector3::from_element(0.5),
+        }
+        .tessellate(&detail);
+        unit.transform_mut(&Matrix4::from(aabb));
+        let expected = aabb.tessellate(&detail);
+        for (a, b) in unit.vertices.iter().zip(expected.vertices.iter()) {
+            assert!((a - b).magnitude() < 1e-9);
+        }
+        assert_eq!(unit.normals, expected.normals);
+    }
+}
@@ -3,7 +3,7 @@ use super::*;
 #[cfg(feature = "serde-serialize")]
 use serde::{Deserialize, Serialize};
 
-use std::ops::*;
+use core::ops::*;
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
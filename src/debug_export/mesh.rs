@@ -0,0 +1,112 @@
+use super::*;
+use crate::collision3d::TriangleBuffers;
+
+/// Writes each mesh as a separate object in a Wavefront OBJ file, with normals.
+pub fn write_obj<N: FloatingPhysicsScalar, W: Write>(
+    mut writer: W,
+    meshes: &[TriangleBuffers<N>],
+) -> io::Result<()> {
+    let mut offset = 1;
+    for (i, mesh) in meshes.iter().enumerate() {
+        writeln!(writer, "o shape{}", i)?;
+        for v in mesh.vertices.iter() {
+            writeln!(writer, "v {} {} {}", to_f64(v.x), to_f64(v.y), to_f64(v.z))?;
+        }
+        for n in mesh.normals.iter() {
+            writeln!(writer, "vn {} {} {}", to_f64(n.x), to_f64(n.y), to_f64(n.z))?;
+        }
+        for [a, b, c] in mesh.indices.iter() {
+            let (a, b, c) = (a + offset, b + offset, c + offset);
+            writeln!(writer, "f {}//{} {}//{} {}//{}", a, a, b, b, c, c)?;
+        }
+        offset += mesh.vertices.len();
+    }
+    Ok(())
+}
+
+/// Writes all meshes into a single ASCII PLY file.
+pub fn write_ply<N: FloatingPhysicsScalar, W: Write>(
+    mut writer: W,
+    meshes: &[TriangleBuffers<N>],
+) -> io::Result<()> {
+    let mut merged = TriangleBuffers::new();
+    for mesh in meshes.iter() {
+        merged.append(mesh);
+    }
+
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format ascii 1.0")?;
+    writeln!(writer, "element vertex {}", merged.vertices.len())?;
+    writeln!(writer, "property double x")?;
+    writeln!(writer, "property double y")?;
+    writeln!(writer, "property double z")?;
+    writeln!(writer, "property double nx")?;
+    writeln!(writer, "property double ny")?;
+    writeln!(writer, "property double nz")?;
+    writeln!(writer, "element face {}", merged.indices.len())?;
+    writeln!(writer, "property list uchar uint vertex_indices")?;
+    writeln!(writer, "end_header")?;
+    for (v, n) in merged.vertices.iter().zip(merged.normals.iter()) {
+        writeln!(
+            writer,
+            "{} {} {} {} {} {}",
+            to_f64(v.x),
+            to_f64(v.y),
+            to_f64(v.z),
+            to_f64(n.x),
+            to_f64(n.y),
+            to_f64(n.z)
+        )?;
+    }
+    for [a, b, c] in merged.indices.iter() {
+        writeln!(writer, "3 {} {} {}", a, b, c)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collision3d::*;
+    use std::string::String;
+
+    fn meshes() -> Vec<TriangleBuffers<f32>> {
+        vec![
+            AxisAlignedBoundingBox {
+                start: Vector3::new(0f32, 0f32, 0f32),
+                end: Vector3::new(1f32, 1f32, 1f32),
+            }
+            .tessellate(&TessellationDetail::default()),
+            Triangle {
+                point1: Vector3::new(0f32, 2f32, 0f32),
+                point2: Vector3::new(1f32, 2f32, 0f32),
+                point3: Vector3::new(0f32, 3f32, 0f32),
+            }
+            .tessellate(&TessellationDetail::default()),
+        ]
+    }
+
+    #[test]
+    fn write_obj_test() {
+        let mut bytes = Vec::new();
+        write_obj(&mut bytes, &meshes()).unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+
+        assert_eq!(text.lines().filter(|l| l.starts_with("o ")).count(), 2);
+        assert_eq!(text.lines().filter(|l| l.starts_with("v ")).count(), 27);
+        assert_eq!(text.lines().filter(|l| l.starts_with("vn ")).count(), 27);
+        assert_eq!(text.lines().filter(|l| l.starts_with("f ")).count(), 13);
+        assert_eq!(text.lines().last(), Some("f 25//25 26//26 27//27"));
+    }
+
+    #[test]
+    fn write_ply_test() {
+        let mut bytes = Vec::new();
+        write_ply(&mut bytes, &meshes()).unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+
+        assert!(text.contains("element vertex 27\n"));
+        assert!(text.contains("element face 13\n"));
+        assert_eq!(text.lines().last(), Some("3 24 25 26"));
+    }
+}
@@ -0,0 +1,18 @@
+//! Writers that dump colliders to files for inspection in external viewers:
+//! SVG for 2D scenes and OBJ or PLY for tessellated 3D shapes.
+
+pub mod svg;
+pub use svg::*;
+
+pub mod mesh;
+pub use mesh::*;
+
+use super::*;
+
+use std::io;
+use std::io::Write;
+
+/// Converts a scalar for printing. Values that cannot be represented print as NaN.
+fn to_f64<N: FloatingPhysicsScalar>(n: N) -> f64 {
+    n.to_f64().unwrap_or(f64::NAN)
+}
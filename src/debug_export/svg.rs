@@ -0,0 +1,137 @@
+use super::*;
+use crate::collision2d::*;
+
+/// A resolution paired with the point its arrow is drawn from.
+pub type SvgContact<N> = (Vector2<N>, CollisionResolution<Vector2<N>, N>);
+
+/// Writes shapes in black and each `(point, resolution)` contact as a red
+/// arrow starting at `point` along the resolution normal, labelled with the
+/// penetration depth. The y axis points up, unlike the SVG default.
+pub fn write_svg<N: FloatingPhysicsScalar, W: Write>(
+    mut writer: W,
+    shapes: &[Collider2D<N>],
+    contacts: &[SvgContact<N>],
+) -> io::Result<()> {
+    let bounds = shapes.iter().map(|s| s.bounding_aabb()).sum::<AABB2D<N>>();
+    let bounds = contacts
+        .iter()
+        .fold(bounds, |b, (point, _)| b.expand_to_point(point));
+    let (min_vec, max_vec) = if bounds.is_empty() {
+        (Vector2::zeros(), Vector2::new(N::one(), N::one()))
+    } else {
+        bounds.min_max()
+    };
+    let (min_x, min_y) = (to_f64(min_vec.x), to_f64(min_vec.y));
+    let (max_x, max_y) = (to_f64(max_vec.x), to_f64(max_vec.y));
+    let size = (max_x - min_x).max(max_y - min_y).max(f64::EPSILON);
+    let margin = size * 0.1;
+    let stroke = size * 0.005;
+
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+        min_x - margin,
+        -max_y - margin,
+        max_x - min_x + margin * 2.0,
+        max_y - min_y + margin * 2.0
+    )?;
+    writeln!(
+        writer,
+        r#"<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="6" markerHeight="6" orient="auto"><path d="M 0 0 L 10 5 L 0 10 z" fill="red"/></marker></defs>"#
+    )?;
+
+    writeln!(
+        writer,
+        r#"<g fill="none" stroke="black" stroke-width="{}">"#,
+        stroke
+    )?;
+    for shape in shapes.iter() {
+        match shape {
+            Collider2D::AABB(a) => {
+                let (start, end) = a.min_max();
+                writeln!(
+                    writer,
+                    r#"<rect x="{}" y="{}" width="{}" height="{}"/>"#,
+                    to_f64(start.x),
+                    -to_f64(end.y),
+                    to_f64(a.width()),
+                    to_f64(a.height())
+                )?;
+            }
+            Collider2D::Circle(c) => {
+                writeln!(
+                    writer,
+                    r#"<circle cx="{}" cy="{}" r="{}"/>"#,
+                    to_f64(c.center.x),
+                    -to_f64(c.center.y),
+                    to_f64(c.radius)
+                )?;
+            }
+        }
+    }
+    writeln!(writer, "</g>")?;
+
+    writeln!(
+        writer,
+        r#"<g fill="red" stroke="red" stroke-width="{}" font-size="{}">"#,
+        stroke,
+        size * 0.03
+    )?;
+    for (point, res) in contacts.iter() {
+        // Keep shallow contacts visible by giving the arrow a minimum length.
+        let length = to_f64(res.penetration).max(size * 0.05);
+        let (x, y) = (to_f64(point.x), to_f64(point.y));
+        let (nx, ny) = (to_f64(res.normal.x), to_f64(res.normal.y));
+        writeln!(
+            writer,
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" marker-end="url(#arrow)"/>"#,
+            x,
+            -y,
+            x + nx * length,
+            -(y + ny * length)
+        )?;
+        writeln!(
+            writer,
+            r#"<text x="{}" y="{}" stroke="none">{}</text>"#,
+            x + nx * length,
+            -(y + ny * length),
+            to_f64(res.penetration)
+        )?;
+    }
+    writeln!(writer, "</g>")?;
+    writeln!(writer, "</svg>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::String;
+
+    #[test]
+    fn write_svg_test() {
+        let aabb = AABB2D {
+            start: Vector2::new(0f64, 0f64),
+            end: Vector2::new(2f64, 1f64),
+        };
+        let circle = Circle {
+            center: Vector2::new(2.5f64, 0.5f64),
+            radius: 1f64,
+        };
+        let res = aabb.get_circle_collision(&circle).unwrap().flip();
+
+        let mut bytes = Vec::new();
+        write_svg(
+            &mut bytes,
+            &[Collider2D::AABB(aabb), Collider2D::Circle(circle)],
+            &[(Vector2::new(2f64, 0.5f64), res)],
+        )
+        .unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+
+        assert!(text.contains(r#"<rect x="0" y="-1" width="2" height="1"/>"#));
+        assert!(text.contains(r#"<circle cx="2.5" cy="-0.5" r="1"/>"#));
+        assert!(text.contains(r#"<line x1="2" y1="-0.5" x2="2.5" y2="-0.5""#));
+        assert!(text.contains(">0.5</text>"));
+        assert!(text.ends_with("</svg>\n"));
+    }
+}
@@ -0,0 +1,297 @@
+use super::*;
+use crate::collision2d::*;
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct RigidBody2D<N: PhysicsScalar> {
+    pub shape: Collider2D<N>,
+    pub rotation: N,
+    pub linear_velocity: Vector2<N>,
+    pub angular_velocity: N,
+    pub inverse_mass: N,
+    pub inverse_inertia: N,
+    pub restitution: N,
+    pub friction: N,
+}
+
+#[inline(always)]
+fn cross<N: FloatingPhysicsScalar>(a: &Vector2<N>, b: &Vector2<N>) -> N {
+    a.x * b.y - a.y * b.x
+}
+
+#[inline(always)]
+fn cross_scalar<N: FloatingPhysicsScalar>(w: N, r: &Vector2<N>) -> Vector2<N> {
+    Vector2::new(-w * r.y, w * r.x)
+}
+
+impl<N: FloatingPhysicsScalar> RigidBody2D<N> {
+    pub fn new(shape: Collider2D<N>, density: N) -> Self {
+        let MassData { mass, inertia, .. } = shape.mass_properties(density);
+        let (inverse_mass, inverse_inertia) = if mass > N::zero() {
+            (N::one() / mass, N::one() / inertia)
+        } else {
+            (N::zero(), N::zero())
+        };
+        RigidBody2D {
+            shape,
+            rotation: N::zero(),
+            linear_velocity: Vector2::zeros(),
+            angular_velocity: N::zero(),
+            inverse_mass,
+            inverse_inertia,
+            restitution: N::zero(),
+            friction: N::from_f64(0.5).unwrap(),
+        }
+    }
+    pub fn new_static(shape: Collider2D<N>) -> Self {
+        Self::new(shape, N::zero())
+    }
+    pub fn is_static(&self) -> bool {
+        is_zero(self.inverse_mass)
+    }
+    pub fn position(&self) -> Vector2<N> {
+        self.shape.center()
+    }
+    pub fn velocity_at(&self, point: &Vector2<N>) -> Vector2<N> {
+        self.linear_velocity + cross_scalar(self.angular_velocity, &(point - self.position()))
+    }
+    pub fn apply_impulse(&mut self, impulse: &Vector2<N>, point: &Vector2<N>) {
+        let r = point - self.position();
+        self.linear_velocity += impulse * self.inverse_mass;
+        self.angular_velocity += cross(&r, impulse) * self.inverse_inertia;
+    }
+    fn integrate(&mut self, gravity: &Vector2<N>, dt: N) {
+        if self.is_static() {
+            return;
+        }
+        self.linear_velocity += gravity * dt;
+        self.shape.translate_mut(&(self.linear_velocity * dt));
+        self.rotation += self.angular_velocity * dt;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct World2D<N: PhysicsScalar> {
+    pub bodies: Vec<RigidBody2D<N>>,
+    pub gravity: Vector2<N>,
+    pub settings: SolverSettings<N>,
+    accumulator: N,
+}
+
+struct Contact2D<N: PhysicsScalar> {
+    a: usize,
+    b: usize,
+    normal: Vector2<N>,
+    point: Vector2<N>,
+    penetration: N,
+    bias: N,
+    normal_impulse: N,
+    tangent_impulse: N,
+}
+
+impl<N: FloatingPhysicsScalar> World2D<N> {
+    pub fn new(gravity: Vector2<N>, settings: SolverSettings<N>) -> Self {
+        World2D {
+            bodies: Vec::new(),
+            gravity,
+            settings,
+            accumulator: N::zero(),
+        }
+    }
+    /// Advances the simulation by `elapsed`, running as many fixed steps as fit.
+    /// Returns the number of steps taken.
+    pub fn update(&mut self, elapsed: N) -> usize {
+        self.accumulator += elapsed;
+        let mut steps = 0;
+        while self.accumulator >= self.settings.timestep {
+            self.step();
+            self.accumulator -= self.settings.timestep;
+            steps += 1;
+        }
+        steps
+    }
+    pub fn step(&mut self) {
+        let dt = self.settings.timestep;
+        for body in self.bodies.iter_mut() {
+            body.integrate(&self.gravity, dt);
+        }
+
+        let mut contacts = self.find_contacts();
+        for _ in 0..self.settings.iterations {
+            for contact in contacts.iter_mut() {
+                self.solve_contact(contact);
+            }
+        }
+        for contact in contacts.iter() {
+            self.correct_position(contact);
+        }
+    }
+    fn find_contacts(&self) -> Vec<Contact2D<N>> {
+        let mut contacts = Vec::new();
+        for a in 0..self.bodies.len() {
+            for b in (a + 1)..self.bodies.len() {
+                let (body_a, body_b) = (&self.bodies[a], &self.bodies[b]);
+                if body_a.is_static() && body_b.is_static() {
+                    continue;
+                }
+                let res = match body_a.shape.get_collision(&body_b.shape) {
+                    Some(res) if res.penetration > N::zero() => res,
+                    _ => continue,
+                };
+                let point = (support_point(&body_a.shape, &-res.normal)
+                    + support_point(&body_b.shape, &res.normal))
+                    * N::from_f64(0.5).unwrap();
+                let relative = body_a.velocity_at(&point) - body_b.velocity_at(&point);
+                let approach = relative.dot(&res.normal);
+                let restitution = combine_restitution(body_a.restitution, body_b.restitution);
+                contacts.push(Contact2D {
+                    a,
+                    b,
+                    normal: res.normal,
+                    point,
+                    penetration: res.penetration,
+                    bias: restitution_bias(approach, restitution, &self.settings),
+                    normal_impulse: N::zero(),
+                    tangent_impulse: N::zero(),
+                });
+            }
+        }
+        contacts
+    }
+    fn effective_mass(&self, contact: &Contact2D<N>, direction: &Vector2<N>) -> N {
+        let (a, b) = (&self.bodies[contact.a], &self.bodies[contact.b]);
+        let ra = cross(&(contact.point - a.position()), direction);
+        let rb = cross(&(contact.point - b.position()), direction);
+        a.inverse_mass + b.inverse_mass + ra * ra * a.inverse_inertia + rb * rb * b.inverse_inertia
+    }
+    fn apply(&mut self, contact: &Contact2D<N>, impulse: &Vector2<N>) {
+        self.bodies[contact.a].apply_impulse(impulse, &contact.point);
+        self.bodies[contact.b].apply_impulse(&-impulse, &contact.point);
+    }
+    fn solve_contact(&mut self, contact: &mut Contact2D<N>) {
+        let relative = self.bodies[contact.a].velocity_at(&contact.point)
+            - self.bodies[contact.b].velocity_at(&contact.point);
+
+        let k = self.effective_mass(contact, &contact.normal);
+        if is_zero(k) {
+            return;
+        }
+        let j = (contact.bias - relative.dot(&contact.normal)) / k;
+        let total = n_max(contact.normal_impulse + j, N::zero());
+        let j = total - contact.normal_impulse;
+        contact.normal_impulse = total;
+        self.apply(contact, &(contact.normal * j));
+
+        let relative = self.bodies[contact.a].velocity_at(&contact.point)
+            - self.bodies[contact.b].velocity_at(&contact.point);
+        let tangent = Vector2::new(-contact.normal.y, contact.normal.x);
+        let k = self.effective_mass(contact, &tangent);
+        if is_zero(k) {
+            return;
+        }
+        let friction = combine_friction(
+            self.bodies[contact.a].friction,
+            self.bodies[contact.b].friction,
+        );
+        let limit = contact.normal_impulse * friction;
+        let jt = -relative.dot(&tangent) / k;
+        let total = num_traits::clamp(contact.tangent_impulse + jt, -limit, limit);
+        let jt = total - contact.tangent_impulse;
+        contact.tangent_impulse = total;
+        self.apply(contact, &(tangent * jt));
+    }
+    fn correct_position(&mut self, contact: &Contact2D<N>) {
+        let (ima, imb) = (
+            self.bodies[contact.a].inverse_mass,
+            self.bodies[contact.b].inverse_mass,
+        );
+        let correction = position_correction(contact.penetration, ima + imb, &self.settings);
+        let correction = contact.normal * correction;
+        self.bodies[contact.a]
+            .shape
+            .translate_mut(&(correction * ima));
+        self.bodies[contact.b]
+            .shape
+            .translate_mut(&(correction * -imb));
+    }
+}
+
+fn support_point<N: FloatingPhysicsScalar>(
+    shape: &Collider2D<N>,
+    direction: &Vector2<N>,
+) -> Vector2<N> {
+    match shape {
+        Collider2D::AABB(a) => {
+            let (min_vec, max_vec) = a.min_max();
+            let center = a.center();
+            Vector2::from_fn(|i, _| {
+                if is_zero(direction[i]) {
+                    center[i]
+                } else if direction[i] > N::zero() {
+                    max_vec[i]
+                } else {
+                    min_vec[i]
+                }
+            })
+        }
+        Collider2D::Circle(c) => c.center + direction * c.radius,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn box_rests_on_floor_test() {
+        let mut world = World2D::new(Vector2::new(0.0, -9.8), SolverSettings::default());
+        world
+            .bodies
+            .push(RigidBody2D::new_static(Collider2D::AABB(AABB2D {
+                start: Vector2::new(-10.0, -1.0),
+                end: Vector2::new(10.0, 0.0),
+            })));
+        world.bodies.push(RigidBody2D::new(
+            Collider2D::AABB(AABB2D {
+                start: Vector2::new(-0.5, 0.5),
+                end: Vector2::new(0.5, 1.5f32),
+            }),
+            1.0,
+        ));
+        world.update(2.0);
+
+        let body = &world.bodies[1];
+        assert!((body.position().y - 0.5).abs() < 0.05);
+        assert!(body.linear_velocity.magnitude() < 0.1);
+    }
+
+    #[test]
+    fn ball_bounces_test() {
+        let mut world = World2D::new(Vector2::new(0.0, -9.8), SolverSettings::default());
+        world
+            .bodies
+            .push(RigidBody2D::new_static(Collider2D::AABB(AABB2D {
+                start: Vector2::new(-10.0, -1.0),
+                end: Vector2::new(10.0, 0.0),
+            })));
+        let mut ball = RigidBody2D::new(
+            Collider2D::Circle(Circle {
+                center: Vector2::new(0.0, 0.55f32),
+                radius: 0.5,
+            }),
+            1.0,
+        );
+        ball.linear_velocity = Vector2::new(0.0, -5.0);
+        ball.restitution = 1.0;
+        world.bodies.push(ball);
+
+        world.step();
+        world.step();
+
+        assert!(world.bodies[1].linear_velocity.y > 4.0);
+    }
+}
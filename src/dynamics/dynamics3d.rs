@@ -0,0 +1,337 @@
+use super::*;
+use crate::collision3d::*;
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct RigidBody3D<N: FloatingPhysicsScalar> {
+    pub shape: Collider3D<N>,
+    pub orientation: UnitQuaternion<N>,
+    pub linear_velocity: Vector3<N>,
+    pub angular_velocity: Vector3<N>,
+    pub inverse_mass: N,
+    /// Inverse inertia tensor in the body's local frame.
+    pub inverse_inertia: Matrix3<N>,
+    pub restitution: N,
+    pub friction: N,
+}
+
+impl<N: FloatingPhysicsScalar> RigidBody3D<N> {
+    pub fn new(shape: Collider3D<N>, density: N) -> Self {
+        let MassData { mass, inertia, .. } = shape.mass_properties(density);
+        let (inverse_mass, inverse_inertia) = if mass > N::zero() {
+            (
+                N::one() / mass,
+                inertia.try_inverse().unwrap_or_else(Matrix3::zeros),
+            )
+        } else {
+            (N::zero(), Matrix3::zeros())
+        };
+        RigidBody3D {
+            shape,
+            orientation: UnitQuaternion::identity(),
+            linear_velocity: Vector3::zeros(),
+            angular_velocity: Vector3::zeros(),
+            inverse_mass,
+            inverse_inertia,
+            restitution: N::zero(),
+            friction: N::from_f64(0.5).unwrap(),
+        }
+    }
+    pub fn new_static(shape: Collider3D<N>) -> Self {
+        Self::new(shape, N::zero())
+    }
+    pub fn is_static(&self) -> bool {
+        is_zero(self.inverse_mass)
+    }
+    pub fn position(&self) -> Vector3<N> {
+        self.shape.center()
+    }
+    pub fn world_inverse_inertia(&self) -> Matrix3<N> {
+        let r = self.orientation.to_rotation_matrix();
+        r.matrix() * self.inverse_inertia * r.matrix().transpose()
+    }
+    pub fn velocity_at(&self, point: &Vector3<N>) -> Vector3<N> {
+        self.linear_velocity + self.angular_velocity.cross(&(point - self.position()))
+    }
+    pub fn apply_impulse(&mut self, impulse: &Vector3<N>, point: &Vector3<N>) {
+        let r = point - self.position();
+        self.linear_velocity += impulse * self.inverse_mass;
+        self.angular_velocity += self.world_inverse_inertia() * r.cross(impulse);
+    }
+    fn integrate(&mut self, gravity: &Vector3<N>, dt: N) {
+        if self.is_static() {
+            return;
+        }
+        self.linear_velocity += gravity * dt;
+        self.shape.translate_mut(&(self.linear_velocity * dt));
+
+        let rotation = UnitQuaternion::from_scaled_axis(self.angular_velocity * dt);
+        self.orientation = rotation * self.orientation;
+        self.orientation.renormalize();
+
+        // Boxes, spheres and planes stay axis aligned; only segment-based shapes turn.
+        let center = self.shape.center();
+        let turn = |p: &mut Vector3<N>| *p = center + rotation * (*p - center);
+        match &mut self.shape {
+            Collider3D::Capsule(c) => {
+                turn(&mut c.start);
+                turn(&mut c.end);
+            }
+            Collider3D::Triangle(t) => {
+                turn(&mut t.point1);
+                turn(&mut t.point2);
+                turn(&mut t.point3);
+            }
+            _ => (),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct World3D<N: FloatingPhysicsScalar> {
+    pub bodies: Vec<RigidBody3D<N>>,
+    pub gravity: Vector3<N>,
+    pub settings: SolverSettings<N>,
+    accumulator: N,
+}
+
+struct Contact3D<N: PhysicsScalar> {
+    a: usize,
+    b: usize,
+    normal: Vector3<N>,
+    point: Vector3<N>,
+    penetration: N,
+    bias: N,
+    normal_impulse: N,
+    tangent_impulse: N,
+}
+
+impl<N: FloatingPhysicsScalar> World3D<N> {
+    pub fn new(gravity: Vector3<N>, settings: SolverSettings<N>) -> Self {
+        World3D {
+            bodies: Vec::new(),
+            gravity,
+            settings,
+            accumulator: N::zero(),
+        }
+    }
+    /// Advances the simulation by `elapsed`, running as many fixed steps as fit.
+    /// Returns the number of steps taken.
+    pub fn update(&mut self, elapsed: N) -> usize {
+        self.accumulator += elapsed;
+        let mut steps = 0;
+        while self.accumulator >= self.settings.timestep {
+            self.step();
+            self.accumulator -= self.settings.timestep;
+            steps += 1;
+        }
+        steps
+    }
+    pub fn step(&mut self) {
+        let dt = self.settings.timestep;
+        for body in self.bodies.iter_mut() {
+            body.integrate(&self.gravity, dt);
+        }
+
+        let mut contacts = self.find_contacts();
+        for _ in 0..self.settings.iterations {
+            for contact in contacts.iter_mut() {
+                self.solve_contact(contact);
+            }
+        }
+        for contact in contacts.iter() {
+            self.correct_position(contact);
+        }
+    }
+    fn find_contacts(&self) -> Vec<Contact3D<N>> {
+        let mut contacts = Vec::new();
+        for a in 0..self.bodies.len() {
+            for b in (a + 1)..self.bodies.len() {
+                let (body_a, body_b) = (&self.bodies[a], &self.bodies[b]);
+                if body_a.is_static() && body_b.is_static() {
+                    continue;
+                }
+                let res = match body_a.shape.get_collision(&body_b.shape) {
+                    Some(res) if res.penetration > N::zero() => res,
+                    _ => continue,
+                };
+                let point = contact_point(&body_a.shape, &body_b.shape, &res.normal);
+                let relative = body_a.velocity_at(&point) - body_b.velocity_at(&point);
+                let approach = relative.dot(&res.normal);
+                let restitution = combine_restitution(body_a.restitution, body_b.restitution);
+                contacts.push(Contact3D {
+                    a,
+                    b,
+                    normal: res.normal,
+                    point,
+                    penetration: res.penetration,
+                    bias: restitution_bias(approach, restitution, &self.settings),
+                    normal_impulse: N::zero(),
+                    tangent_impulse: N::zero(),
+                });
+            }
+        }
+        contacts
+    }
+    fn effective_mass(&self, contact: &Contact3D<N>, direction: &Vector3<N>) -> N {
+        let (a, b) = (&self.bodies[contact.a], &self.bodies[contact.b]);
+        let ra = contact.point - a.position();
+        let rb = contact.point - b.position();
+        let angular_a = (a.world_inverse_inertia() * ra.cross(direction)).cross(&ra);
+        let angular_b = (b.world_inverse_inertia() * rb.cross(direction)).cross(&rb);
+        a.inverse_mass + b.inverse_mass + direction.dot(&(angular_a + angular_b))
+    }
+    fn apply(&mut self, contact: &Contact3D<N>, impulse: &Vector3<N>) {
+        self.bodies[contact.a].apply_impulse(impulse, &contact.point);
+        self.bodies[contact.b].apply_impulse(&-impulse, &contact.point);
+    }
+    fn solve_contact(&mut self, contact: &mut Contact3D<N>) {
+        let relative = self.bodies[contact.a].velocity_at(&contact.point)
+            - self.bodies[contact.b].velocity_at(&contact.point);
+
+        let k = self.effective_mass(contact, &contact.normal);
+        if is_zero(k) {
+            return;
+        }
+        let j = (contact.bias - relative.dot(&contact.normal)) / k;
+        let total = n_max(contact.normal_impulse + j, N::zero());
+        let j = total - contact.normal_impulse;
+        contact.normal_impulse = total;
+        self.apply(contact, &(contact.normal * j));
+
+        let relative = self.bodies[contact.a].velocity_at(&contact.point)
+            - self.bodies[contact.b].velocity_at(&contact.point);
+        let tangent = relative - contact.normal * relative.dot(&contact.normal);
+        if is_zero(tangent.magnitude_squared()) {
+            return;
+        }
+        let tangent = tangent.normalize();
+        let k = self.effective_mass(contact, &tangent);
+        if is_zero(k) {
+            return;
+        }
+        let friction = combine_friction(
+            self.bodies[contact.a].friction,
+            self.bodies[contact.b].friction,
+        );
+        let limit = contact.normal_impulse * friction;
+        let jt = -relative.dot(&tangent) / k;
+        let total = num_traits::clamp(contact.tangent_impulse + jt, -limit, limit);
+        let jt = total - contact.tangent_impulse;
+        contact.tangent_impulse = total;
+        self.apply(contact, &(tangent * jt));
+    }
+    fn correct_position(&mut self, contact: &Contact3D<N>) {
+        let (ima, imb) = (
+            self.bodies[contact.a].inverse_mass,
+            self.bodies[contact.b].inverse_mass,
+        );
+        let correction = position_correction(contact.penetration, ima + imb, &self.settings);
+        let correction = contact.normal * correction;
+        self.bodies[contact.a]
+            .shape
+            .translate_mut(&(correction * ima));
+        self.bodies[contact.b]
+            .shape
+            .translate_mut(&(correction * -imb));
+    }
+}
+
+/// Estimates where two overlapping shapes touch given a normal pointing from `b` to `a`.
+fn contact_point<N: FloatingPhysicsScalar>(
+    a: &Collider3D<N>,
+    b: &Collider3D<N>,
+    normal: &Vector3<N>,
+) -> Vector3<N> {
+    match (a.support_map(), b.support_map()) {
+        (Some(shape_a), Some(shape_b)) => {
+            let on_a = shape_a.support_point(&-normal);
+            let on_b = shape_b.support_point(normal);
+            match (a, b) {
+                (_, Collider3D::Triangle(_)) => on_a,
+                (Collider3D::Triangle(_), _) => on_b,
+                _ => (on_a + on_b) * N::from_f64(0.5).unwrap(),
+            }
+        }
+        // Everything behind a plane is solid, so the other shape touches it at
+        // its deepest point.
+        (Some(shape_a), None) => shape_a.support_point(&-normal),
+        (None, Some(shape_b)) => shape_b.support_point(normal),
+        // Planes never collide with each other.
+        (None, None) => Vector3::zeros(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn world() -> World3D<f32> {
+        let mut world = World3D::new(Vector3::new(0.0, -9.8, 0.0), SolverSettings::default());
+        world.bodies.push(RigidBody3D::new_static(Collider3D::Plane(
+            Plane::from_point(&Vector3::y(), &Vector3::zeros()),
+        )));
+        world
+    }
+
+    #[test]
+    fn box_rests_on_plane_test() {
+        let mut world = world();
+        world.bodies.push(RigidBody3D::new(
+            Collider3D::AABB(AABB3D {
+                start: Vector3::new(-0.5, 0.0, -0.5),
+                end: Vector3::new(0.5, 1.0, 0.5),
+            }),
+            1.0,
+        ));
+        world.update(2.0);
+
+        let body = &world.bodies[1];
+        assert!((body.position().y - 0.5).abs() < 0.05);
+        assert!(body.linear_velocity.magnitude() < 0.1);
+        assert!(body.angular_velocity.magnitude() < 1e-3);
+    }
+
+    #[test]
+    fn spheres_exchange_momentum_test() {
+        let mut world = World3D::new(Vector3::zeros(), SolverSettings::default());
+        let mut a = RigidBody3D::new(
+            Collider3D::Sphere(Sphere {
+                center: Vector3::new(-1.05, 0.0, 0.0),
+                radius: 0.5f32,
+            }),
+            1.0,
+        );
+        a.linear_velocity = Vector3::new(2.0, 0.0, 0.0);
+        a.restitution = 1.0;
+        let mut b = a;
+        b.shape.set_center_mut(&Vector3::zeros());
+        b.linear_velocity = Vector3::zeros();
+        world.bodies.push(a);
+        world.bodies.push(b);
+
+        world.update(0.2);
+
+        let (a, b) = (&world.bodies[0], &world.bodies[1]);
+        assert!(a.linear_velocity.x.abs() < 0.05);
+        assert!((b.linear_velocity.x - 2.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn plane_contact_point_test() {
+        let normal = Vector3::new(1.0f32, 1.0, 0.0).normalize();
+        let plane = Collider3D::Plane(Plane::from_point(&normal, &Vector3::zeros()));
+        let sphere = Collider3D::Sphere(Sphere {
+            center: Vector3::new(0.5, 0.5, 0.0),
+            radius: 1.0,
+        });
+        let res = sphere.get_collision(&plane).unwrap();
+        let deepest = Vector3::new(0.5, 0.5, 0.0) - normal;
+        assert!((contact_point(&sphere, &plane, &res.normal) - deepest).magnitude() < 1e-4);
+        assert!((contact_point(&plane, &sphere, &-res.normal) - deepest).magnitude() < 1e-4);
+    }
+}
@@ -0,0 +1,77 @@
+//! Rigid bodies stepped at a fixed rate, with contacts from the `get_*_collision`
+//! functions resolved by a sequential impulse solver.
+
+pub mod dynamics2d;
+pub use dynamics2d::*;
+
+pub mod dynamics3d;
+pub use dynamics3d::*;
+
+use super::*;
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct SolverSettings<N: PhysicsScalar> {
+    /// Length of a single fixed step.
+    pub timestep: N,
+    /// Number of passes the impulse solver makes over the contacts each step.
+    pub iterations: usize,
+    /// Penetration that is allowed to remain so resting contacts do not jitter.
+    pub slop: N,
+    /// Fraction of the remaining penetration removed each step.
+    pub correction: N,
+    /// Approach speed below which contacts do not bounce.
+    pub restitution_threshold: N,
+}
+
+impl<N: FloatingPhysicsScalar> Default for SolverSettings<N> {
+    fn default() -> Self {
+        SolverSettings {
+            timestep: N::from_f64(1.0 / 60.0).unwrap(),
+            iterations: 10,
+            slop: N::from_f64(0.005).unwrap(),
+            correction: N::from_f64(0.4).unwrap(),
+            restitution_threshold: N::from_f64(0.5).unwrap(),
+        }
+    }
+}
+
+#[inline(always)]
+pub(crate) fn combine_restitution<N: FloatingPhysicsScalar>(a: N, b: N) -> N {
+    n_max(a, b)
+}
+
+#[inline(always)]
+pub(crate) fn combine_friction<N: FloatingPhysicsScalar>(a: N, b: N) -> N {
+    Float::sqrt(a * b)
+}
+
+/// Target separating speed for a contact approaching at `approach` (negative when closing).
+#[inline(always)]
+pub(crate) fn restitution_bias<N: FloatingPhysicsScalar>(
+    approach: N,
+    restitution: N,
+    settings: &SolverSettings<N>,
+) -> N {
+    if -approach > settings.restitution_threshold {
+        -approach * restitution
+    } else {
+        N::zero()
+    }
+}
+
+#[inline(always)]
+pub(crate) fn position_correction<N: FloatingPhysicsScalar>(
+    penetration: N,
+    inverse_mass: N,
+    settings: &SolverSettings<N>,
+) -> N {
+    if is_zero(inverse_mass) {
+        N::zero()
+    } else {
+        n_max(penetration - settings.slop, N::zero()) * settings.correction / inverse_mass
+    }
+}
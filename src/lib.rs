@@ -1,3 +1,25 @@
+//! Collision detection for simple 2D and 3D shapes.
+//!
+//! The crate is `no_std`. The default `std` feature can be swapped for `libm`
+//! to provide float math on targets without the standard library, and the
+//! `alloc` feature enables the parts that need a heap, such as triangle meshes,
+//! tessellation, batches and pair finding.
+
+#![no_std]
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("either the `std` or the `libm` feature is required for float math");
+
+// Keeps `core` pointing at the standard crate rather than `nalgebra::core`.
+extern crate core;
+
+#[cfg(any(feature = "std", test))]
+extern crate std;
+
+#[cfg(feature = "alloc")]
+#[macro_use]
+extern crate alloc;
+
 pub mod collision3d;
 
 pub mod collision2d;
@@ -5,12 +27,37 @@ pub mod collision2d;
 pub mod collision_resolution;
 pub use collision_resolution::*;
 
+pub mod mass_properties;
+pub use mass_properties::*;
+
+pub mod point_projection;
+pub use point_projection::*;
+
+pub mod character_controller;
+
+#[cfg(feature = "alloc")]
+pub mod broad_phase;
+#[cfg(feature = "alloc")]
+pub use broad_phase::*;
+
+#[cfg(feature = "alloc")]
+mod spatial_tree;
+
+#[cfg(feature = "dynamics")]
+pub mod dynamics;
+
+#[cfg(feature = "debug-export")]
+pub mod debug_export;
+
 use nalgebra::base::dimension::Dim;
 use nalgebra::storage::Storage;
 use nalgebra::*;
 use num_traits::*;
 
-use std::cmp::Ordering;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use core::cmp::Ordering;
 
 pub trait PhysicsScalar:
     Scalar
@@ -89,12 +136,9 @@ pub(crate) fn n_min_iter<
     index: usize,
 ) -> N {
     iter.min_by(|a, b| unsafe {
-        match a
-            .vget_unchecked(index)
-            .partial_cmp(&b.vget_unchecked(index))
-        {
+        match a.vget_unchecked(index).partial_cmp(b.vget_unchecked(index)) {
             Some(ord) => ord,
-            None => std::cmp::Ordering::Equal,
+            None => core::cmp::Ordering::Equal,
         }
     })
     .map(|v| unsafe { *v.get_unchecked(index) })
@@ -113,12 +157,9 @@ pub(crate) fn n_max_iter<
     index: usize,
 ) -> N {
     iter.max_by(|a, b| unsafe {
-        match a
-            .vget_unchecked(index)
-            .partial_cmp(&b.vget_unchecked(index))
-        {
+        match a.vget_unchecked(index).partial_cmp(b.vget_unchecked(index)) {
             Some(ord) => ord,
-            None => std::cmp::Ordering::Equal,
+            None => core::cmp::Ordering::Equal,
         }
     })
     .map(|v| unsafe { *v.get_unchecked(index) })
@@ -130,6 +171,258 @@ pub(crate) fn is_zero<N: Float>(a: N) -> bool {
     Float::abs(a) < Float::epsilon()
 }
 
+/// The part of a ray that lies inside a box, as distances along the ray.
+pub(crate) struct SlabClip<N> {
+    pub(crate) enter: N,
+    pub(crate) exit: N,
+    /// The axis whose faces the ray enters through, or `None` when it starts
+    /// inside.
+    pub(crate) axis: Option<usize>,
+}
+
+/// Clips a ray from `point` along `direction` to the box from `min` to `max`
+/// by the slab test over each axis. `None` when it misses or only reaches the
+/// box past `max_distance`.
+pub(crate) fn slab_clip<N: FloatingPhysicsScalar>(
+    min: &[N],
+    max: &[N],
+    point: &[N],
+    direction: &[N],
+    max_distance: N,
+) -> Option<SlabClip<N>> {
+    let mut clip = SlabClip {
+        enter: N::zero(),
+        exit: max_distance,
+        axis: None,
+    };
+    for i in 0..point.len() {
+        if is_zero(direction[i]) {
+            if point[i] < min[i] || point[i] > max[i] {
+                return None;
+            }
+            continue;
+        }
+        let t1 = (min[i] - point[i]) / direction[i];
+        let t2 = (max[i] - point[i]) / direction[i];
+        if n_min(t1, t2) > clip.enter {
+            clip.enter = n_min(t1, t2);
+            clip.axis = Some(i);
+        }
+        clip.exit = n_min(clip.exit, n_max(t1, t2));
+        if clip.enter > clip.exit {
+            return None;
+        }
+    }
+    Some(clip)
+}
+
+/// Where a ray from `point` along `direction` enters the box from `min` to
+/// `max`. Zero when it starts inside, `None` when it misses or only reaches
+/// the box past `max_distance`.
+pub(crate) fn slab_entry<N: FloatingPhysicsScalar>(
+    min: &[N],
+    max: &[N],
+    point: &[N],
+    direction: &[N],
+    max_distance: N,
+) -> Option<N> {
+    slab_clip(min, max, point, direction, max_distance).map(|clip| clip.enter)
+}
+
+/// A cell a ray touches on its walk through a grid.
+#[cfg(feature = "alloc")]
+pub(crate) struct GridStep<N, const D: usize> {
+    pub(crate) cell: [usize; D],
+    pub(crate) distance: N,
+    /// The axes whose faces the ray enters the cell through. None when it
+    /// starts inside the cell or on its boundary.
+    pub(crate) entered: [bool; D],
+}
+
+/// Walks a ray through the cells of a grid in the order it reaches them, using
+/// the traversal of Amanatides and Woo, and returns the first cell `blocks`
+/// accepts within `max_distance`. Cells are closed boxes, so a ray running
+/// along a face or passing through an edge or corner touches the cells on
+/// both sides.
+#[cfg(feature = "alloc")]
+pub(crate) fn grid_walk<N: FloatingPhysicsScalar, const D: usize>(
+    origin: &[N],
+    cell_size: N,
+    dimensions: [usize; D],
+    point: &[N],
+    direction: &[N],
+    max_distance: N,
+    mut blocks: impl FnMut(&GridStep<N, D>) -> bool,
+) -> Option<GridStep<N, D>> {
+    let max: [N; D] =
+        core::array::from_fn(|i| origin[i] + N::from_usize(dimensions[i]).unwrap() * cell_size);
+    let clip = slab_clip(origin, &max, point, direction, max_distance)?;
+
+    let mut cell = [0isize; D];
+    let mut step = [0isize; D];
+    // The offset to the other cell sharing the face the ray is on, if it is
+    // on one. Along axes it does not move on this holds for the whole walk.
+    let mut back = [0isize; D];
+    let mut next = [Bounded::max_value(); D];
+    let mut delta = [Bounded::max_value(); D];
+    for i in 0..D {
+        let position = point[i] + direction[i] * clip.enter;
+        let index = Float::floor((position - origin[i]) / cell_size);
+        let last = dimensions[i].saturating_sub(1);
+        cell[i] = n_max(index, N::zero()).to_usize().unwrap_or(last).min(last) as isize;
+        let on_face = index > N::zero()
+            && index.to_usize().is_some_and(|index| index <= last)
+            && position == origin[i] + index * cell_size;
+        if is_zero(direction[i]) {
+            back[i] = if on_face { -1 } else { 0 };
+            continue;
+        }
+        delta[i] = cell_size / Float::abs(direction[i]);
+        if direction[i] > N::zero() {
+            step[i] = 1;
+        } else {
+            step[i] = -1;
+            if on_face {
+                cell[i] -= 1;
+            }
+        }
+        if on_face {
+            back[i] = -step[i];
+        }
+        let boundary = origin[i] + N::from_isize(cell[i] + step[i].max(0)).unwrap() * cell_size;
+        next[i] = clip.enter + (boundary - position) / direction[i];
+    }
+
+    // A ray can come into the grid through an edge or corner, entering
+    // through more than the one face the clip reports.
+    let mut distance = clip.enter;
+    let mut entered: [bool; D] = core::array::from_fn(|i| {
+        let near = if direction[i] > N::zero() {
+            origin[i]
+        } else {
+            max[i]
+        };
+        clip.axis.is_some()
+            && !is_zero(direction[i])
+            && (near - point[i]) / direction[i] == clip.enter
+    });
+    let mut crossed = [false; D];
+    loop {
+        // Every combination of stepping back over the shared faces touches
+        // the ray here, except stepping back over all the crossed ones, which
+        // gives cells touched earlier.
+        let flips: usize = (0..D).filter(|&i| back[i] != 0).map(|i| 1 << i).sum();
+        let crossed_mask: usize = (0..D).filter(|&i| crossed[i]).map(|i| 1 << i).sum();
+        for mask in (0..1usize << D).filter(|mask| mask & !flips == 0) {
+            if crossed_mask != 0 && mask & crossed_mask == crossed_mask {
+                continue;
+            }
+            let mut touched = GridStep {
+                cell: [0; D],
+                distance,
+                entered,
+            };
+            let inside = (0..D).all(|i| {
+                let flipped = mask & (1 << i) != 0;
+                let index = cell[i] + if flipped { back[i] } else { 0 };
+                touched.entered[i] &= !flipped;
+                touched.cell[i] = index as usize;
+                index >= 0 && (index as usize) < dimensions[i]
+            });
+            if inside && blocks(&touched) {
+                return Some(touched);
+            }
+        }
+        if (0..D).any(|i| cell[i] < 0 || cell[i] as usize >= dimensions[i]) {
+            return None;
+        }
+
+        distance = next.iter().copied().fold(Bounded::max_value(), n_min);
+        if distance > clip.exit {
+            return None;
+        }
+        for i in 0..D {
+            crossed[i] = next[i] == distance;
+            entered[i] = crossed[i];
+            if crossed[i] {
+                cell[i] += step[i];
+                next[i] += delta[i];
+                back[i] = -step[i];
+            } else if !is_zero(direction[i]) {
+                back[i] = 0;
+            }
+        }
+    }
+}
+
+/// Fisher-Yates with a fixed xorshift seed, so results stay reproducible.
+#[cfg(feature = "alloc")]
+pub(crate) fn shuffle<T>(items: &mut [T]) {
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    for i in (1..items.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        items.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+}
+
+/// A circle or sphere, so both can be built by the same Welzl's algorithm.
+pub(crate) trait Ball<N: FloatingPhysicsScalar>: Copy {
+    type Point: Copy;
+    /// The most points that can sit on the surface of the smallest ball.
+    const MAX_BOUNDARY: usize;
+
+    fn radius(&self) -> N;
+    fn with_radius(&self, radius: N) -> Self;
+    fn distance(&self, point: &Self::Point) -> N;
+    /// The smallest ball with every point of `boundary` on its surface, or
+    /// radius zero at the origin for none.
+    fn through(boundary: &[Self::Point]) -> Self;
+
+    /// Containment with some slack, so points on the boundary of the ball
+    /// being built do not force a rebuild.
+    fn holds(&self, point: &Self::Point) -> bool {
+        let slack = N::epsilon() * N::from_f64(64.0).unwrap();
+        self.distance(point) <= self.radius() * (N::one() + slack) + slack
+    }
+}
+
+/// The smallest ball holding every point, by Welzl's algorithm. Takes
+/// expected linear time when the points are in random order.
+pub(crate) fn smallest_ball<N: FloatingPhysicsScalar, B: Ball<N>>(points: &[B::Point]) -> B {
+    let ball: B = welzl(points, &mut arrayvec::ArrayVec::<[B::Point; 4]>::new());
+    // Grow over any rounding so every point is inside.
+    let radius = points
+        .iter()
+        .map(|p| ball.distance(p))
+        .fold(ball.radius(), n_max);
+    ball.with_radius(radius)
+}
+
+/// The smallest ball holding `points` with every point of `boundary` on its
+/// surface.
+fn welzl<N: FloatingPhysicsScalar, B: Ball<N>>(
+    points: &[B::Point],
+    boundary: &mut arrayvec::ArrayVec<[B::Point; 4]>,
+) -> B {
+    if boundary.len() == B::MAX_BOUNDARY {
+        return B::through(boundary);
+    }
+    let (mut ball, skip) = match (boundary.is_empty(), points.first()) {
+        (true, Some(first)) => (B::through(&[*first]), 1),
+        _ => (B::through(boundary), 0),
+    };
+    for i in skip..points.len() {
+        if !ball.holds(&points[i]) {
+            boundary.push(points[i]);
+            ball = welzl(&points[..i], boundary);
+            boundary.pop();
+        }
+    }
+    ball
+}
+
 #[inline(always)]
 pub(crate) fn min_component<'a, N: PhysicsScalar, S: Storage<N, D> + 'a, D: Dim>(
     v: &'a Vector<N, D, S>,
@@ -158,10 +451,71 @@ pub(crate) fn closest_to_segment<N: PhysicsScalar>(
     point: &Vector3<N>,
 ) -> Vector3<N> {
     let ab: Vector3<N> = end - start;
-    let t: N = (point - start).dot(&ab) / ab.dot(&ab);
+    let length = ab.dot(&ab);
+    if length == N::zero() {
+        return *start;
+    }
+    let t: N = (point - start).dot(&ab) / length;
     start + ab * n_min(n_max(t, N::zero()), N::one())
 }
 
+/// A unit vector perpendicular to `axis`, or the x axis if `axis` is zero.
+pub(crate) fn perpendicular<N: FloatingPhysicsScalar>(axis: &Vector3<N>) -> Vector3<N> {
+    let (index, _) = min_component(&axis.abs());
+    let perpendicular = axis.cross(&Vector3::ith(index, N::one()));
+    if is_zero(perpendicular.magnitude_squared()) {
+        Vector3::x()
+    } else {
+        perpendicular.normalize()
+    }
+}
+
+/// Returns the closest points between the segments `start1..end1` and
+/// `start2..end2`, in that order. For parallel segments any pair at the
+/// shortest distance may be returned.
+pub(crate) fn closest_between_segments<N: FloatingPhysicsScalar>(
+    start1: &Vector3<N>,
+    end1: &Vector3<N>,
+    start2: &Vector3<N>,
+    end2: &Vector3<N>,
+) -> (Vector3<N>, Vector3<N>) {
+    let d1 = end1 - start1;
+    let d2 = end2 - start2;
+    let r = start1 - start2;
+    let a = d1.dot(&d1);
+    let e = d2.dot(&d2);
+    let f = d2.dot(&r);
+    let unit = |t: N| num_traits::clamp(t, N::zero(), N::one());
+
+    let (s, t) = if is_zero(a) && is_zero(e) {
+        (N::zero(), N::zero())
+    } else if is_zero(a) {
+        (N::zero(), unit(f / e))
+    } else {
+        let c = d1.dot(&r);
+        if is_zero(e) {
+            (unit(-c / a), N::zero())
+        } else {
+            let b = d1.dot(&d2);
+            let denom = a * e - b * b;
+            let s = if is_zero(denom / (a * e)) {
+                N::zero()
+            } else {
+                unit((b * f - c * e) / denom)
+            };
+            let t = (b * s + f) / e;
+            if t < N::zero() {
+                (unit(-c / a), N::zero())
+            } else if t > N::one() {
+                (unit((b - c) / a), N::one())
+            } else {
+                (s, t)
+            }
+        }
+    };
+    (start1 + d1 * s, start2 + d2 * t)
+}
+
 #[cfg(feature = "serde-serialize")]
 use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Copy, PartialEq)]
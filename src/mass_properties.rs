@@ -0,0 +1,24 @@
+use super::*;
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+/// Mass, center of mass and inertia of a shape with uniform density.
+///
+/// `V` is the vector type of the shape's space and `I` its inertia type: a
+/// tensor (`Matrix3`) about the center of mass in 3D and a scalar moment about
+/// the center of mass in 2D.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct MassData<V, I, N: PhysicsScalar> {
+    pub mass: N,
+    pub center_of_mass: V,
+    pub inertia: I,
+}
+
+pub trait MassProperties<N: PhysicsScalar> {
+    type Vector;
+    type Inertia;
+
+    fn mass_properties(&self, density: N) -> MassData<Self::Vector, Self::Inertia, N>;
+}
@@ -0,0 +1,11 @@
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+/// The closest point on a shape's boundary to a query point.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub struct PointProjection<T> {
+    pub point: T,
+    /// Whether the query point was inside the shape.
+    pub is_inside: bool,
+}
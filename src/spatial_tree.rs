@@ -0,0 +1,279 @@
+//! The tree behind `Octree` and `Quadtree`. Each node splits its box into
+//! equal children at its centre, and an item is kept in the deepest node whose
+//! box holds its whole bounding box, so an item straddling a split stays in the
+//! parent and every node's box bounds everything below it.
+
+use super::*;
+
+#[cfg(feature = "serde-serialize")]
+use serde::{Deserialize, Serialize};
+
+/// The bounding boxes the tree is built from.
+pub(crate) trait TreeBounds<N: FloatingPhysicsScalar>: Copy {
+    type Vector;
+
+    /// The number of children of a split node.
+    const CHILDREN: usize;
+
+    /// The `index`th equal part of the box, taking the upper half along axis
+    /// `k` when bit `k` of `index` is set.
+    fn child(&self, index: usize) -> Self;
+    fn contains(&self, other: &Self) -> bool;
+    fn union(&self, other: &Self) -> Self;
+    fn empty() -> Self;
+    /// The distance from `point` to the box, zero inside it.
+    fn distance_to(&self, point: &Self::Vector) -> N;
+}
+
+impl<N: FloatingPhysicsScalar> TreeBounds<N> for collision3d::AxisAlignedBoundingBox<N> {
+    type Vector = Vector3<N>;
+
+    const CHILDREN: usize = 8;
+
+    fn child(&self, index: usize) -> Self {
+        let (min, max) = self.min_max();
+        let center = collision3d::Shape3D::center(self);
+        let upper = |axis: usize| index & (1 << axis) != 0;
+        collision3d::AxisAlignedBoundingBox {
+            start: Vector3::from_fn(|i, _| if upper(i) { center[i] } else { min[i] }),
+            end: Vector3::from_fn(|i, _| if upper(i) { max[i] } else { center[i] }),
+        }
+    }
+    fn contains(&self, other: &Self) -> bool {
+        self.contains_aabb(other)
+    }
+    fn union(&self, other: &Self) -> Self {
+        *self + *other
+    }
+    fn empty() -> Self {
+        Self::empty()
+    }
+    fn distance_to(&self, point: &Vector3<N>) -> N {
+        (self.closest_point(point) - point).magnitude()
+    }
+}
+
+impl<N: FloatingPhysicsScalar> TreeBounds<N> for collision2d::AxisAlignedBoundingBox<N> {
+    type Vector = Vector2<N>;
+
+    const CHILDREN: usize = 4;
+
+    fn child(&self, index: usize) -> Self {
+        let (min, max) = self.min_max();
+        let center = collision2d::Shape2D::center(self);
+        let upper = |axis: usize| index & (1 << axis) != 0;
+        collision2d::AxisAlignedBoundingBox {
+            start: Vector2::from_fn(|i, _| if upper(i) { center[i] } else { min[i] }),
+            end: Vector2::from_fn(|i, _| if upper(i) { max[i] } else { center[i] }),
+        }
+    }
+    fn contains(&self, other: &Self) -> bool {
+        self.contains_aabb(other)
+    }
+    fn union(&self, other: &Self) -> Self {
+        *self + *other
+    }
+    fn empty() -> Self {
+        Self::empty()
+    }
+    fn distance_to(&self, point: &Vector2<N>) -> N {
+        (self.closest_point(point) - point).magnitude()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+struct Node<B> {
+    bounds: B,
+    /// Indices of the items kept in this node.
+    items: Vec<usize>,
+    children: Vec<Node<B>>,
+}
+
+impl<B> Node<B> {
+    fn new(bounds: B) -> Self {
+        Node {
+            bounds,
+            items: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+}
+
+/// Items with their bounding boxes, and the nodes indexing them.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+pub(crate) struct Tree<B, T> {
+    root: Node<B>,
+    bounds: Vec<B>,
+    items: Vec<T>,
+    max_depth: usize,
+    leaf_capacity: usize,
+}
+
+impl<B, T> Tree<B, T> {
+    pub(crate) fn new(bounds: B, max_depth: usize, leaf_capacity: usize) -> Self {
+        Tree {
+            root: Node::new(bounds),
+            bounds: Vec::new(),
+            items: Vec::new(),
+            max_depth,
+            leaf_capacity,
+        }
+    }
+    pub(crate) fn items(&self) -> &[T] {
+        &self.items
+    }
+    pub(crate) fn bounds(&self) -> &B {
+        &self.root.bounds
+    }
+    /// Builds a tree around all of `items`, bounded by `bounds` of each.
+    pub(crate) fn build<N, I, F>(
+        items: I,
+        max_depth: usize,
+        leaf_capacity: usize,
+        bounds: F,
+    ) -> Self
+    where
+        N: FloatingPhysicsScalar,
+        B: TreeBounds<N>,
+        I: IntoIterator<Item = T>,
+        F: Fn(&T) -> B,
+    {
+        let items: Vec<T> = items.into_iter().collect();
+        let boxes: Vec<B> = items.iter().map(&bounds).collect();
+        let root = match boxes.split_first() {
+            Some((first, rest)) => rest.iter().fold(*first, |a, b| a.union(b)),
+            None => B::empty(),
+        };
+        let mut tree = Tree::new(root, max_depth, leaf_capacity);
+        for (item, bounds) in items.into_iter().zip(boxes) {
+            tree.insert(item, bounds);
+        }
+        tree
+    }
+    /// Adds an item and returns its index. Items outside the tree's bounds are
+    /// kept at the root.
+    pub(crate) fn insert<N>(&mut self, item: T, bounds: B) -> usize
+    where
+        N: FloatingPhysicsScalar,
+        B: TreeBounds<N>,
+    {
+        let index = self.items.len();
+        self.items.push(item);
+        self.bounds.push(bounds);
+        let (max_depth, leaf_capacity) = (self.max_depth, self.leaf_capacity);
+        insert(
+            &mut self.root,
+            index,
+            &self.bounds,
+            0,
+            max_depth,
+            leaf_capacity,
+        );
+        index
+    }
+    /// The indices of the items whose boxes pass `test`, in ascending order.
+    /// A node is skipped when its box fails `test`, so `test` must pass for
+    /// any box that holds one that passes.
+    pub(crate) fn query<N, F>(&self, test: F) -> Vec<usize>
+    where
+        N: FloatingPhysicsScalar,
+        B: TreeBounds<N>,
+        F: Fn(&B) -> bool,
+    {
+        let mut found = Vec::new();
+        // The root is always searched as it also holds items outside its box.
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            found.extend(
+                node.items
+                    .iter()
+                    .copied()
+                    .filter(|&i| test(&self.bounds[i])),
+            );
+            stack.extend(node.children.iter().filter(|child| test(&child.bounds)));
+        }
+        found.sort_unstable();
+        found
+    }
+    /// The item closest to `point` by `distance`, which must never be less
+    /// than the distance to the item's box.
+    pub(crate) fn nearest<N, F>(&self, point: &B::Vector, distance: F) -> Option<(usize, N)>
+    where
+        N: FloatingPhysicsScalar,
+        B: TreeBounds<N>,
+        F: Fn(usize) -> N,
+    {
+        let mut best = None;
+        nearest(&self.root, point, &self.bounds, &distance, &mut best);
+        best
+    }
+}
+
+fn insert<N, B>(
+    node: &mut Node<B>,
+    index: usize,
+    bounds: &[B],
+    depth: usize,
+    max_depth: usize,
+    leaf_capacity: usize,
+) where
+    N: FloatingPhysicsScalar,
+    B: TreeBounds<N>,
+{
+    if !node.children.is_empty() {
+        if let Some(child) = node
+            .children
+            .iter_mut()
+            .find(|child| child.bounds.contains(&bounds[index]))
+        {
+            return insert(child, index, bounds, depth + 1, max_depth, leaf_capacity);
+        }
+        node.items.push(index);
+        return;
+    }
+
+    node.items.push(index);
+    if node.items.len() > leaf_capacity && depth < max_depth {
+        node.children = (0..B::CHILDREN)
+            .map(|i| Node::new(node.bounds.child(i)))
+            .collect();
+        for index in core::mem::take(&mut node.items) {
+            insert(node, index, bounds, depth, max_depth, leaf_capacity);
+        }
+    }
+}
+
+fn nearest<N, B, F>(
+    node: &Node<B>,
+    point: &B::Vector,
+    bounds: &[B],
+    distance: &F,
+    best: &mut Option<(usize, N)>,
+) where
+    N: FloatingPhysicsScalar,
+    B: TreeBounds<N>,
+    F: Fn(usize) -> N,
+{
+    let beats = |d: N, best: &Option<(usize, N)>| best.is_none_or(|(_, b)| d < b);
+    for &i in &node.items {
+        if beats(bounds[i].distance_to(point), best) {
+            let d = distance(i);
+            if beats(d, best) {
+                *best = Some((i, d));
+            }
+        }
+    }
+    let mut children: Vec<_> = node
+        .children
+        .iter()
+        .map(|child| (child.bounds.distance_to(point), child))
+        .collect();
+    children.sort_unstable_by(|a, b| n_ordering(a.0, b.0));
+    for (d, child) in children {
+        if beats(d, best) {
+            nearest(child, point, bounds, distance, best);
+        }
+    }
+}
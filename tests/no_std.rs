@@ -0,0 +1,51 @@
+//! Exercises the heap-free core of the crate. Run it against the `no_std`
+//! build with `cargo test --no-default-features --features libm`.
+
+use nalgebra::{Vector2, Vector3};
+use simple_collision::collision2d::{Circle, Collider2D, AABB2D};
+use simple_collision::collision3d::*;
+use simple_collision::MassProperties;
+
+#[test]
+fn collisions_3d_test() {
+    let aabb = Collider3D::AABB(AxisAlignedBoundingBox {
+        start: Vector3::new(-1f32, -1f32, -1f32),
+        end: Vector3::new(1f32, 1f32, 1f32),
+    });
+    let sphere = Collider3D::Sphere(Sphere {
+        center: Vector3::new(1.5f32, 0f32, 0f32),
+        radius: 1f32,
+    });
+    let plane = Collider3D::Plane(Plane::from_point(
+        &Vector3::y(),
+        &Vector3::new(0f32, -0.5f32, 0f32),
+    ));
+
+    let res = sphere.get_collision(&aabb).unwrap();
+    assert_eq!(res.normal, Vector3::x());
+    assert!((res.penetration - 0.5).abs() < 1e-6);
+
+    let res = aabb.get_collision(&plane).unwrap();
+    assert_eq!(res.normal, Vector3::y());
+    assert!((res.penetration - 0.5).abs() < 1e-6);
+
+    assert!(sphere.contains_point(&Vector3::new(2f32, 0f32, 0f32)));
+    assert!((aabb.mass_properties(2f32).mass - 16f32).abs() < 1e-6);
+}
+
+#[test]
+fn collisions_2d_test() {
+    let aabb = Collider2D::AABB(AABB2D {
+        start: Vector2::new(0f64, 0f64),
+        end: Vector2::new(2f64, 1f64),
+    });
+    let circle = Collider2D::Circle(Circle {
+        center: Vector2::new(1f64, 1.5f64),
+        radius: 1f64,
+    });
+
+    let res = circle.get_collision(&aabb).unwrap();
+    assert_eq!(res.normal, Vector2::y());
+    assert!((res.penetration - 0.5).abs() < 1e-12);
+    assert!(!circle.project_point(&Vector2::new(1f64, 3f64)).is_inside);
+}
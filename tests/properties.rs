@@ -0,0 +1,1507 @@
+//! Property tests for the pairwise collision routines. Every routine is checked
+//! for translation invariance and for resolving its own overlap, same-type
+//! routines for symmetry, and the results are compared with a brute-force
+//! oracle that measures the overlap of the two support functions over many
+//! sampled directions.
+
+use nalgebra::allocator::Allocator;
+use nalgebra::{DefaultAllocator, DimName, Isometry3, Vector2, Vector3, VectorN};
+use proptest::prelude::*;
+use simple_collision::collision2d::{self, Circle, Collider2D, Shape2D};
+use simple_collision::collision3d::*;
+use simple_collision::{Axis, CollisionResolution, PointProjection};
+use std::sync::OnceLock;
+
+type Resolution3 = CollisionResolution<Vector3<f64>, f64>;
+type Resolution2 = CollisionResolution<Vector2<f64>, f64>;
+type AABB2D = collision2d::AxisAlignedBoundingBox<f64>;
+
+/// Slack for comparisons against the oracle, which can overestimate the depth
+/// when its search stops short of the best direction.
+const ORACLE_TOLERANCE: f64 = 0.01;
+/// Slack for comparisons between two evaluations of the same routine.
+const TOLERANCE: f64 = 1e-6;
+const DIRECTIONS: usize = 500;
+/// Cases per property, kept low because the oracle is slow in debug builds.
+const CASES: u32 = 64;
+/// How many of the best sampled directions the 3D oracle refines.
+const REFINED: usize = 4;
+/// Upper bound on the moves of one refinement, which can otherwise creep
+/// along a flat ridge in ever smaller improvements.
+const REFINE_STEPS: usize = 200;
+
+fn coordinate() -> impl Strategy<Value = f64> {
+    -4.0..4.0f64
+}
+
+fn extent() -> impl Strategy<Value = f64> {
+    0.1..2.0f64
+}
+
+fn vector3() -> impl Strategy<Value = Vector3<f64>> {
+    (coordinate(), coordinate(), coordinate()).prop_map(|(x, y, z)| Vector3::new(x, y, z))
+}
+
+fn vector2() -> impl Strategy<Value = Vector2<f64>> {
+    (coordinate(), coordinate()).prop_map(|(x, y)| Vector2::new(x, y))
+}
+
+fn unit3() -> impl Strategy<Value = Vector3<f64>> {
+    vector3()
+        .prop_filter("direction too short", |v| v.magnitude() > 0.1)
+        .prop_map(|v| v.normalize())
+}
+
+fn sphere() -> impl Strategy<Value = Sphere<f64>> {
+    (vector3(), extent()).prop_map(|(center, radius)| Sphere { center, radius })
+}
+
+fn aabb() -> impl Strategy<Value = AxisAlignedBoundingBox<f64>> {
+    (vector3(), extent(), extent(), extent()).prop_map(|(center, x, y, z)| {
+        let half = Vector3::new(x, y, z);
+        AxisAlignedBoundingBox {
+            start: center - half,
+            end: center + half,
+        }
+    })
+}
+
+fn capsule() -> impl Strategy<Value = Capsule<f64>> {
+    (vector3(), vector3(), extent()).prop_map(|(start, offset, radius)| Capsule {
+        start,
+        end: start + offset * 0.5,
+        radius,
+    })
+}
+
+fn cylinder() -> impl Strategy<Value = Cylinder<f64>> {
+    (
+        vector3(),
+        extent(),
+        extent(),
+        prop_oneof![Just(Axis::X), Just(Axis::Y), Just(Axis::Z)],
+    )
+        .prop_map(|(center, half_height, radius, axis)| Cylinder {
+            center,
+            half_height,
+            radius,
+            axis,
+        })
+}
+
+fn cone() -> impl Strategy<Value = Cone<f64>> {
+    (vector3(), unit3(), extent(), extent())
+        .prop_map(|(apex, axis, height, radius)| Cone::new(&apex, &axis, height * 2.0, radius))
+}
+
+fn triangle() -> impl Strategy<Value = Triangle<f64>> {
+    (vector3(), vector3(), vector3())
+        .prop_map(|(point1, point2, point3)| Triangle {
+            point1,
+            point2,
+            point3,
+        })
+        .prop_filter("degenerate triangle", |t| t.area() > 0.5)
+}
+
+/// A triangle squashed onto a line or a point.
+fn degenerate_triangle() -> impl Strategy<Value = Triangle<f64>> {
+    (
+        vector3(),
+        vector3(),
+        prop_oneof![Just(0.0), Just(1.0), -1.0..2.0f64],
+        any::<bool>(),
+    )
+        .prop_map(|(point1, point2, t, point)| {
+            let point2 = if point { point1 } else { point2 };
+            Triangle {
+                point1,
+                point2,
+                point3: point1 + (point2 - point1) * t,
+            }
+        })
+}
+
+fn plane() -> impl Strategy<Value = Plane<f64>> {
+    (unit3(), vector3()).prop_map(|(normal, point)| Plane::from_point(&normal, &point))
+}
+
+fn circle() -> impl Strategy<Value = Circle<f64>> {
+    (vector2(), extent()).prop_map(|(center, radius)| Circle { center, radius })
+}
+
+fn aabb2d() -> impl Strategy<Value = AABB2D> {
+    (vector2(), extent(), extent()).prop_map(|(center, x, y)| {
+        let half = Vector2::new(x, y);
+        AABB2D {
+            start: center - half,
+            end: center + half,
+        }
+    })
+}
+
+/// A 4x4x4 grid of unit voxels around the origin with random cells filled.
+#[cfg(feature = "alloc")]
+fn voxel_grid() -> impl Strategy<Value = VoxelGrid<f64>> {
+    any::<u64>().prop_map(|bits| {
+        VoxelGrid::from_fn(&Vector3::from_element(-2.0), 1.0, [4, 4, 4], |[x, y, z]| {
+            bits & (1 << (x + 4 * y + 16 * z)) != 0
+        })
+    })
+}
+
+/// A coordinate that is often exactly on a grid line of the unit cell grids.
+fn grid_coordinate() -> impl Strategy<Value = f64> {
+    prop_oneof![coordinate(), (-4..=4).prop_map(f64::from)]
+}
+
+/// A ray direction component that is often zero, so the ray runs along the
+/// grid.
+fn grid_direction() -> impl Strategy<Value = f64> {
+    prop_oneof![Just(0.0), -1.0..1.0f64]
+}
+
+/// A coordinate that is often exactly on a split of an octree over -4 to 4,
+/// down to four levels deep.
+fn split_coordinate() -> impl Strategy<Value = f64> {
+    prop_oneof![
+        coordinate(),
+        (0..=16).prop_map(|k| -4.0 + 0.5 * f64::from(k))
+    ]
+}
+
+/// A box that often lies on, ends on or straddles the octree's splits,
+/// including flat boxes inside a split plane.
+fn split_aabb() -> impl Strategy<Value = AxisAlignedBoundingBox<f64>> {
+    let half = || prop_oneof![Just(0.0), Just(0.5), 0.0..1.0f64];
+    (
+        (split_coordinate(), split_coordinate(), split_coordinate()),
+        (half(), half(), half()),
+    )
+        .prop_map(|((x, y, z), (hx, hy, hz))| {
+            let center = Vector3::new(x, y, z);
+            let half = Vector3::new(hx, hy, hz);
+            AxisAlignedBoundingBox {
+                start: center - half,
+                end: center + half,
+            }
+        })
+}
+
+fn grid_ray() -> impl Strategy<Value = Ray<f64>> {
+    (
+        (grid_coordinate(), grid_coordinate(), grid_coordinate()),
+        (grid_direction(), grid_direction(), grid_direction()),
+    )
+        .prop_filter_map("direction too short", |((x, y, z), (dx, dy, dz))| {
+            let direction = Vector3::new(dx, dy, dz);
+            (direction.magnitude() > 0.1).then(|| Ray::new(&Vector3::new(x, y, z), &direction))
+        })
+}
+
+/// A point and direction for a ray through the tile maps.
+fn grid_ray2() -> impl Strategy<Value = (Vector2<f64>, Vector2<f64>)> {
+    (
+        (grid_coordinate(), grid_coordinate()),
+        (grid_direction(), grid_direction()),
+    )
+        .prop_filter_map("direction too short", |((x, y), (dx, dy))| {
+            let direction = Vector2::new(dx, dy);
+            (direction.magnitude() > 0.1).then(|| (Vector2::new(x, y), direction))
+        })
+}
+
+/// A 4x4 map of unit tiles around the origin with random solid tiles and
+/// one-way platforms.
+#[cfg(feature = "alloc")]
+fn tile_map() -> impl Strategy<Value = collision2d::TileMap2D<f64>> {
+    use collision2d::{Tile, TileMap2D};
+    any::<u32>().prop_map(|bits| {
+        TileMap2D::from_fn(&Vector2::from_element(-2.0), 1.0, [4, 4], |[x, y]| {
+            match (bits >> (2 * (x + 4 * y))) & 3 {
+                0 | 1 => Tile::Empty,
+                2 => Tile::Solid,
+                _ => Tile::OneWay,
+            }
+        })
+    })
+}
+
+fn collider3d() -> impl Strategy<Value = Collider3D<f64>> {
+    prop_oneof![
+        aabb().prop_map(Collider3D::AABB),
+        sphere().prop_map(Collider3D::Sphere),
+    ]
+}
+
+/// Colliders that can be swept with `Collider3D::shape_cast`.
+fn caster() -> impl Strategy<Value = Collider3D<f64>> {
+    prop_oneof![
+        aabb().prop_map(Collider3D::AABB),
+        sphere().prop_map(Collider3D::Sphere),
+        capsule().prop_map(Collider3D::Capsule),
+    ]
+}
+
+fn cast_target() -> impl Strategy<Value = Collider3D<f64>> {
+    prop_oneof![
+        aabb().prop_map(Collider3D::AABB),
+        sphere().prop_map(Collider3D::Sphere),
+        capsule().prop_map(Collider3D::Capsule),
+        triangle().prop_map(Collider3D::Triangle),
+        plane().prop_map(Collider3D::Plane),
+    ]
+}
+
+/// A pose a few units from the origin, turned by up to about half a turn.
+fn pose() -> impl Strategy<Value = Isometry3<f64>> {
+    (vector3(), vector3()).prop_map(|(translation, axis)| Isometry3::new(translation, axis * 0.5))
+}
+
+fn motion() -> impl Strategy<Value = Motion<f64>> {
+    (pose(), pose()).prop_map(|(start, end)| Motion::new(&start, &end))
+}
+
+fn collider2d() -> impl Strategy<Value = Collider2D<f64>> {
+    prop_oneof![
+        aabb2d().prop_map(Collider2D::AABB),
+        circle().prop_map(Collider2D::Circle),
+    ]
+}
+
+/// The furthest extent of a shape along a direction.
+trait Support<V> {
+    fn support(&self, direction: &V) -> f64;
+}
+
+impl Support<Vector3<f64>> for Sphere<f64> {
+    fn support(&self, direction: &Vector3<f64>) -> f64 {
+        self.center.dot(direction) + self.radius * direction.magnitude()
+    }
+}
+
+impl Support<Vector3<f64>> for AxisAlignedBoundingBox<f64> {
+    fn support(&self, direction: &Vector3<f64>) -> f64 {
+        let half = (self.end - self.start) * 0.5;
+        (self.start + half).dot(direction) + half.dot(&direction.abs())
+    }
+}
+
+impl Support<Vector3<f64>> for Capsule<f64> {
+    fn support(&self, direction: &Vector3<f64>) -> f64 {
+        f64::max(self.start.dot(direction), self.end.dot(direction))
+            + self.radius * direction.magnitude()
+    }
+}
+
+impl Support<Vector3<f64>> for Cylinder<f64> {
+    fn support(&self, direction: &Vector3<f64>) -> f64 {
+        let axis = self.axis.to_num();
+        let mut radial = *direction;
+        radial[axis] = 0.0;
+        self.center.dot(direction)
+            + self.half_height * direction[axis].abs()
+            + self.radius * radial.magnitude()
+    }
+}
+
+impl Support<Vector3<f64>> for Cone<f64> {
+    fn support(&self, direction: &Vector3<f64>) -> f64 {
+        self.support_point(direction).dot(direction)
+    }
+}
+
+impl Support<Vector3<f64>> for Triangle<f64> {
+    fn support(&self, direction: &Vector3<f64>) -> f64 {
+        [self.point1, self.point2, self.point3]
+            .iter()
+            .map(|p| p.dot(direction))
+            .fold(f64::MIN, f64::max)
+    }
+}
+
+impl Support<Vector2<f64>> for [Vector2<f64>; 3] {
+    fn support(&self, direction: &Vector2<f64>) -> f64 {
+        self.iter()
+            .map(|p| p.dot(direction))
+            .fold(f64::MIN, f64::max)
+    }
+}
+
+impl Support<Vector2<f64>> for Circle<f64> {
+    fn support(&self, direction: &Vector2<f64>) -> f64 {
+        self.center.dot(direction) + self.radius * direction.magnitude()
+    }
+}
+
+impl Support<Vector2<f64>> for AABB2D {
+    fn support(&self, direction: &Vector2<f64>) -> f64 {
+        let half = (self.end - self.start) * 0.5;
+        (self.start + half).dot(direction) + half.dot(&direction.abs())
+    }
+}
+
+/// Directions spread evenly over the unit sphere, plus the coordinate axes so
+/// box faces are measured exactly.
+fn directions3() -> &'static [Vector3<f64>] {
+    static DIRECTIONS3: OnceLock<Vec<Vector3<f64>>> = OnceLock::new();
+    DIRECTIONS3.get_or_init(|| {
+        let golden = core::f64::consts::PI * (3.0 - 5f64.sqrt());
+        let mut directions: Vec<_> = (0..DIRECTIONS)
+            .map(|i| {
+                let y = 1.0 - 2.0 * (i as f64 + 0.5) / DIRECTIONS as f64;
+                let r = (1.0 - y * y).sqrt();
+                let theta = golden * i as f64;
+                Vector3::new(r * theta.cos(), y, r * theta.sin())
+            })
+            .collect();
+        for i in 0..3 {
+            directions.push(Vector3::ith(i, 1.0));
+            directions.push(Vector3::ith(i, -1.0));
+        }
+        directions
+    })
+}
+
+fn directions2() -> &'static [Vector2<f64>] {
+    static DIRECTIONS2: OnceLock<Vec<Vector2<f64>>> = OnceLock::new();
+    DIRECTIONS2.get_or_init(|| {
+        (0..DIRECTIONS)
+            .map(|i| {
+                let theta = core::f64::consts::PI * 2.0 * i as f64 / DIRECTIONS as f64;
+                Vector2::new(theta.cos(), theta.sin())
+            })
+            .collect()
+    })
+}
+
+/// How far `a` has to move to leave `b`, minimised over the sampled directions
+/// and then refined around the best few of them. Positive when the convex
+/// shapes overlap, negative when they are apart.
+fn oracle_depth3(a: &dyn Support<Vector3<f64>>, b: &dyn Support<Vector3<f64>>) -> f64 {
+    let overlap = |d: &Vector3<f64>| a.support(&-d) + b.support(d);
+    let mut sampled: Vec<_> = directions3().iter().map(|d| (overlap(d), *d)).collect();
+    sampled.sort_unstable_by(|x, y| x.0.partial_cmp(&y.0).unwrap());
+    sampled
+        .iter()
+        .take(REFINED)
+        .map(|&(value, d)| refine(value, d, overlap))
+        .fold(f64::MAX, f64::min)
+}
+
+/// A pattern search over the unit sphere that moves to the best neighbouring
+/// direction, doubling the step after a move so it can follow a narrow valley
+/// without crawling. When nothing improves the step is halved and the
+/// neighbours are turned, since a fixed pattern can straddle a ridge where
+/// the support of a flat shape has a kink.
+fn refine<F: Fn(&Vector3<f64>) -> f64>(mut value: f64, mut d: Vector3<f64>, overlap: F) -> f64 {
+    let mut step: f64 = 0.05;
+    let mut turn = 0.0;
+    for _ in 0..REFINE_STEPS {
+        if step < 1e-9 {
+            break;
+        }
+        let u = d.cross(&Vector3::ith(d.iamin(), 1.0)).normalize();
+        let v = d.cross(&u);
+        let next = (0..8)
+            .map(|i| {
+                let angle = core::f64::consts::FRAC_PI_4 * i as f64 + turn;
+                let candidate = (d + (u * angle.cos() + v * angle.sin()) * step).normalize();
+                (overlap(&candidate), candidate)
+            })
+            .filter(|(candidate, _)| *candidate < value)
+            .min_by(|x, y| x.0.partial_cmp(&y.0).unwrap());
+        match next {
+            Some((candidate, direction)) => {
+                value = candidate;
+                d = direction;
+                step = (step * 2.0).min(0.5);
+            }
+            None => {
+                step *= 0.5;
+                turn += 0.3;
+            }
+        }
+    }
+    value
+}
+
+fn oracle_depth2(a: &dyn Support<Vector2<f64>>, b: &dyn Support<Vector2<f64>>) -> f64 {
+    directions2()
+        .iter()
+        .map(|d| a.support(&-d) + b.support(d))
+        .fold(f64::MAX, f64::min)
+}
+
+/// The largest absolute component, for comparing vectors of either dimension.
+trait MaxAbs {
+    fn max_abs(&self) -> f64;
+}
+
+impl MaxAbs for Vector3<f64> {
+    fn max_abs(&self) -> f64 {
+        self.amax()
+    }
+}
+
+impl MaxAbs for Vector2<f64> {
+    fn max_abs(&self) -> f64 {
+        self.amax()
+    }
+}
+
+/// A routine that computes the penetration depth exactly must agree with the
+/// oracle whenever the shapes are clearly apart or clearly overlapping.
+fn check_depth<T>(
+    result: Option<CollisionResolution<T, f64>>,
+    depth: f64,
+) -> Result<(), TestCaseError>
+where
+    T: core::ops::Mul<f64> + Copy,
+{
+    match result {
+        Some(r) => prop_assert!(
+            (r.penetration - depth).abs() < ORACLE_TOLERANCE,
+            "penetration {} but the oracle measured {}",
+            r.penetration,
+            depth
+        ),
+        None => prop_assert!(depth < ORACLE_TOLERANCE, "missed an overlap of {}", depth),
+    }
+    Ok(())
+}
+
+/// A routine that only detects contact must agree with the oracle on whether
+/// the shapes touch.
+fn check_overlap<T>(
+    result: Option<CollisionResolution<T, f64>>,
+    depth: f64,
+) -> Result<(), TestCaseError>
+where
+    T: core::ops::Mul<f64> + Copy,
+{
+    if depth > ORACLE_TOLERANCE {
+        prop_assert!(result.is_some(), "missed an overlap of {}", depth);
+    }
+    if depth < -ORACLE_TOLERANCE {
+        prop_assert!(
+            result.is_none(),
+            "reported a gap of {} as a collision",
+            -depth
+        );
+    }
+    Ok(())
+}
+
+/// A separating axis routine between flat-sided shapes finds the exact depth,
+/// which is often along the direction across two edges that the sampled
+/// directions miss, so the oracle only bounds it from above.
+fn check_depth_at_most<T>(
+    result: Option<CollisionResolution<T, f64>>,
+    depth: f64,
+) -> Result<(), TestCaseError>
+where
+    T: core::ops::Mul<f64> + Copy,
+{
+    match result {
+        Some(r) => prop_assert!(
+            r.penetration < depth + ORACLE_TOLERANCE,
+            "penetration {} but the oracle measured {}",
+            r.penetration,
+            depth
+        ),
+        None => prop_assert!(depth < ORACLE_TOLERANCE, "missed an overlap of {}", depth),
+    }
+    Ok(())
+}
+
+fn check_symmetric<T>(
+    ab: Option<CollisionResolution<T, f64>>,
+    ba: Option<CollisionResolution<T, f64>>,
+) -> Result<(), TestCaseError>
+where
+    T: core::ops::Mul<f64, Output = T> + core::ops::Sub<Output = T> + Copy + MaxAbs,
+{
+    match (ab, ba) {
+        (Some(ab), Some(ba)) => {
+            let ba = ba.flip();
+            prop_assert!((ab.penetration - ba.penetration).abs() < TOLERANCE);
+            prop_assert!((ab.normal - ba.normal).max_abs() < TOLERANCE);
+        }
+        (None, None) => {}
+        (Some(r), None) | (None, Some(r)) => prop_assert!(r.penetration < TOLERANCE),
+    }
+    Ok(())
+}
+
+fn is_close<T>(
+    a: &Option<CollisionResolution<T, f64>>,
+    b: &Option<CollisionResolution<T, f64>>,
+) -> bool
+where
+    T: core::ops::Mul<f64> + core::ops::Sub<Output = T> + Copy + MaxAbs,
+{
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            (a.penetration - b.penetration).abs() < TOLERANCE
+                && (a.normal - b.normal).max_abs() < TOLERANCE
+        }
+        (None, None) => true,
+        (Some(r), None) | (None, Some(r)) => r.penetration < TOLERANCE,
+    }
+}
+
+/// A ray cast must report the nearest of the distances the brute-force search
+/// found. Either may miss a hit that is only within `max` by rounding.
+fn check_ray_distance(
+    distance: Option<f64>,
+    expected: Option<f64>,
+    max: f64,
+) -> Result<(), TestCaseError> {
+    match (distance, expected) {
+        (Some(a), Some(b)) => prop_assert!((a - b).abs() < TOLERANCE, "{} but expected {}", a, b),
+        (None, None) => {}
+        (Some(d), None) | (None, Some(d)) => {
+            prop_assert!(
+                max - d < TOLERANCE,
+                "{:?} but expected {:?}",
+                distance,
+                expected
+            )
+        }
+    }
+    Ok(())
+}
+
+/// Moving `a` by the resolution must leave the shapes at most touching.
+fn check_resolves3<A, B, F>(a: &A, b: &B, collide: F) -> Result<(), TestCaseError>
+where
+    A: Shape3D<f64>,
+    F: Fn(&A, &B) -> Option<Resolution3>,
+{
+    if let Some(r) = collide(a, b) {
+        let moved = a.translate(&r.total_force());
+        if let Some(after) = collide(&moved, b) {
+            prop_assert!(
+                after.penetration < TOLERANCE,
+                "still {} deep after resolving {:?}",
+                after.penetration,
+                r
+            );
+        }
+    }
+    Ok(())
+}
+
+fn check_resolves2<A, B, F>(a: &A, b: &B, collide: F) -> Result<(), TestCaseError>
+where
+    A: Shape2D<f64>,
+    F: Fn(&A, &B) -> Option<Resolution2>,
+{
+    if let Some(r) = collide(a, b) {
+        let moved = a.translate(&r.total_force());
+        if let Some(after) = collide(&moved, b) {
+            prop_assert!(after.penetration < TOLERANCE);
+        }
+    }
+    Ok(())
+}
+
+fn check_translation3<A, B, F>(
+    a: &A,
+    b: &B,
+    offset: &Vector3<f64>,
+    collide: F,
+) -> Result<(), TestCaseError>
+where
+    A: Shape3D<f64>,
+    B: Shape3D<f64>,
+    F: Fn(&A, &B) -> Option<Resolution3>,
+{
+    let before = collide(a, b);
+    let after = collide(&a.translate(offset), &b.translate(offset));
+    prop_assert!(is_close(&before, &after), "{:?} became {:?}", before, after);
+    Ok(())
+}
+
+fn check_translation2<A, B, F>(
+    a: &A,
+    b: &B,
+    offset: &Vector2<f64>,
+    collide: F,
+) -> Result<(), TestCaseError>
+where
+    A: Shape2D<f64>,
+    B: Shape2D<f64>,
+    F: Fn(&A, &B) -> Option<Resolution2>,
+{
+    let before = collide(a, b);
+    let after = collide(&a.translate(offset), &b.translate(offset));
+    prop_assert!(is_close(&before, &after), "{:?} became {:?}", before, after);
+    Ok(())
+}
+
+/// The overlap of a convex shape with the solid half-space behind a plane.
+fn half_space_depth(shape: &dyn Support<Vector3<f64>>, plane: &Plane<f64>) -> f64 {
+    shape.support(&-plane.normal) - plane.d
+}
+
+/// Points sampled along a shape cast.
+const CAST_SAMPLES: usize = 32;
+
+fn convex(collider: &Collider3D<f64>) -> &dyn SupportMap<f64> {
+    match collider {
+        Collider3D::AABB(a) => a,
+        Collider3D::Sphere(a) => a,
+        Collider3D::Capsule(a) => a,
+        Collider3D::Triangle(a) => a,
+        Collider3D::Plane(_) => unreachable!("planes have no support map"),
+    }
+}
+
+/// How far apart two colliders are, zero when they overlap. Everything behind
+/// a plane is solid.
+fn collider_gap(a: &Collider3D<f64>, b: &Collider3D<f64>) -> f64 {
+    match b {
+        Collider3D::Plane(plane) => plane
+            .distance(&convex(a).support_point(&-plane.normal))
+            .max(0.0),
+        _ => distance_between(convex(a), convex(b)),
+    }
+}
+
+/// The gap `time_of_impact` stops within.
+const IMPACT_TOLERANCE: f64 = 1e-3;
+/// Enough iterations for fast spins to close in on contact, which can take a
+/// few hundred as each step assumes the worst about the rotation.
+const IMPACT_ITERATIONS: usize = 1000;
+
+/// A shape placed by a pose.
+struct Posed<'a> {
+    shape: &'a dyn SupportMap<f64>,
+    pose: Isometry3<f64>,
+}
+
+impl SupportMap<f64> for Posed<'_> {
+    fn support_point(&self, direction: &Vector3<f64>) -> Vector3<f64> {
+        let local = self.pose.rotation.inverse() * direction;
+        self.pose.rotation * self.shape.support_point(&local) + self.pose.translation.vector
+    }
+}
+
+const CULLINGS: [FaceCulling; 3] = [
+    FaceCulling::OneSided,
+    FaceCulling::DoubleSided,
+    FaceCulling::HalfSpace,
+];
+
+/// Compares a plane collision with how far `culling` should push the shape:
+/// out of the side its centre is on, out to the front for a half-space, or not
+/// at all from behind a one-sided plane.
+fn check_culled_depth(
+    result: Option<Resolution3>,
+    shape: &dyn Support<Vector3<f64>>,
+    center: &Vector3<f64>,
+    plane: &Plane<f64>,
+    culling: FaceCulling,
+) -> Result<(), TestCaseError> {
+    let behind = plane.distance(center) < 0.0;
+    match culling {
+        FaceCulling::OneSided if behind => prop_assert!(result.is_none()),
+        FaceCulling::DoubleSided if behind => {
+            check_depth(result, shape.support(&plane.normal) + plane.d)?
+        }
+        _ => check_depth(result, half_space_depth(shape, plane))?,
+    }
+    Ok(())
+}
+
+/// Compares a half-space triangle collision with the triangle's plane: any
+/// push is the plane's, and a shape centred in the prism behind the triangle
+/// always collides.
+fn check_prism(
+    result: Option<Resolution3>,
+    shape: &dyn Support<Vector3<f64>>,
+    center: &Vector3<f64>,
+    triangle: &Triangle<f64>,
+) -> Result<(), TestCaseError> {
+    let plane = Plane::from(triangle);
+    let distance = plane.distance(center);
+    let foot = center - plane.normal * distance;
+    if distance < 0.0 && triangle.closest_point(&foot).1 < TOLERANCE {
+        prop_assert!(result.is_some(), "missed a shape inside the prism");
+    }
+    if result.is_some() {
+        check_depth(result, half_space_depth(shape, &plane))?;
+    }
+    Ok(())
+}
+
+/// Whether the bounding circle or sphere holds the shape along every sampled
+/// direction.
+fn check_bounds<V: core::fmt::Debug>(
+    shape: &dyn Support<V>,
+    bound: &dyn Support<V>,
+    directions: &[V],
+) -> Result<(), TestCaseError> {
+    for d in directions {
+        prop_assert!(
+            shape.support(d) <= bound.support(d) + TOLERANCE,
+            "{:?} is outside the bound",
+            d
+        );
+    }
+    Ok(())
+}
+
+/// Whether the points on the surface of the bounding circle or sphere
+/// surround its centre, which holds only for the smallest one: otherwise
+/// moving the centre towards them would shrink it.
+fn check_minimal<D: DimName>(
+    points: &[VectorN<f64, D>],
+    center: &VectorN<f64, D>,
+    radius: f64,
+    directions: &[VectorN<f64, D>],
+) -> Result<(), TestCaseError>
+where
+    DefaultAllocator: Allocator<f64, D>,
+{
+    let surface: Vec<_> = points
+        .iter()
+        .map(|p| p - center)
+        .filter(|p| p.magnitude() >= radius - TOLERANCE)
+        .collect();
+    for d in directions {
+        let reach = surface.iter().map(|p| p.dot(d)).fold(f64::MIN, f64::max);
+        prop_assert!(reach >= -TOLERANCE, "nothing on the surface along {:?}", d);
+    }
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    fn sphere_sphere(a in sphere(), b in sphere(), offset in vector3()) {
+        let collide = |a: &Sphere<f64>, b: &Sphere<f64>| a.get_sphere_collision(b);
+        check_symmetric(collide(&a, &b), collide(&b, &a))?;
+        check_resolves3(&a, &b, collide)?;
+        check_translation3(&a, &b, &offset, collide)?;
+        check_depth(collide(&a, &b), oracle_depth3(&a, &b))?;
+    }
+
+    #[test]
+    fn aabb_aabb(a in aabb(), b in aabb(), offset in vector3()) {
+        let collide = |a: &AxisAlignedBoundingBox<f64>, b: &AxisAlignedBoundingBox<f64>| {
+            a.get_aabb_collision(b)
+        };
+        check_symmetric(collide(&a, &b), collide(&b, &a))?;
+        check_resolves3(&a, &b, collide)?;
+        check_translation3(&a, &b, &offset, collide)?;
+        check_depth(collide(&a, &b), oracle_depth3(&a, &b))?;
+    }
+
+    #[test]
+    fn aabb_sphere(a in aabb(), b in sphere(), offset in vector3()) {
+        let collide = |a: &AxisAlignedBoundingBox<f64>, b: &Sphere<f64>| a.get_sphere_collision(b);
+        check_resolves3(&a, &b, collide)?;
+        check_resolves3(&b, &a, |b, a| collide(a, b).map(|r| r.flip()))?;
+        check_translation3(&a, &b, &offset, collide)?;
+        check_depth(collide(&a, &b), oracle_depth3(&a, &b))?;
+    }
+
+    #[test]
+    fn sphere_capsule(a in sphere(), b in capsule(), offset in vector3()) {
+        let collide = |a: &Sphere<f64>, b: &Capsule<f64>| a.get_capsule_collision(b);
+        check_resolves3(&a, &b, collide)?;
+        check_translation3(&a, &b, &offset, collide)?;
+        check_depth(collide(&a, &b), oracle_depth3(&a, &b))?;
+    }
+
+    #[test]
+    fn sphere_plane(a in sphere(), b in plane(), offset in vector3()) {
+        for &culling in CULLINGS.iter() {
+            let collide = |a: &Sphere<f64>, b: &Plane<f64>| a.get_plane_collision(b, culling);
+            check_resolves3(&a, &b, collide)?;
+            check_translation3(&a, &b, &offset, collide)?;
+            check_culled_depth(collide(&a, &b), &a, &a.center, &b, culling)?;
+        }
+    }
+
+    #[test]
+    fn aabb_plane(a in aabb(), b in plane(), offset in vector3()) {
+        for &culling in CULLINGS.iter() {
+            let collide = |a: &AxisAlignedBoundingBox<f64>, b: &Plane<f64>| {
+                a.get_plane_collision(b, culling)
+            };
+            check_resolves3(&a, &b, collide)?;
+            check_translation3(&a, &b, &offset, collide)?;
+            check_culled_depth(collide(&a, &b), &a, &a.center(), &b, culling)?;
+        }
+    }
+
+    #[test]
+    fn capsule_plane(a in capsule(), b in plane(), offset in vector3()) {
+        for &culling in CULLINGS.iter() {
+            let collide = |a: &Capsule<f64>, b: &Plane<f64>| a.get_plane_collision(b, culling);
+            check_resolves3(&a, &b, collide)?;
+            check_translation3(&a, &b, &offset, collide)?;
+            check_culled_depth(collide(&a, &b), &a, &a.center(), &b, culling)?;
+        }
+    }
+
+    #[test]
+    fn sphere_triangle(a in sphere(), b in triangle(), offset in vector3()) {
+        for &culling in CULLINGS.iter() {
+            let collide = |a: &Sphere<f64>, b: &Triangle<f64>| a.get_triangle_collision(b, culling);
+            check_resolves3(&a, &b, collide)?;
+            check_translation3(&a, &b, &offset, collide)?;
+        }
+        let collide = |a: &Sphere<f64>, b: &Triangle<f64>| {
+            a.get_triangle_collision(b, FaceCulling::DoubleSided)
+        };
+        check_depth_at_most(collide(&a, &b), oracle_depth3(&a, &b))?;
+        let result = a.get_triangle_collision(&b, FaceCulling::HalfSpace);
+        check_prism(result, &a, &a.center, &b)?;
+    }
+
+    #[test]
+    fn capsule_triangle(a in capsule(), b in triangle(), offset in vector3()) {
+        for &culling in CULLINGS.iter() {
+            let collide = |a: &Capsule<f64>, b: &Triangle<f64>| a.get_triangle_collision(b, culling);
+            check_resolves3(&a, &b, collide)?;
+            check_translation3(&a, &b, &offset, collide)?;
+        }
+        let collide = |a: &Capsule<f64>, b: &Triangle<f64>| {
+            a.get_triangle_collision(b, FaceCulling::DoubleSided)
+        };
+        check_resolves3(&b, &a, |b, a| collide(a, b).map(|r| r.flip()))?;
+        check_depth_at_most(collide(&a, &b), oracle_depth3(&a, &b))?;
+        let result = a.get_triangle_collision(&b, FaceCulling::HalfSpace);
+        check_prism(result, &a, &a.center(), &b)?;
+    }
+
+    #[test]
+    fn aabb_triangle(a in aabb(), b in triangle(), offset in vector3()) {
+        for &culling in CULLINGS.iter() {
+            let collide = |a: &AxisAlignedBoundingBox<f64>, b: &Triangle<f64>| {
+                a.get_triangle_collision(b, culling)
+            };
+            check_resolves3(&a, &b, collide)?;
+            check_translation3(&a, &b, &offset, collide)?;
+        }
+        let collide = |a: &AxisAlignedBoundingBox<f64>, b: &Triangle<f64>| {
+            a.get_triangle_collision(b, FaceCulling::DoubleSided)
+        };
+        check_resolves3(&b, &a, |b, a| collide(a, b).map(|r| r.flip()))?;
+        let result = a.get_triangle_collision(&b, FaceCulling::HalfSpace);
+        check_prism(result, &a, &a.center(), &b)?;
+    }
+
+    #[test]
+    fn aabb_triangle_matches_oracle(a in aabb(), b in triangle()) {
+        let result = a.get_triangle_collision(&b, FaceCulling::DoubleSided);
+        check_depth_at_most(result, oracle_depth3(&a, &b))?;
+        prop_assert_eq!(a.intersects_triangle(&b), result.is_some());
+    }
+
+    #[test]
+    fn coplanar_triangles(a in triangle(), points in [vector2(), vector2(), vector2()]) {
+        // `b` in the plane of `a`, in coordinates along two of its edges.
+        let u = (a.point2 - a.point1).normalize();
+        let v = a.normal().cross(&u);
+        let to_world = |p: &Vector2<f64>| a.point1 + u * p.x + v * p.y;
+        let b = Triangle {
+            point1: to_world(&points[0]),
+            point2: to_world(&points[1]),
+            point3: to_world(&points[2]),
+        };
+        let to_plane = |p: &Vector3<f64>| Vector2::new((p - a.point1).dot(&u), (p - a.point1).dot(&v));
+        let flat = [to_plane(&a.point1), to_plane(&a.point2), to_plane(&a.point3)];
+        // Sampled directions miss slivers, but polygons are deepest along one
+        // of their edge normals, so the exact depth is among those.
+        let depth = [flat, points]
+            .iter()
+            .flat_map(|t| (0..3).map(move |i| t[(i + 1) % 3] - t[i]))
+            .map(|edge| Vector2::new(-edge.y, edge.x).normalize())
+            .flat_map(|normal| [normal, -normal])
+            .map(|d| flat.support(&-d) + points.support(&d))
+            .fold(oracle_depth2(&flat, &points), f64::min);
+
+        let collide = |a: &Triangle<f64>, b: &Triangle<f64>| a.get_triangle_collision(b);
+        check_overlap(collide(&a, &b), depth)?;
+        if let Some(r) = collide(&a, &b) {
+            prop_assert!(r.penetration < TOLERANCE, "coplanar triangles only touch");
+        }
+        prop_assert!(a.intersection_segment(&b).is_none());
+    }
+
+    #[test]
+    fn degenerate_triangles(a in degenerate_triangle(), b in triangle(), c in degenerate_triangle()) {
+        let collide = |a: &Triangle<f64>, b: &Triangle<f64>| a.get_triangle_collision(b);
+        for (a, b) in [(a, b), (a, c)].iter() {
+            check_symmetric(collide(a, b), collide(b, a))?;
+            check_overlap(collide(a, b), oracle_depth3(a, b))?;
+            if let Some(r) = collide(a, b) {
+                prop_assert!(r.penetration.is_finite() && r.normal.iter().all(|x| x.is_finite()));
+            }
+            prop_assert!(a.intersection_segment(b).is_none());
+        }
+    }
+
+    #[test]
+    fn triangle_triangle(a in triangle(), b in triangle(), offset in vector3()) {
+        let collide = |a: &Triangle<f64>, b: &Triangle<f64>| a.get_triangle_collision(b);
+        check_symmetric(collide(&a, &b), collide(&b, &a))?;
+        check_resolves3(&a, &b, collide)?;
+        check_translation3(&a, &b, &offset, collide)?;
+        check_depth_at_most(collide(&a, &b), oracle_depth3(&a, &b))?;
+        if let Some((start, end)) = a.intersection_segment(&b) {
+            prop_assert!(collide(&a, &b).is_some());
+            for point in [start, end].iter() {
+                prop_assert!(a.closest_point(point).1 < TOLERANCE);
+                prop_assert!(b.closest_point(point).1 < TOLERANCE);
+            }
+            prop_assert!((end - start).dot(&a.normal().cross(&b.normal())) >= -TOLERANCE);
+        }
+    }
+
+    #[test]
+    fn cylinder_triangle(a in cylinder(), b in triangle(), offset in vector3()) {
+        for &culling in CULLINGS.iter() {
+            let collide = |a: &Cylinder<f64>, b: &Triangle<f64>| a.get_triangle_collision(b, culling);
+            check_resolves3(&a, &b, collide)?;
+            check_translation3(&a, &b, &offset, collide)?;
+        }
+        let collide = |a: &Cylinder<f64>, b: &Triangle<f64>| {
+            a.get_triangle_collision(b, FaceCulling::DoubleSided)
+        };
+        check_resolves3(&b, &a, |b, a| collide(a, b).map(|r| r.flip()))?;
+        check_overlap(collide(&a, &b), oracle_depth3(&a, &b))?;
+        let result = a.get_triangle_collision(&b, FaceCulling::HalfSpace);
+        check_prism(result, &a, &a.center, &b)?;
+    }
+
+    #[test]
+    fn aabb_capsule(a in aabb(), b in capsule(), offset in vector3()) {
+        let collide = |a: &AxisAlignedBoundingBox<f64>, b: &Capsule<f64>| {
+            a.get_capsule_collision(b)
+        };
+        check_resolves3(&a, &b, collide)?;
+        check_resolves3(&b, &a, |b, a| collide(a, b).map(|r| r.flip()))?;
+        check_translation3(&a, &b, &offset, collide)?;
+        check_depth(collide(&a, &b), oracle_depth3(&a, &b))?;
+    }
+
+    #[test]
+    fn capsule_capsule(a in capsule(), b in capsule(), offset in vector3()) {
+        let collide = |a: &Capsule<f64>, b: &Capsule<f64>| a.get_capsule_collision(b);
+        check_symmetric(collide(&a, &b), collide(&b, &a))?;
+        check_resolves3(&a, &b, collide)?;
+        check_translation3(&a, &b, &offset, collide)?;
+        check_depth(collide(&a, &b), oracle_depth3(&a, &b))?;
+    }
+
+    #[test]
+    fn cylinder_capsule(a in cylinder(), b in capsule(), offset in vector3()) {
+        let collide = |a: &Cylinder<f64>, b: &Capsule<f64>| a.get_capsule_collision(b);
+        check_resolves3(&a, &b, collide)?;
+        check_resolves3(&b, &a, |b, a| collide(a, b).map(|r| r.flip()))?;
+        check_translation3(&a, &b, &offset, collide)?;
+        check_overlap(collide(&a, &b), oracle_depth3(&a, &b))?;
+    }
+
+    #[test]
+    fn cylinder_sphere(a in cylinder(), b in sphere(), offset in vector3()) {
+        let collide = |a: &Cylinder<f64>, b: &Sphere<f64>| a.get_sphere_collision(b);
+        check_resolves3(&a, &b, collide)?;
+        check_translation3(&a, &b, &offset, collide)?;
+        // The routine tests three projections, so it can report a collision
+        // near the rim that the oracle does not, but it never misses one.
+        let depth = oracle_depth3(&a, &b);
+        prop_assert!(depth < ORACLE_TOLERANCE || collide(&a, &b).is_some());
+    }
+
+    #[test]
+    fn cylinder_aabb(a in cylinder(), b in aabb(), offset in vector3()) {
+        let collide = |a: &Cylinder<f64>, b: &AxisAlignedBoundingBox<f64>| a.get_aabb_collision(b);
+        check_resolves3(&a, &b, collide)?;
+        check_translation3(&a, &b, &offset, collide)?;
+        let depth = oracle_depth3(&a, &b);
+        prop_assert!(depth < ORACLE_TOLERANCE || collide(&a, &b).is_some());
+    }
+
+    #[test]
+    fn cone_sphere(a in cone(), b in sphere(), offset in vector3()) {
+        let collide = |a: &Cone<f64>, b: &Sphere<f64>| a.get_sphere_collision(b);
+        check_resolves3(&a, &b, collide)?;
+        check_resolves3(&b, &a, |b, a| collide(a, b).map(|r| r.flip()))?;
+        check_translation3(&a, &b, &offset, collide)?;
+        check_depth(collide(&a, &b), oracle_depth3(&a, &b))?;
+    }
+
+    #[test]
+    fn cone_aabb(a in cone(), b in aabb(), offset in vector3()) {
+        let collide = |a: &Cone<f64>, b: &AxisAlignedBoundingBox<f64>| a.get_aabb_collision(b);
+        check_resolves3(&a, &b, collide)?;
+        check_resolves3(&b, &a, |b, a| collide(a, b).map(|r| r.flip()))?;
+        check_translation3(&a, &b, &offset, collide)?;
+        check_overlap(collide(&a, &b), oracle_depth3(&a, &b))?;
+    }
+
+    #[test]
+    fn cone_capsule(a in cone(), b in capsule(), offset in vector3()) {
+        let collide = |a: &Cone<f64>, b: &Capsule<f64>| a.get_capsule_collision(b);
+        check_resolves3(&a, &b, collide)?;
+        check_resolves3(&b, &a, |b, a| collide(a, b).map(|r| r.flip()))?;
+        check_translation3(&a, &b, &offset, collide)?;
+        check_overlap(collide(&a, &b), oracle_depth3(&a, &b))?;
+    }
+
+    #[test]
+    fn cone_contains_point(a in cone(), point in vector3()) {
+        let projection = a.project_point(&point);
+        prop_assert_eq!(projection.is_inside, a.contains_point(&point));
+        prop_assert!(a.bounding_aabb().contains_point(&point) || !projection.is_inside);
+        let sphere = a.bounding_sphere();
+        prop_assert!(
+            (point - sphere.center).magnitude() <= sphere.radius + TOLERANCE
+                || !projection.is_inside
+        );
+    }
+
+    #[test]
+    fn bounding_spheres_hold_shapes(
+        a in aabb(),
+        b in capsule(),
+        c in cylinder(),
+        d in triangle(),
+        e in cone(),
+    ) {
+        check_bounds(&a, &a.bounding_sphere(), directions3())?;
+        check_bounds(&b, &b.bounding_sphere(), directions3())?;
+        check_bounds(&c, &c.bounding_sphere(), directions3())?;
+        check_bounds(&d, &d.bounding_sphere(), directions3())?;
+        check_bounds(&e, &e.bounding_sphere(), directions3())?;
+        let points = [d.point1, d.point2, d.point3];
+        let sphere = d.bounding_sphere();
+        check_minimal(&points, &sphere.center, sphere.radius, directions3())?;
+    }
+
+    #[test]
+    fn bounding_circles_hold_shapes(a in aabb2d(), b in circle()) {
+        check_bounds(&a, &a.bounding_sphere(), directions2())?;
+        check_bounds(&b, &b.bounding_sphere(), directions2())?;
+    }
+
+    #[test]
+    fn closest_points_match_oracle(a in cylinder(), b in aabb()) {
+        let depth = oracle_depth3(&a, &b);
+        match closest_points(&a, &b) {
+            Some((on_a, on_b)) => {
+                prop_assert!(((on_a - on_b).magnitude() + depth).abs() < ORACLE_TOLERANCE);
+                prop_assert!((on_a - a.project_point(&on_a).point).magnitude() < ORACLE_TOLERANCE);
+                prop_assert!(b.expand_by(TOLERANCE).contains_point(&on_b));
+            }
+            None => prop_assert!(depth > -ORACLE_TOLERANCE),
+        }
+    }
+
+    #[test]
+    fn sdf_matches_projection(
+        a in sphere(),
+        b in aabb(),
+        c in capsule(),
+        d in cylinder(),
+        e in cone(),
+        point in vector3(),
+    ) {
+        let check = |sdf: &dyn Sdf3D<f64>, projection: PointProjection<Vector3<f64>>| {
+            let distance = (point - projection.point).magnitude();
+            let expected = if projection.is_inside { -distance } else { distance };
+            prop_assert!(
+                (sdf.signed_distance(&point) - expected).abs() < TOLERANCE,
+                "distance {} but the projection is {} away",
+                sdf.signed_distance(&point),
+                expected
+            );
+            let gradient = sdf.gradient(&point);
+            prop_assert!((gradient.magnitude() - 1.0).abs() < TOLERANCE);
+            Ok(())
+        };
+        check(&a, a.project_point(&point))?;
+        check(&b, b.project_point(&point))?;
+        check(&c, c.project_point(&point))?;
+        check(&d, d.project_point(&point))?;
+        check(&e, e.project_point(&point))?;
+    }
+
+    #[test]
+    fn smooth_union_rounds_union(
+        a in sphere(),
+        b in aabb(),
+        k in prop_oneof![Just(0.0), -2.0..2.0f64],
+        point in vector3(),
+    ) {
+        // Blending only ever adds material, and at most a quarter of `k`.
+        let union = Union::new(a, b).signed_distance(&point);
+        let smooth = SmoothUnion::new(a, b, k);
+        let distance = smooth.signed_distance(&point);
+        prop_assert!(distance <= union + TOLERANCE, "{} above the union {}", distance, union);
+        prop_assert!(distance >= union - k.abs() / 4.0 - TOLERANCE);
+        if k == 0.0 {
+            prop_assert_eq!(distance, union);
+        }
+        prop_assert!((smooth.gradient(&point).magnitude() - 1.0).abs() < TOLERANCE);
+    }
+
+    #[test]
+    fn sphere_sdf(a in sphere(), b in aabb(), c in cone(), offset in vector3()) {
+        let collide = |a: &Sphere<f64>, b: &AxisAlignedBoundingBox<f64>| a.get_sdf_collision(b);
+        check_resolves3(&a, &b, collide)?;
+        check_translation3(&a, &b, &offset, collide)?;
+        check_depth(collide(&a, &b), oracle_depth3(&a, &b))?;
+        check_depth(a.get_sdf_collision(&c), oracle_depth3(&a, &c))?;
+    }
+
+    #[test]
+    fn capsule_sdf(a in capsule(), b in aabb(), c in sphere(), offset in vector3()) {
+        let union = Union::new(b, c);
+        let collide = |a: &Capsule<f64>, b: &AxisAlignedBoundingBox<f64>| a.get_sdf_collision(b);
+        check_translation3(&a, &b, &offset, collide)?;
+        check_overlap(collide(&a, &b), oracle_depth3(&a, &b))?;
+        let depth = f64::max(oracle_depth3(&a, &b), oracle_depth3(&a, &c));
+        check_overlap(a.get_sdf_collision(&union), depth)?;
+    }
+
+    #[test]
+    fn shape_cast_matches_sampling(
+        a in caster(),
+        b in cast_target(),
+        displacement in vector3(),
+        skin in 0.0..0.5f64,
+    ) {
+        let hit = a.shape_cast(&displacement, &b, skin);
+        let gap = |time: f64| collider_gap(&a.translate(&(displacement * time)), &b);
+        let end = hit.map_or(1.0, |hit| hit.time);
+        // Nothing comes within the skin before the hit, or at all without one.
+        let samples = if hit.is_some() { CAST_SAMPLES } else { CAST_SAMPLES + 1 };
+        if end > 0.0 {
+            for i in 0..samples {
+                let time = end * i as f64 / CAST_SAMPLES as f64;
+                prop_assert!(gap(time) > skin - TOLERANCE, "{} apart at {}", gap(time), time);
+            }
+        }
+        if let Some(hit) = hit {
+            prop_assert!((0.0..=1.0).contains(&hit.time));
+            if hit.normal == Vector3::zeros() {
+                prop_assert_eq!(hit.time, 0.0);
+                prop_assert!(gap(0.0) < TOLERANCE);
+            } else {
+                let distance = (hit.point1 - hit.point2).magnitude();
+                prop_assert!((hit.normal.magnitude() - 1.0).abs() < TOLERANCE);
+                prop_assert!((hit.normal.dot(&(hit.point1 - hit.point2)) - distance).abs() < TOLERANCE);
+                prop_assert!((distance - gap(hit.time)).abs() < TOLERANCE);
+                prop_assert!(distance < skin + TOLERANCE);
+                if hit.time > 0.0 {
+                    prop_assert!(distance > skin - TOLERANCE);
+                }
+            }
+        }
+        if let Collider3D::Plane(_) = b {
+            let reverse = b.shape_cast(&-displacement, &a, skin);
+            prop_assert_eq!(reverse.map(|hit| hit.time), hit.map(|hit| hit.time));
+            prop_assert_eq!(reverse.map(|hit| -hit.normal), hit.map(|hit| hit.normal));
+        }
+    }
+
+    #[test]
+    fn time_of_impact_matches_sampling(
+        a in caster(),
+        motion_a in motion(),
+        b in prop_oneof![caster(), triangle().prop_map(Collider3D::Triangle)],
+        motion_b in motion(),
+    ) {
+        let (a, b) = (convex(&a), convex(&b));
+        let hit = time_of_impact(a, &motion_a, b, &motion_b, IMPACT_TOLERANCE, IMPACT_ITERATIONS);
+        let gap = |time: f64| {
+            distance_between(
+                &Posed { shape: a, pose: motion_a.at(time) },
+                &Posed { shape: b, pose: motion_b.at(time) },
+            )
+        };
+        let end = hit.map_or(1.0, |hit| hit.time);
+        // Nothing touches before the hit, or at all without one.
+        let samples = if hit.is_some() { CAST_SAMPLES } else { CAST_SAMPLES + 1 };
+        if end > 0.0 {
+            for i in 0..samples {
+                let time = end * i as f64 / CAST_SAMPLES as f64;
+                prop_assert!(gap(time) > 0.0, "touching at {}", time);
+            }
+        }
+        if let Some(hit) = hit {
+            prop_assert!((0.0..=1.0).contains(&hit.time));
+            if hit.normal == Vector3::zeros() {
+                prop_assert_eq!(hit.time, 0.0);
+                prop_assert_eq!(gap(0.0), 0.0);
+            } else {
+                let distance = (hit.point1 - hit.point2).magnitude();
+                prop_assert!((hit.normal.magnitude() - 1.0).abs() < TOLERANCE);
+                prop_assert!((hit.normal.dot(&(hit.point1 - hit.point2)) - distance).abs() < TOLERANCE);
+                prop_assert!((distance - gap(hit.time)).abs() < TOLERANCE);
+                prop_assert!(distance < IMPACT_TOLERANCE + TOLERANCE);
+            }
+        }
+        // A tight cap gives up rather than report a hit short of contact.
+        let capped = time_of_impact(a, &motion_a, b, &motion_b, IMPACT_TOLERANCE, 2);
+        if capped.is_some() {
+            prop_assert_eq!(capped, hit);
+        }
+    }
+
+    #[test]
+    fn collider3d_symmetry(a in collider3d(), b in collider3d()) {
+        check_symmetric(a.get_collision(&b), b.get_collision(&a))?;
+    }
+
+    #[test]
+    fn circle_circle(a in circle(), b in circle(), offset in vector2()) {
+        let collide = |a: &Circle<f64>, b: &Circle<f64>| a.get_circle_collision(b);
+        check_symmetric(collide(&a, &b), collide(&b, &a))?;
+        check_resolves2(&a, &b, collide)?;
+        check_translation2(&a, &b, &offset, collide)?;
+        check_depth(collide(&a, &b), oracle_depth2(&a, &b))?;
+    }
+
+    #[test]
+    fn aabb2d_aabb2d(a in aabb2d(), b in aabb2d(), offset in vector2()) {
+        let collide = |a: &AABB2D, b: &AABB2D| a.get_aabb_collision(b);
+        check_symmetric(collide(&a, &b), collide(&b, &a))?;
+        check_resolves2(&a, &b, collide)?;
+        check_translation2(&a, &b, &offset, collide)?;
+        check_depth(collide(&a, &b), oracle_depth2(&a, &b))?;
+    }
+
+    #[test]
+    fn aabb2d_circle(a in aabb2d(), b in circle(), offset in vector2()) {
+        let collide = |a: &AABB2D, b: &Circle<f64>| a.get_circle_collision(b);
+        check_resolves2(&a, &b, collide)?;
+        check_resolves2(&b, &a, |b, a| collide(a, b).map(|r| r.flip()))?;
+        check_translation2(&a, &b, &offset, collide)?;
+        check_depth(collide(&a, &b), oracle_depth2(&a, &b))?;
+    }
+
+    #[test]
+    fn collider2d_symmetry(a in collider2d(), b in collider2d()) {
+        check_symmetric(a.get_collision(&b), b.get_collision(&a))?;
+    }
+}
+
+#[cfg(feature = "alloc")]
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    fn triangle_mesh_sphere(a in sphere(), b in triangle(), c in triangle(), offset in vector3()) {
+        let mesh: TriangleMesh<f64> = vec![b, c].into();
+        let collide = |a: &Sphere<f64>, mesh: &TriangleMesh<f64>| {
+            mesh.get_sphere_collision(a, FaceCulling::DoubleSided).map(|r| r.flip())
+        };
+        check_translation3(&a, &mesh, &offset, collide)?;
+    }
+
+    #[test]
+    fn voxel_grid_sphere(a in sphere(), b in voxel_grid(), offset in vector3()) {
+        let collide = |a: &Sphere<f64>, grid: &VoxelGrid<f64>| {
+            grid.get_sphere_collision(a).map(|r| r.flip())
+        };
+        check_translation3(&a, &b, &offset, collide)?;
+    }
+
+    #[test]
+    fn voxel_grid_aabb(a in aabb(), b in voxel_grid(), offset in vector3()) {
+        let collide = |a: &AxisAlignedBoundingBox<f64>, grid: &VoxelGrid<f64>| {
+            grid.get_aabb_collision(a).map(|r| r.flip())
+        };
+        check_translation3(&a, &b, &offset, collide)?;
+    }
+
+    #[test]
+    fn voxel_ray_matches_brute_force(grid in voxel_grid(), ray in grid_ray(), max in 0.0..10.0f64) {
+        let [x, y, z] = grid.dimensions();
+        let expected = (0..x)
+            .flat_map(|i| (0..y).flat_map(move |j| (0..z).map(move |k| [i, j, k])))
+            .filter(|cell| grid.is_solid(*cell))
+            .filter_map(|cell| ray.intersects_aabb(&grid.cell_aabb(cell)))
+            .filter(|distance| *distance <= max)
+            .fold(None, |a: Option<f64>, d| Some(a.map_or(d, |a| a.min(d))));
+        let hit = grid.cast_ray(&ray, max);
+        check_ray_distance(hit.map(|hit| hit.distance), expected, max)?;
+        if let Some(hit) = hit {
+            prop_assert!(grid.is_solid(hit.cell));
+            let distance = ray.intersects_aabb(&grid.cell_aabb(hit.cell));
+            check_ray_distance(Some(hit.distance), distance, max)?;
+        }
+    }
+
+    #[test]
+    fn tile_ray_matches_brute_force(
+        map in tile_map(),
+        (point, direction) in grid_ray2(),
+        max in 0.0..10.0f64,
+    ) {
+        use collision2d::Tile;
+        let (x, y, dx, dy) = (point.x, point.y, direction.x, direction.y);
+        let [width, height] = map.dimensions();
+        let expected = (0..width)
+            .flat_map(|i| (0..height).map(move |j| [i, j]))
+            .filter_map(|tile| {
+                let min = map.origin + Vector2::new(tile[0] as f64, tile[1] as f64) * map.tile_size;
+                let max = min + Vector2::from_element(map.tile_size);
+                match map.tile(tile) {
+                    Tile::Solid => {
+                        let ray = Ray::new(&point.push(0.0), &direction.push(0.0));
+                        let aabb = AxisAlignedBoundingBox {
+                            start: min.push(-1.0),
+                            end: max.push(1.0),
+                        };
+                        ray.intersects_aabb(&aabb)
+                    }
+                    // Only crossing the top edge on the way down.
+                    Tile::OneWay if dy < 0.0 => {
+                        let distance = (max.y - y) / dy;
+                        let across = x + dx * distance;
+                        (distance > 0.0 && across >= min.x && across <= max.x).then_some(distance)
+                    }
+                    _ => None,
+                }
+            })
+            .filter(|distance| *distance <= max)
+            .fold(None, |a: Option<f64>, d| Some(a.map_or(d, |a| a.min(d))));
+        let hit = map.cast_ray(&point, &direction, max);
+        check_ray_distance(hit.map(|hit| hit.distance), expected, max)?;
+    }
+
+    #[test]
+    fn tile_map_aabb(a in aabb2d(), b in tile_map(), offset in vector2()) {
+        let collide = |a: &AABB2D, map: &collision2d::TileMap2D<f64>| {
+            map.get_aabb_collision(a).map(|r| r.flip())
+        };
+        check_translation2(&a, &b, &offset, collide)?;
+    }
+
+    #[test]
+    fn tile_map_circle(a in circle(), b in tile_map(), offset in vector2()) {
+        let collide = |a: &Circle<f64>, map: &collision2d::TileMap2D<f64>| {
+            map.get_circle_collision(a).map(|r| r.flip())
+        };
+        check_translation2(&a, &b, &offset, collide)?;
+    }
+
+    #[test]
+    fn sphere_from_points_is_minimal(
+        mut points in prop::collection::vec(vector3(), 1..16),
+        flat in any::<bool>(),
+    ) {
+        // Coplanar points have no sphere through four of them.
+        if flat {
+            points.iter_mut().for_each(|p| p.z = 0.0);
+        }
+        let sphere = Sphere::from_points(points.iter().copied());
+        for p in &points {
+            prop_assert!((p - sphere.center).magnitude() <= sphere.radius);
+        }
+        check_minimal(&points, &sphere.center, sphere.radius, directions3())?;
+    }
+
+    #[test]
+    fn circle_from_points_is_minimal(points in prop::collection::vec(vector2(), 1..16)) {
+        let circle = Circle::from_points(points.iter().copied());
+        for p in &points {
+            prop_assert!((p - circle.center).magnitude() <= circle.radius);
+        }
+        check_minimal(&points, &circle.center, circle.radius, directions2())?;
+    }
+
+    #[test]
+    fn octree_matches_brute_force(
+        spheres in proptest::collection::vec(sphere(), 0..64),
+        query in aabb(),
+        point in vector3(),
+    ) {
+        let tree = Octree::from_items(spheres.iter().copied(), 4, 2);
+        let expected: Vec<_> = spheres
+            .iter()
+            .filter(|s| s.bounding_aabb().intersection(&query).is_some())
+            .collect();
+        prop_assert_eq!(tree.query_aabb(&query), expected);
+        let nearest = spheres
+            .iter()
+            .map(|s| (s.bounding_aabb().closest_point(&point) - point).magnitude())
+            .fold(None, |a: Option<f64>, d| Some(a.map_or(d, |a| a.min(d))));
+        prop_assert_eq!(tree.nearest(&point).map(|(_, d)| d), nearest);
+    }
+
+    #[test]
+    fn octree_matches_brute_force_on_splits(
+        items in proptest::collection::vec(split_aabb(), 0..48),
+        query in split_aabb(),
+        point in (split_coordinate(), split_coordinate(), split_coordinate()),
+        radius in prop_oneof![Just(0.0), 0.0..2.0f64],
+        ray in grid_ray(),
+    ) {
+        // Small leaves so the tree splits as deep as it can.
+        let bounds = AxisAlignedBoundingBox {
+            start: Vector3::from_element(-4.0),
+            end: Vector3::from_element(4.0),
+        };
+        let mut tree = Octree::new(&bounds, 4, 1);
+        for item in &items {
+            tree.insert(*item);
+        }
+        let point = Vector3::new(point.0, point.1, point.2);
+        let brute = |test: &dyn Fn(&AxisAlignedBoundingBox<f64>) -> bool| {
+            items.iter().filter(|b| test(b)).collect::<Vec<_>>()
+        };
+        prop_assert_eq!(tree.query_aabb(&query), brute(&|b| b.intersection(&query).is_some()));
+        prop_assert_eq!(tree.query_point(&point), brute(&|b| b.contains_point(&point)));
+        let sphere = Sphere { center: point, radius };
+        prop_assert_eq!(
+            tree.query_sphere(&sphere),
+            brute(&|b| (b.closest_point(&point) - point).magnitude() <= radius)
+        );
+
+        let mut expected: Vec<_> = items.iter().filter_map(|b| ray.intersects_aabb(b)).collect();
+        expected.sort_by(f64::total_cmp);
+        let hits: Vec<_> = tree.query_ray(&ray, f64::MAX).into_iter().map(|(_, d)| d).collect();
+        prop_assert_eq!(hits, expected);
+
+        let nearest = items
+            .iter()
+            .map(|b| (b.closest_point(&point) - point).magnitude())
+            .fold(None, |a: Option<f64>, d| Some(a.map_or(d, |a| a.min(d))));
+        prop_assert_eq!(tree.nearest(&point).map(|(_, d)| d), nearest);
+    }
+
+    #[test]
+    fn quadtree_matches_brute_force(
+        circles in proptest::collection::vec(circle(), 0..64),
+        query in circle(),
+        point in vector2(),
+    ) {
+        let tree = collision2d::Quadtree::from_items(circles.iter().copied(), 4, 2);
+        let expected: Vec<_> = circles
+            .iter()
+            .filter(|c| {
+                let closest = c.bounding_aabb().closest_point(&query.center);
+                (closest - query.center).magnitude_squared() <= query.radius * query.radius
+            })
+            .collect();
+        prop_assert_eq!(tree.query_circle(&query), expected);
+        let nearest = circles
+            .iter()
+            .map(|c| (c.bounding_aabb().closest_point(&point) - point).magnitude())
+            .fold(None, |a: Option<f64>, d| Some(a.map_or(d, |a| a.min(d))));
+        prop_assert_eq!(tree.nearest(&point).map(|(_, d)| d), nearest);
+    }
+}